@@ -40,7 +40,7 @@ use prost::Message;
 use reqwest::header::CONTENT_TYPE;
 use reqwest::Client;
 #[cfg(not(target_arch = "wasm32"))]
-use reqwest::Certificate;
+use reqwest::{Certificate, Proxy};
 
 use crate::error::LdkServerError;
 use crate::error::LdkServerErrorCode::{
@@ -49,6 +49,27 @@ use crate::error::LdkServerErrorCode::{
 
 const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
 
+/// Builds the HTTP client, trusting `server_cert_pem` if given and routing through a SOCKS5
+/// proxy at `proxy_address` if given.
+#[cfg(not(target_arch = "wasm32"))]
+fn build_http_client(
+	server_cert_pem: Option<&[u8]>, proxy_address: Option<&str>,
+) -> Result<Client, String> {
+	let mut builder = Client::builder();
+	if let Some(pem) = server_cert_pem {
+		let cert = Certificate::from_pem(pem)
+			.map_err(|e| format!("Failed to parse server certificate: {e}"))?;
+		builder = builder.add_root_certificate(cert);
+	}
+	if let Some(address) = proxy_address {
+		// `socks5h` lets the proxy resolve host names, which `.onion` addresses require.
+		let proxy = Proxy::all(format!("socks5h://{address}"))
+			.map_err(|e| format!("Invalid proxy address: {e}"))?;
+		builder = builder.proxy(proxy);
+	}
+	builder.build().map_err(|e| format!("Failed to build HTTP client: {e}"))
+}
+
 /// Client to access a hosted instance of LDK Server.
 ///
 /// The client requires the server's TLS certificate to be provided for verification.
@@ -56,6 +77,7 @@ const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
 /// server generates it on first startup.
 #[derive(Clone)]
 pub struct LdkServerClient {
+	scheme: &'static str,
 	base_url: String,
 	client: Client,
 	api_key: String,
@@ -73,15 +95,42 @@ impl LdkServerClient {
 	/// handles TLS verification.
 	#[cfg(not(target_arch = "wasm32"))]
 	pub fn new(base_url: String, api_key: String, server_cert_pem: &[u8]) -> Result<Self, String> {
-		let cert = Certificate::from_pem(server_cert_pem)
-			.map_err(|e| format!("Failed to parse server certificate: {e}"))?;
+		let client = build_http_client(Some(server_cert_pem), None)?;
+		Ok(Self { scheme: "https", base_url, client, api_key })
+	}
 
-		let client = Client::builder()
-			.add_root_certificate(cert)
-			.build()
-			.map_err(|e| format!("Failed to build HTTP client: {e}"))?;
+	/// Constructs a [`LdkServerClient`] that sends every request through the SOCKS5 proxy at
+	/// `proxy_address` (`host:port`), e.g. a local Tor daemon at `127.0.0.1:9050`.
+	///
+	/// Host names are resolved by the proxy, so `base_url` may be an `.onion` address.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn new_with_proxy(
+		base_url: String, api_key: String, server_cert_pem: &[u8], proxy_address: &str,
+	) -> Result<Self, String> {
+		let client = build_http_client(Some(server_cert_pem), Some(proxy_address))?;
+		Ok(Self { scheme: "https", base_url, client, api_key })
+	}
 
-		Ok(Self { base_url, client, api_key })
+	/// Constructs a [`LdkServerClient`] that talks plain HTTP without TLS.
+	///
+	/// Requests and the API key authentication travel unencrypted, so this is only meant for
+	/// local development setups, e.g. a regtest node on `localhost`.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn new_insecure(base_url: String, api_key: String) -> Result<Self, String> {
+		let client = build_http_client(None, None)?;
+		Ok(Self { scheme: "http", base_url, client, api_key })
+	}
+
+	/// Constructs a [`LdkServerClient`] that talks plain HTTP through the SOCKS5 proxy at
+	/// `proxy_address`.
+	///
+	/// Meant for `.onion` services, where Tor already encrypts and authenticates the connection.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn new_insecure_with_proxy(
+		base_url: String, api_key: String, proxy_address: &str,
+	) -> Result<Self, String> {
+		let client = build_http_client(None, Some(proxy_address))?;
+		Ok(Self { scheme: "http", base_url, client, api_key })
 	}
 
 	/// Constructs a [`LdkServerClient`] for WASM targets.
@@ -93,7 +142,7 @@ impl LdkServerClient {
 			.build()
 			.map_err(|e| format!("Failed to build HTTP client: {e}"))?;
 
-		Ok(Self { base_url, client, api_key })
+		Ok(Self { scheme: "https", base_url, client, api_key })
 	}
 
 	/// Computes the HMAC-SHA256 authentication header value.
@@ -122,7 +171,7 @@ impl LdkServerClient {
 	pub async fn get_node_info(
 		&self, request: GetNodeInfoRequest,
 	) -> Result<GetNodeInfoResponse, LdkServerError> {
-		let url = format!("https://{}/{GET_NODE_INFO_PATH}", self.base_url);
+		let url = format!("{}://{}/{GET_NODE_INFO_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -131,7 +180,7 @@ impl LdkServerClient {
 	pub async fn get_balances(
 		&self, request: GetBalancesRequest,
 	) -> Result<GetBalancesResponse, LdkServerError> {
-		let url = format!("https://{}/{GET_BALANCES_PATH}", self.base_url);
+		let url = format!("{}://{}/{GET_BALANCES_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -140,7 +189,7 @@ impl LdkServerClient {
 	pub async fn onchain_receive(
 		&self, request: OnchainReceiveRequest,
 	) -> Result<OnchainReceiveResponse, LdkServerError> {
-		let url = format!("https://{}/{ONCHAIN_RECEIVE_PATH}", self.base_url);
+		let url = format!("{}://{}/{ONCHAIN_RECEIVE_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -149,7 +198,7 @@ impl LdkServerClient {
 	pub async fn onchain_send(
 		&self, request: OnchainSendRequest,
 	) -> Result<OnchainSendResponse, LdkServerError> {
-		let url = format!("https://{}/{ONCHAIN_SEND_PATH}", self.base_url);
+		let url = format!("{}://{}/{ONCHAIN_SEND_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -158,7 +207,7 @@ impl LdkServerClient {
 	pub async fn bolt11_receive(
 		&self, request: Bolt11ReceiveRequest,
 	) -> Result<Bolt11ReceiveResponse, LdkServerError> {
-		let url = format!("https://{}/{BOLT11_RECEIVE_PATH}", self.base_url);
+		let url = format!("{}://{}/{BOLT11_RECEIVE_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -167,7 +216,7 @@ impl LdkServerClient {
 	pub async fn bolt11_send(
 		&self, request: Bolt11SendRequest,
 	) -> Result<Bolt11SendResponse, LdkServerError> {
-		let url = format!("https://{}/{BOLT11_SEND_PATH}", self.base_url);
+		let url = format!("{}://{}/{BOLT11_SEND_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -176,7 +225,7 @@ impl LdkServerClient {
 	pub async fn bolt12_receive(
 		&self, request: Bolt12ReceiveRequest,
 	) -> Result<Bolt12ReceiveResponse, LdkServerError> {
-		let url = format!("https://{}/{BOLT12_RECEIVE_PATH}", self.base_url);
+		let url = format!("{}://{}/{BOLT12_RECEIVE_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -185,7 +234,7 @@ impl LdkServerClient {
 	pub async fn bolt12_send(
 		&self, request: Bolt12SendRequest,
 	) -> Result<Bolt12SendResponse, LdkServerError> {
-		let url = format!("https://{}/{BOLT12_SEND_PATH}", self.base_url);
+		let url = format!("{}://{}/{BOLT12_SEND_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -194,7 +243,7 @@ impl LdkServerClient {
 	pub async fn open_channel(
 		&self, request: OpenChannelRequest,
 	) -> Result<OpenChannelResponse, LdkServerError> {
-		let url = format!("https://{}/{OPEN_CHANNEL_PATH}", self.base_url);
+		let url = format!("{}://{}/{OPEN_CHANNEL_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -203,7 +252,7 @@ impl LdkServerClient {
 	pub async fn splice_in(
 		&self, request: SpliceInRequest,
 	) -> Result<SpliceInResponse, LdkServerError> {
-		let url = format!("https://{}/{SPLICE_IN_PATH}", self.base_url);
+		let url = format!("{}://{}/{SPLICE_IN_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -212,7 +261,7 @@ impl LdkServerClient {
 	pub async fn splice_out(
 		&self, request: SpliceOutRequest,
 	) -> Result<SpliceOutResponse, LdkServerError> {
-		let url = format!("https://{}/{SPLICE_OUT_PATH}", self.base_url);
+		let url = format!("{}://{}/{SPLICE_OUT_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -221,7 +270,7 @@ impl LdkServerClient {
 	pub async fn close_channel(
 		&self, request: CloseChannelRequest,
 	) -> Result<CloseChannelResponse, LdkServerError> {
-		let url = format!("https://{}/{CLOSE_CHANNEL_PATH}", self.base_url);
+		let url = format!("{}://{}/{CLOSE_CHANNEL_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -230,7 +279,7 @@ impl LdkServerClient {
 	pub async fn force_close_channel(
 		&self, request: ForceCloseChannelRequest,
 	) -> Result<ForceCloseChannelResponse, LdkServerError> {
-		let url = format!("https://{}/{FORCE_CLOSE_CHANNEL_PATH}", self.base_url);
+		let url = format!("{}://{}/{FORCE_CLOSE_CHANNEL_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -239,7 +288,7 @@ impl LdkServerClient {
 	pub async fn list_channels(
 		&self, request: ListChannelsRequest,
 	) -> Result<ListChannelsResponse, LdkServerError> {
-		let url = format!("https://{}/{LIST_CHANNELS_PATH}", self.base_url);
+		let url = format!("{}://{}/{LIST_CHANNELS_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -248,7 +297,7 @@ impl LdkServerClient {
 	pub async fn list_payments(
 		&self, request: ListPaymentsRequest,
 	) -> Result<ListPaymentsResponse, LdkServerError> {
-		let url = format!("https://{}/{LIST_PAYMENTS_PATH}", self.base_url);
+		let url = format!("{}://{}/{LIST_PAYMENTS_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -257,7 +306,7 @@ impl LdkServerClient {
 	pub async fn update_channel_config(
 		&self, request: UpdateChannelConfigRequest,
 	) -> Result<UpdateChannelConfigResponse, LdkServerError> {
-		let url = format!("https://{}/{UPDATE_CHANNEL_CONFIG_PATH}", self.base_url);
+		let url = format!("{}://{}/{UPDATE_CHANNEL_CONFIG_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -266,7 +315,7 @@ impl LdkServerClient {
 	pub async fn get_payment_details(
 		&self, request: GetPaymentDetailsRequest,
 	) -> Result<GetPaymentDetailsResponse, LdkServerError> {
-		let url = format!("https://{}/{GET_PAYMENT_DETAILS_PATH}", self.base_url);
+		let url = format!("{}://{}/{GET_PAYMENT_DETAILS_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -275,7 +324,7 @@ impl LdkServerClient {
 	pub async fn list_forwarded_payments(
 		&self, request: ListForwardedPaymentsRequest,
 	) -> Result<ListForwardedPaymentsResponse, LdkServerError> {
-		let url = format!("https://{}/{LIST_FORWARDED_PAYMENTS_PATH}", self.base_url);
+		let url = format!("{}://{}/{LIST_FORWARDED_PAYMENTS_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -284,7 +333,7 @@ impl LdkServerClient {
 	pub async fn connect_peer(
 		&self, request: ConnectPeerRequest,
 	) -> Result<ConnectPeerResponse, LdkServerError> {
-		let url = format!("https://{}/{CONNECT_PEER_PATH}", self.base_url);
+		let url = format!("{}://{}/{CONNECT_PEER_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -293,7 +342,7 @@ impl LdkServerClient {
 	pub async fn list_peers(
 		&self, request: ListPeersRequest,
 	) -> Result<ListPeersResponse, LdkServerError> {
-		let url = format!("https://{}/{LIST_PEERS_PATH}", self.base_url);
+		let url = format!("{}://{}/{LIST_PEERS_PATH}", self.scheme, self.base_url);
 		self.post_request(&request, &url).await
 	}
 
@@ -311,7 +360,10 @@ impl LdkServerClient {
 			.send()
 			.await
 			.map_err(|e| {
-				LdkServerError::new(InternalError, format!("HTTP request failed: {}", e))
+				LdkServerError::new(
+					InternalError,
+					format!("HTTP request failed: {}", error_with_sources(&e)),
+				)
 			})?;
 
 		let status = response_raw.status();
@@ -346,3 +398,15 @@ impl LdkServerClient {
 		}
 	}
 }
+
+/// Formats an error along with its chain of sources, which for transport errors hold the actual
+/// cause (e.g. a refused connection or an untrusted certificate).
+fn error_with_sources(error: &dyn std::error::Error) -> String {
+	let mut message = error.to_string();
+	let mut source = error.source();
+	while let Some(cause) = source {
+		message.push_str(&format!(": {}", cause));
+		source = cause.source();
+	}
+	message
+}
@@ -0,0 +1,182 @@
+//! Lightweight classification of on-chain addresses.
+//!
+//! Only the prefix and length are used to tell address types apart. Checksums are not verified;
+//! the server rejects invalid addresses when sending.
+
+use crate::network::Network;
+
+/// Output type of an on-chain address.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AddressType {
+    P2pkh,
+    P2sh,
+    P2wpkh,
+    P2wsh,
+    P2tr,
+}
+
+impl AddressType {
+    /// Detect the address type, returning `None` if the address is not recognized.
+    pub fn detect(address: &str) -> Option<Self> {
+        let address = address.trim();
+        let lower = address.to_ascii_lowercase();
+
+        for hrp in ["bcrt1", "bc1", "tb1"] {
+            if let Some(data) = lower.strip_prefix(hrp) {
+                // Witness version, program and the 6 character checksum
+                return match (data.chars().next()?, data.len()) {
+                    ('q', 39) => Some(AddressType::P2wpkh),
+                    ('q', 59) => Some(AddressType::P2wsh),
+                    ('p', 59) => Some(AddressType::P2tr),
+                    _ => None,
+                };
+            }
+        }
+
+        if !(26..=35).contains(&address.len()) {
+            return None;
+        }
+        match address.chars().next()? {
+            '1' | 'm' | 'n' => Some(AddressType::P2pkh),
+            '3' | '2' => Some(AddressType::P2sh),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AddressType::P2pkh => "Legacy (P2PKH)",
+            AddressType::P2sh => "Script hash (P2SH)",
+            AddressType::P2wpkh => "SegWit (P2WPKH)",
+            AddressType::P2wsh => "SegWit script (P2WSH)",
+            AddressType::P2tr => "Taproot (P2TR)",
+        }
+    }
+
+    pub fn short_label(&self) -> &'static str {
+        match self {
+            AddressType::P2pkh => "P2PKH",
+            AddressType::P2sh => "P2SH",
+            AddressType::P2wpkh => "P2WPKH",
+            AddressType::P2wsh => "P2WSH",
+            AddressType::P2tr => "P2TR",
+        }
+    }
+
+    /// Standard relay dust threshold for outputs of this type.
+    pub fn dust_threshold_sats(&self) -> u64 {
+        match self {
+            AddressType::P2pkh | AddressType::P2sh => 546,
+            AddressType::P2wpkh | AddressType::P2wsh | AddressType::P2tr => 330,
+        }
+    }
+}
+
+/// Dust threshold for sending to `address`, using the most conservative value when the address
+/// type is not recognized.
+pub fn dust_threshold_sats(address: &str) -> u64 {
+    AddressType::detect(address).map_or(546, |t| t.dust_threshold_sats())
+}
+
+/// Returns true if sending `amount_sats` to `address` creates an output below the dust threshold.
+pub fn is_dust(address: &str, amount_sats: u64) -> bool {
+    amount_sats < dust_threshold_sats(address)
+}
+
+/// Returns false if `address` is recognizably meant for a different network than `network`.
+/// Addresses of unrecognized types are not rejected.
+///
+/// Testnet, testnet4 and signet share their address prefixes, and regtest reuses the testnet
+/// prefixes for legacy addresses, so those cannot be told apart.
+pub fn is_for_network(address: &str, network: Network) -> bool {
+    let address = address.trim();
+    let lower = address.to_ascii_lowercase();
+    // "bcrt1" also starts with "bc", so it has to be checked before "bc1"
+    if lower.starts_with("bcrt1") {
+        return network == Network::Regtest;
+    }
+    if lower.starts_with("tb1") {
+        return matches!(network, Network::Testnet | Network::Testnet4 | Network::Signet);
+    }
+    if lower.starts_with("bc1") {
+        return network == Network::Bitcoin;
+    }
+    if AddressType::detect(address).is_none() {
+        return true;
+    }
+    // Legacy addresses: 1 and 3 on mainnet, m, n and 2 everywhere else
+    address.starts_with(['1', '3']) == (network == Network::Bitcoin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P2PKH: &str = "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2";
+    const P2SH: &str = "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy";
+    const P2WPKH: &str = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+    const P2WSH: &str = "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3";
+    const P2TR: &str = "bc1p5d7rjq7g6rdk2yhzks9smlaqtedr4dekq08ge8ztwac72sfr9rusxg3297";
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(AddressType::detect(P2PKH), Some(AddressType::P2pkh));
+        assert_eq!(AddressType::detect(P2SH), Some(AddressType::P2sh));
+        assert_eq!(AddressType::detect(P2WPKH), Some(AddressType::P2wpkh));
+        assert_eq!(AddressType::detect(&P2WPKH.to_uppercase()), Some(AddressType::P2wpkh));
+        assert_eq!(AddressType::detect(P2WSH), Some(AddressType::P2wsh));
+        assert_eq!(AddressType::detect(P2TR), Some(AddressType::P2tr));
+        assert_eq!(
+            AddressType::detect("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"),
+            Some(AddressType::P2wpkh)
+        );
+        assert_eq!(
+            AddressType::detect("bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080"),
+            Some(AddressType::P2wpkh)
+        );
+        assert_eq!(AddressType::detect("mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn"), Some(AddressType::P2pkh));
+        assert_eq!(AddressType::detect(""), None);
+        assert_eq!(AddressType::detect("not an address"), None);
+        assert_eq!(AddressType::detect("bc1zw508d6qejxtdg4y5r3zarvaryvg6kdaj"), None);
+    }
+
+    #[test]
+    fn test_dust_threshold() {
+        assert_eq!(dust_threshold_sats(P2PKH), 546);
+        assert_eq!(dust_threshold_sats(P2SH), 546);
+        assert_eq!(dust_threshold_sats(P2WPKH), 330);
+        assert_eq!(dust_threshold_sats(P2TR), 330);
+        assert_eq!(dust_threshold_sats("unknown"), 546);
+
+        assert!(is_dust(P2WPKH, 329));
+        assert!(!is_dust(P2WPKH, 330));
+        assert!(is_dust(P2PKH, 545));
+        assert!(!is_dust(P2PKH, 546));
+    }
+    #[test]
+    fn test_is_for_network() {
+        assert!(is_for_network(P2WPKH, Network::Bitcoin));
+        assert!(is_for_network(P2PKH, Network::Bitcoin));
+        assert!(is_for_network(P2SH, Network::Bitcoin));
+        assert!(!is_for_network(P2TR, Network::Signet));
+        assert!(!is_for_network(P2PKH, Network::Regtest));
+
+        let testnet = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx";
+        assert!(is_for_network(testnet, Network::Testnet));
+        assert!(is_for_network(testnet, Network::Testnet4));
+        assert!(is_for_network(testnet, Network::Signet));
+        assert!(!is_for_network(testnet, Network::Regtest));
+        assert!(!is_for_network(testnet, Network::Bitcoin));
+
+        let regtest = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+        assert!(is_for_network(regtest, Network::Regtest));
+        assert!(!is_for_network(regtest, Network::Bitcoin));
+
+        let legacy_testnet = "mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn";
+        assert!(is_for_network(legacy_testnet, Network::Regtest));
+        assert!(is_for_network(legacy_testnet, Network::Signet));
+        assert!(!is_for_network(legacy_testnet, Network::Bitcoin));
+
+        assert!(is_for_network("not an address", Network::Bitcoin));
+    }
+}
@@ -0,0 +1,188 @@
+//! Parsing and formatting of user-entered amounts.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+const SATS_PER_BTC: u64 = 100_000_000;
+
+/// Whether amounts are displayed in BTC; see [`display_unit`].
+static DISPLAY_IN_BTC: AtomicBool = AtomicBool::new(false);
+
+/// Unit used to display and enter amounts.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum AmountUnit {
+    #[default]
+    Sats,
+    Btc,
+}
+
+impl AmountUnit {
+    pub const ALL: [AmountUnit; 2] = [AmountUnit::Sats, AmountUnit::Btc];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AmountUnit::Sats => "sats",
+            AmountUnit::Btc => "BTC",
+        }
+    }
+}
+
+/// Format a sat amount for display, e.g. `1,500,000 sats` or `0.01500000 BTC`.
+pub fn format_amount(sats: u64, unit: AmountUnit) -> String {
+    format!("{} {}", format_amount_value(sats, unit), unit.label())
+}
+
+/// Format a millisat amount for display. Sub-sat remainders are kept, as three more decimals.
+pub fn format_msat_amount(msat: u64, unit: AmountUnit) -> String {
+    let sats = msat / 1000;
+    let remainder = msat % 1000;
+    if remainder == 0 {
+        return format_amount(sats, unit);
+    }
+    let separator = match unit {
+        AmountUnit::Sats => ".",
+        AmountUnit::Btc => "",
+    };
+    format!("{}{}{:03} {}", format_amount_value(sats, unit), separator, remainder, unit.label())
+}
+
+/// Unit amounts are displayed in outside the forms with their own unit selector. Kept outside
+/// the app state so that the formatting helpers can read it from anywhere.
+pub fn display_unit() -> AmountUnit {
+    if DISPLAY_IN_BTC.load(Ordering::Relaxed) {
+        AmountUnit::Btc
+    } else {
+        AmountUnit::Sats
+    }
+}
+
+pub fn set_display_unit(unit: AmountUnit) {
+    DISPLAY_IN_BTC.store(unit == AmountUnit::Btc, Ordering::Relaxed);
+}
+
+/// Format a sat amount without the unit suffix, suitable for prefilling an input field.
+pub fn format_amount_input(sats: u64, unit: AmountUnit) -> String {
+    match unit {
+        AmountUnit::Sats => sats.to_string(),
+        AmountUnit::Btc => format_btc(sats),
+    }
+}
+
+fn format_amount_value(sats: u64, unit: AmountUnit) -> String {
+    match unit {
+        AmountUnit::Sats => group_digits(sats),
+        AmountUnit::Btc => format_btc(sats),
+    }
+}
+
+/// Format a number with thousands separators, e.g. `1,500,000`.
+pub fn group_digits(n: u64) -> String {
+    let s = n.to_string();
+    let mut result = String::new();
+    for (i, c) in s.chars().rev().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            result.insert(0, ',');
+        }
+        result.insert(0, c);
+    }
+    result
+}
+
+/// Format sats as BTC with exactly 8 decimal places.
+fn format_btc(sats: u64) -> String {
+    format!("{}.{:08}", sats / SATS_PER_BTC, sats % SATS_PER_BTC)
+}
+
+/// Parse a user-entered amount in the given unit into sats.
+pub fn parse_amount(input: &str, unit: AmountUnit) -> Result<u64, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Amount is required".to_string());
+    }
+    match unit {
+        AmountUnit::Sats => {
+            let digits = input.replace([',', '_'], "");
+            if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                return Err(format!("'{}' is not a whole number of sats", input));
+            }
+            digits.parse().map_err(|_| format!("'{}' is too large", input))
+        }
+        AmountUnit::Btc => parse_btc(input),
+    }
+}
+
+/// Convert a decimal BTC amount (e.g. `0.015`) to sats without going through floating point.
+pub fn parse_btc(input: &str) -> Result<u64, String> {
+    let invalid = || format!("'{}' is not a valid BTC amount (up to 8 decimals)", input);
+    let (whole, frac) = input.split_once('.').unwrap_or((input, ""));
+    if (whole.is_empty() && frac.is_empty())
+        || frac.len() > 8
+        || !whole.chars().chain(frac.chars()).all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    let whole: u64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+    let frac: u64 = format!("{:0<8}", frac).parse().map_err(|_| invalid())?;
+    whole.checked_mul(SATS_PER_BTC).and_then(|sats| sats.checked_add(frac)).ok_or_else(invalid)
+}
+
+/// Parse an optional msat amount as used by the lightning forms. Empty input yields `None`.
+pub fn parse_optional_msat(input: &str) -> Result<Option<u64>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    input.parse().map(Some).map_err(|_| format!("'{}' is not a whole number of msat", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("1500000", AmountUnit::Sats), Ok(1_500_000));
+        assert_eq!(parse_amount("1,500,000", AmountUnit::Sats), Ok(1_500_000));
+        assert!(parse_amount("0.015", AmountUnit::Sats).is_err());
+        assert!(parse_amount("", AmountUnit::Sats).is_err());
+
+        assert_eq!(parse_amount("0.015", AmountUnit::Btc), Ok(1_500_000));
+        assert_eq!(parse_amount("1", AmountUnit::Btc), Ok(100_000_000));
+        assert_eq!(parse_amount(".00000001", AmountUnit::Btc), Ok(1));
+        assert!(parse_amount("0.000000001", AmountUnit::Btc).is_err());
+        assert!(parse_amount("1e-3", AmountUnit::Btc).is_err());
+        assert!(parse_amount("-1", AmountUnit::Btc).is_err());
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(1_500_000, AmountUnit::Sats), "1,500,000 sats");
+        assert_eq!(format_amount(1_500_000, AmountUnit::Btc), "0.01500000 BTC");
+        assert_eq!(format_amount(2_100_000_000_000_000, AmountUnit::Btc), "21000000.00000000 BTC");
+        assert_eq!(format_amount_input(1, AmountUnit::Btc), "0.00000001");
+    }
+
+    #[test]
+    fn test_format_boundaries() {
+        assert_eq!(format_amount(0, AmountUnit::Sats), "0 sats");
+        assert_eq!(format_amount(0, AmountUnit::Btc), "0.00000000 BTC");
+        assert_eq!(format_msat_amount(0, AmountUnit::Btc), "0.00000000 BTC");
+
+        assert_eq!(format_msat_amount(1, AmountUnit::Sats), "0.001 sats");
+        assert_eq!(format_msat_amount(1, AmountUnit::Btc), "0.00000000001 BTC");
+        assert_eq!(format_msat_amount(1_500_250, AmountUnit::Sats), "1,500.250 sats");
+
+        let max_msat = 21_000_000 * SATS_PER_BTC * 1000;
+        assert_eq!(format_msat_amount(max_msat, AmountUnit::Sats), "2,100,000,000,000,000 sats");
+        assert_eq!(format_msat_amount(max_msat, AmountUnit::Btc), "21000000.00000000 BTC");
+    }
+
+    #[test]
+    fn test_parse_optional_msat() {
+        assert_eq!(parse_optional_msat(" "), Ok(None));
+        assert_eq!(parse_optional_msat("1000"), Ok(Some(1000)));
+        assert!(parse_optional_msat("1.5").is_err());
+    }
+}
@@ -8,255 +8,1746 @@ use tokio::runtime::Runtime;
 
 use ldk_server_client::client::LdkServerClient;
 use ldk_server_client::ldk_server_protos::api::{
-    Bolt11ReceiveRequest, Bolt11SendRequest, Bolt12ReceiveRequest, Bolt12SendRequest,
+    Bolt11ReceiveRequest, Bolt11ReceiveResponse, Bolt11SendRequest, Bolt11SendResponse,
+    Bolt12ReceiveRequest, Bolt12ReceiveResponse, Bolt12SendRequest, Bolt12SendResponse,
     CloseChannelRequest, ConnectPeerRequest, ForceCloseChannelRequest, GetBalancesRequest,
-    GetNodeInfoRequest, ListChannelsRequest, ListPaymentsRequest, OnchainReceiveRequest,
-    OnchainSendRequest, OpenChannelRequest, SpliceInRequest, SpliceOutRequest,
-    UpdateChannelConfigRequest,
+    GetBalancesResponse, GetNodeInfoRequest, GetNodeInfoResponse, ListChannelsRequest,
+    ListPaymentsRequest, ListPaymentsResponse, OnchainReceiveRequest, OnchainReceiveResponse,
+    OnchainSendRequest, OnchainSendResponse, OpenChannelRequest, OpenChannelResponse,
+    SpliceInRequest, SpliceOutRequest, SpliceOutResponse, UpdateChannelConfigRequest,
 };
 use ldk_server_client::ldk_server_protos::types::{
     bolt11_invoice_description, Bolt11InvoiceDescription, ChannelConfig,
 };
 
 #[cfg(not(target_arch = "wasm32"))]
-use crate::config;
+use crate::chain_check;
 #[cfg(not(target_arch = "wasm32"))]
-use crate::state::ChainSourceForm;
-use crate::state::{ActiveTab, AppState, ConnectionStatus, StatusMessage};
-use crate::task;
+use crate::cli::EnvOverrides;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::config::{GuiConfig, SectionUpdate};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::diff;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::secrets;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::state::{
+    ChainSourceForm, Lsps2ServiceForm, NewConfigStep, NodeSettingsForm, PendingConfigSave,
+    ShutdownState, TlsCertSource,
+};
+use crate::amount::{self, AmountUnit};
+use crate::balance_changes::BalanceChanges;
+use crate::cli::StartupOptions;
+use crate::config::{self, ChainSourceConfig, DroppedFileKind};
+use crate::errors::{self, ErrorKind, TaskError};
+use crate::esplora::{self, TxWatch};
+use crate::export;
+use crate::fees;
+use crate::fiat;
+use crate::liquidity;
+use crate::lnurl;
+use crate::logs::LogBuffer;
+use crate::network::Network;
+use crate::state::{
+    stale_wallet_syncs, ActiveTab, AppState, AsyncTasks, BalanceRefresh, Cached, ConnectionField,
+    ConnectionStatus, GeneratedAddress, NodeSession, StatusMessage, SyncAll, TaskKind,
+};
+use crate::storage;
+use crate::task::{self, Admission, MaybeSend, RequestLimiter, RetryPolicy, TaskOutput, TaskSpawner};
 use crate::ui;
 
+/// Interval between writes of everything to localStorage on wasm.
+#[cfg(target_arch = "wasm32")]
+const AUTO_SAVE_INTERVAL_SECS: u64 = 5;
+
+/// Tabs listed in the navigation, with their labels.
+const NAV_TABS: [(ActiveTab, &str); 8] = [
+    (ActiveTab::Overview, "Overview"),
+    (ActiveTab::NodeInfo, "Node Info"),
+    (ActiveTab::Balances, "Balances"),
+    (ActiveTab::Channels, "Channels"),
+    (ActiveTab::Payments, "Payment History"),
+    (ActiveTab::Lightning, "Lightning"),
+    (ActiveTab::Onchain, "On-chain"),
+    (ActiveTab::Logs, "Logs"),
+];
+
+/// Interval between balance refreshes after an on-chain send.
+const BALANCE_REFRESH_INTERVAL_SECS: u64 = 20;
+
+/// How long balances keep refreshing after an on-chain send if the total does not change.
+const BALANCE_REFRESH_DURATION_SECS: u64 = 180;
+
+/// Consecutive failed requests or health checks after which the client is reconnected.
+const HEALTH_CHECK_MAX_FAILURES: u32 = 3;
+
+/// Delay before the first reconnection attempt, doubled after each failed attempt.
+const RECONNECT_BASE_DELAY_SECS: u64 = 2;
+
+/// Upper bound on the delay between reconnection attempts.
+const RECONNECT_MAX_DELAY_SECS: u64 = 300;
+
+/// A payments page can hold thousands of entries, so loading one may take this many times the
+/// request timeout.
+const PAYMENTS_PAGE_TIMEOUT_FACTOR: u32 = 3;
+
+/// Longest time closing the window waits for operations that change a node to finish.
+#[cfg(not(target_arch = "wasm32"))]
+pub const SHUTDOWN_GRACE_PERIOD_SECS: u64 = 5;
+
 pub struct LdkServerApp {
     pub state: AppState,
-    #[cfg(not(target_arch = "wasm32"))]
-    pub rt: Runtime,
+    /// Runs spawned tasks: the tokio runtime on native, the browser's event loop on WASM
+    spawner: Box<dyn TaskSpawner>,
+    /// Shared by every spawned task, limiting how many run at once
+    pub limiter: RequestLimiter,
+    /// Repainted by tasks when they complete
+    ctx: egui::Context,
+    /// Recent log events, shown in the Logs tab
+    pub logs: LogBuffer,
+    /// When everything was last written to localStorage, in seconds since the epoch
+    #[cfg(target_arch = "wasm32")]
+    saved_at: u64,
+    /// Set once localStorage was cleared, after which nothing is written to it again
+    #[cfg(target_arch = "wasm32")]
+    storage_cleared: bool,
 }
 
 impl LdkServerApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(
+        cc: &eframe::CreationContext<'_>, options: StartupOptions, logs: LogBuffer,
+    ) -> Self {
+        let mut state = AppState::default();
+
+        if let Some(history) = storage::load(cc, storage::ADDRESS_HISTORY_KEY) {
+            state.address_history = history;
+        }
+        if let Some(book) = storage::load(cc, storage::ADDRESS_BOOK_KEY) {
+            state.address_book = book;
+        }
+        if let Some(unit) = storage::load(cc, storage::AMOUNT_UNIT_KEY) {
+            state.onchain_unit = unit;
+        }
+        // Applied before the config file so that a found config still takes precedence
+        if let Some(settings) = storage::load(cc, storage::SETTINGS_KEY) {
+            state.apply_persisted_settings(settings);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(profiles) = storage::load(cc, storage::CONNECTION_PROFILES_KEY) {
+            state.connection_profiles = profiles;
+        }
+
+        // Load the config given on the command line, or search the default locations
         #[cfg(not(target_arch = "wasm32"))]
-        let state = {
-            let mut state = AppState::default();
-            // Try to load config from file and populate connection settings
-            if let Some(gui_config) = config::find_and_load_config() {
-                state.server_url = gui_config.server_url;
-                state.api_key = gui_config.api_key;
-                state.tls_cert_path = gui_config.tls_cert_path;
-                state.network = gui_config.network;
-                state.forms.chain_source = ChainSourceForm::from_config(&gui_config.chain_source);
-                state.chain_source = gui_config.chain_source;
-                state.status_message =
-                    Some(StatusMessage::success("Config loaded from ldk-server-config.toml"));
-            }
-            state
+        let loaded = match &options.config_path {
+            Some(path) => match config::load_config(path) {
+                Ok(gui_config) => Some((gui_config, path.display().to_string())),
+                Err(e) => {
+                    state.set_config_load_error(path.display().to_string(), e);
+                    None
+                }
+            },
+            None => config::find_and_load_config()
+                .map(|(gui_config, path)| (gui_config, path.display().to_string())),
         };
 
-        #[cfg(target_arch = "wasm32")]
-        let state = AppState::default();
+        if let Some(tab) = options.tab {
+            state.active_tab = tab;
+        }
 
-        Self {
+        let limiter = RequestLimiter::new(state.max_concurrent_requests);
+        let mut app = Self {
             state,
             #[cfg(not(target_arch = "wasm32"))]
-            rt: Runtime::new().expect("Failed to create tokio runtime"),
+            spawner: Box::new(Runtime::new().expect("Failed to create tokio runtime")),
+            #[cfg(target_arch = "wasm32")]
+            spawner: Box::new(task::LocalSpawner),
+            limiter,
+            ctx: cc.egui_ctx.clone(),
+            logs,
+            #[cfg(target_arch = "wasm32")]
+            saved_at: ui::unix_timestamp_now(),
+            #[cfg(target_arch = "wasm32")]
+            storage_cleared: false,
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some((gui_config, source)) = loaded {
+            app.state.set_config_loaded(&source, &gui_config.warnings);
+            app.load_gui_config(gui_config, source);
+        }
+
+        // The environment takes precedence over saved settings and the config file
+        #[cfg(not(target_arch = "wasm32"))]
+        let env_connect = {
+            let env = EnvOverrides::from_env();
+            app.state.env_overrides = env.apply(&mut app.state.session);
+            env.connect
+        };
+        #[cfg(target_arch = "wasm32")]
+        let env_connect = false;
+
+        if options.connect || env_connect {
+            app.connect();
         }
+        app
     }
 
+    /// Build a client and probe it with `get_node_info`; the connection is only marked as
+    /// Connected once the probe succeeds.
     pub fn connect(&mut self) {
-        let url = self.state.server_url.trim().to_string();
-        let api_key = self.state.api_key.clone();
+        if self.state.session.tasks.is_pending(TaskKind::Connect) {
+            return;
+        }
+        self.state.connection_field_errors = self.validate_connection_form();
+        if let Some((field, _)) = self.state.connection_field_errors.first() {
+            self.state.focus_connection_field = Some(*field);
+            return;
+        }
 
+        // Re-read the certificate so a file replaced since it was last shown is noticed
         #[cfg(not(target_arch = "wasm32"))]
-        {
-            let cert_path = self.state.tls_cert_path.trim().to_string();
-
-            if url.is_empty() || api_key.is_empty() || cert_path.is_empty() {
-                self.state.status_message =
-                    Some(StatusMessage::error("Please fill in all connection fields"));
+        if let Some(pinned) = self.state.session.pinned_cert_fingerprint.clone() {
+            self.state.session.tls_cert_fingerprint = None;
+            let current = self.tls_cert_fingerprint().and_then(|f| f.as_ref().ok().cloned());
+            if let Some(fingerprint) = current.filter(|f| *f != pinned) {
+                self.state.cert_pin_mismatch = Some(fingerprint);
                 return;
             }
+        }
 
-            let cert_data = match std::fs::read(&cert_path) {
-                Ok(data) => data,
-                Err(e) => {
-                    self.state.status_message =
-                        Some(StatusMessage::error(format!("Failed to read TLS cert: {}", e)));
-                    return;
+        match self.build_client() {
+            Ok(client) => {
+                self.state.session.connection_status = ConnectionStatus::Connecting;
+                let client = Arc::new(client);
+                self.state.session.tasks.insert(TaskKind::Connect, self.spawn_task(async move {
+                    let node_info = client.get_node_info(GetNodeInfoRequest {}).await?;
+                    Ok::<_, TaskError>((client, node_info))
+                }));
+            }
+            Err(e) => {
+                self.state.session.connection_status = ConnectionStatus::Error(e.clone());
+                self.state.status_message = Some(StatusMessage::error(e));
+            }
+        }
+    }
+
+    /// Abandon a pending connection attempt.
+    pub fn cancel_connect(&mut self) {
+        self.state.session.tasks.remove(TaskKind::Connect);
+        self.state.session.connection_status = ConnectionStatus::Disconnected;
+    }
+
+    /// Check the connection form for missing or invalid fields.
+    fn validate_connection_form(&self) -> Vec<(ConnectionField, String)> {
+        let mut errors = Vec::new();
+        if self.state.session.server_url.trim().is_empty() {
+            errors.push((ConnectionField::ServerUrl, "Server URL is required".to_string()));
+        }
+        if self.state.session.api_key.is_empty() {
+            errors.push((ConnectionField::ApiKey, "API key is required".to_string()));
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let proxy = self.state.session.proxy.trim();
+            if !proxy.is_empty() {
+                if let Err(e) = config::validate_host_port(proxy) {
+                    errors.push((ConnectionField::Proxy, format!("{} (expected host:port)", e)));
                 }
-            };
+            } else if config::is_onion_url(&self.state.session.server_url) {
+                errors.push((
+                    ConnectionField::Proxy,
+                    "Onion addresses need a Tor proxy, e.g. 127.0.0.1:9050".to_string(),
+                ));
+            }
+        }
 
-            match LdkServerClient::new(url.clone(), api_key, &cert_data) {
-                Ok(client) => {
-                    self.state.client = Some(Arc::new(client));
-                    self.state.connection_status = ConnectionStatus::Connected;
-                    self.state.status_message = Some(StatusMessage::success("Connected"));
-                    self.fetch_node_info();
-                    self.fetch_balances();
-                    self.fetch_channels();
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.state.session.insecure_http {
+            if !config::insecure_http_allowed(&self.state.session.server_url) {
+                errors.push((
+                    ConnectionField::ServerUrl,
+                    "Insecure HTTP is only allowed for localhost and onion addresses".to_string(),
+                ));
+            }
+            return errors;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        match self.state.session.tls_cert_source {
+            TlsCertSource::File => {
+                if self.state.session.tls_cert_path.trim().is_empty() {
+                    errors.push((ConnectionField::TlsCert, "Certificate path is required".to_string()));
                 }
-                Err(e) => {
-                    self.state.connection_status = ConnectionStatus::Error(e.clone());
-                    self.state.status_message = Some(StatusMessage::error(e));
+            }
+            TlsCertSource::Pem => {
+                let pem = self.state.session.tls_cert_pem.trim();
+                if pem.is_empty() {
+                    errors.push((ConnectionField::TlsCert, "Certificate is required".to_string()));
+                } else if let Err(e) = config::validate_pem_certificate(pem) {
+                    errors.push((ConnectionField::TlsCert, e));
                 }
             }
         }
 
-        #[cfg(target_arch = "wasm32")]
-        {
-            if url.is_empty() || api_key.is_empty() {
+        errors
+    }
+
+    /// Read the certificate from the selected source.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read_tls_cert(&self) -> Result<Vec<u8>, String> {
+        match self.state.session.tls_cert_source {
+            TlsCertSource::File => {
+                let cert_path = self.state.session.tls_cert_path.trim();
+                if cert_path.is_empty() {
+                    return Err("Please fill in all connection fields".to_string());
+                }
+                std::fs::read(cert_path).map_err(|e| format!("Failed to read TLS cert: {}", e))
+            }
+            TlsCertSource::Pem => {
+                let pem = self.state.session.tls_cert_pem.trim();
+                config::validate_pem_certificate(pem)
+                    .map_err(|e| format!("Invalid TLS cert: {}", e))?;
+                Ok(pem.as_bytes().to_vec())
+            }
+        }
+    }
+
+    /// Fingerprint of the certificate the connection fields point at, re-read only when the
+    /// path or pasted PEM changes. `None` while no certificate is entered.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn tls_cert_fingerprint(&mut self) -> Option<&Result<String, String>> {
+        if self.state.session.insecure_http {
+            return None;
+        }
+        let source = match self.state.session.tls_cert_source {
+            TlsCertSource::File => self.state.session.tls_cert_path.trim().to_string(),
+            TlsCertSource::Pem => self.state.session.tls_cert_pem.trim().to_string(),
+        };
+        if source.is_empty() {
+            return None;
+        }
+        if self.state.session.tls_cert_fingerprint.as_ref().map_or(true, |(s, _)| *s != source) {
+            let fingerprint =
+                self.read_tls_cert().and_then(|data| config::certificate_fingerprint(&data));
+            self.state.session.tls_cert_fingerprint = Some((source, fingerprint));
+        }
+        self.state.session.tls_cert_fingerprint.as_ref().map(|(_, fingerprint)| fingerprint)
+    }
+
+    /// Prepare writing `updates` to the config file at `path`, showing the resulting diff for
+    /// confirmation before anything is written.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn preview_config_save(&mut self, path: String, updates: Vec<SectionUpdate>) {
+        let result = config::read_config_file(&path).and_then(|current| {
+            for update in &updates {
+                update.validate()?;
+            }
+            let contents = config::save_sections(&current, &updates)?;
+            Ok((diff::unified_diff(&current, &contents), contents))
+        });
+
+        match result {
+            Ok((diff, _)) if diff.is_empty() => {
+                self.state.status_message = Some(StatusMessage::success("No changes to save"));
+            }
+            Ok((diff, contents)) => {
+                self.state.pending_config_save =
+                    Some(PendingConfigSave { path, contents, diff, updates });
+            }
+            Err(e) => {
+                self.state.status_message =
+                    Some(StatusMessage::error(format!("Failed to save: {}", e)));
+            }
+        }
+    }
+
+    /// Check that the chain source being edited can be reached before it is saved.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn check_chain_source(&mut self) {
+        let chain_source = self.state.forms.chain_source.to_config();
+        let kind = TaskKind::ChainSourceCheck;
+        let admission =
+            self.state.session.tasks.request(kind, chain_source.clone(), kind.coalesce_policy());
+        if admission != Admission::Start {
+            return;
+        }
+        self.state.chain_source_check_result = None;
+        let handle = self.spawn_task(chain_check::check_chain_source(chain_source));
+        self.state.session.tasks.insert(TaskKind::ChainSourceCheck, handle);
+    }
+
+    /// Write the config change confirmed in the diff preview.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn confirm_config_save(&mut self) {
+        let Some(pending) = self.state.pending_config_save.take() else {
+            return;
+        };
+        match config::write_config_file(&pending.path, &pending.contents) {
+            Ok(()) => {
+                for update in pending.updates {
+                    match update {
+                        SectionUpdate::ChainSource(chain_source) => {
+                            self.state.session.chain_source = chain_source;
+                        }
+                        SectionUpdate::Lsps2Service(settings) => {
+                            self.state.session.lsps2_service = settings;
+                        }
+                        // Take effect once the server restarts
+                        SectionUpdate::Node(_) | SectionUpdate::StorageDir(_) => {}
+                    }
+                }
                 self.state.status_message =
-                    Some(StatusMessage::error("Please fill in server URL and API key"));
+                    Some(StatusMessage::success(format!("Config saved to {}", pending.path)));
+                self.state.session.config_file_path = Some(pending.path);
+            }
+            Err(e) => {
+                self.state.status_message =
+                    Some(StatusMessage::error(format!("Failed to save: {}", e)));
+            }
+        }
+    }
+
+    /// Load the config file at `path`, picked with Load Config or dropped onto the window.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_config_file(&mut self, path: &std::path::Path) {
+        let source = path.display().to_string();
+        match config::load_config(path) {
+            Ok(gui_config) => {
+                self.state.set_config_loaded(&source, &gui_config.warnings);
+                self.load_gui_config(gui_config, source);
+            }
+            Err(e) => self.state.set_config_load_error(source, e),
+        }
+    }
+
+    /// Fill the connection settings and config editors from a config file loaded from `path`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_gui_config(&mut self, gui_config: GuiConfig, path: String) {
+        self.state.session.server_url = gui_config.server_url;
+        self.state.session.api_key = gui_config.api_key;
+        self.state.session.tls_cert_path = gui_config.tls_cert_path;
+        self.state.session.tls_cert_source = TlsCertSource::File;
+        self.state.session.network = gui_config.network;
+        self.state.session.config_file_path = Some(path);
+        self.state.forms.chain_source = ChainSourceForm::from_config(&gui_config.chain_source);
+        self.state.forms.node_settings =
+            NodeSettingsForm::from_settings(&gui_config.node_settings, &gui_config.storage_dir);
+        self.state.forms.lsps2_service =
+            Lsps2ServiceForm::from_settings(gui_config.lsps2_service.as_ref());
+        self.state.session.lsps2_service = gui_config.lsps2_service;
+        self.state.session.chain_source = gui_config.chain_source;
+        self.state.session.node_settings = Some(gui_config.node_settings);
+    }
+
+    /// Check the current step of the new-config wizard and move on to the next one, or create
+    /// the config file after the last step.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn advance_new_config_wizard(&mut self) {
+        let Some(wizard) = &mut self.state.new_config_wizard else {
+            return;
+        };
+        let result = match wizard.step {
+            NewConfigStep::Network => {
+                if Network::from_config_name(&wizard.network).is_some() {
+                    Ok(())
+                } else {
+                    Err("Select a network".to_string())
+                }
+            }
+            NewConfigStep::Storage => match wizard.storage_dir.trim() {
+                "" => Err("Storage directory is required".to_string()),
+                dir => config::check_dir_writable(dir),
+            },
+            NewConfigStep::RestAddress => {
+                config::validate_host_port(wizard.rest_service_address.trim())
+                    .map_err(|e| format!("REST service address: {}", e))
+            }
+            NewConfigStep::ChainSource => wizard.chain_source.to_config().validate(),
+            NewConfigStep::Location => {
+                self.create_new_config();
                 return;
             }
+        };
+        match (result, wizard.step.next()) {
+            (Ok(()), Some(next)) => {
+                wizard.step = next;
+                wizard.error = None;
+            }
+            (Ok(()), None) => {}
+            (Err(e), _) => wizard.error = Some(e),
+        }
+    }
+
+    /// Write the config collected by the new-config wizard and load it.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn create_new_config(&mut self) {
+        let Some(wizard) = &mut self.state.new_config_wizard else {
+            return;
+        };
+        let path = wizard.path.trim().to_string();
+        let result = if path.is_empty() {
+            Err("Config file path is required".to_string())
+        } else {
+            config::new_config(
+                &wizard.node_settings(),
+                wizard.storage_dir.trim(),
+                &wizard.chain_source.to_config(),
+            )
+            .and_then(|contents| config::write_config_file(&path, &contents))
+            .and_then(|()| config::load_config(&path))
+        };
+        match result {
+            Ok(gui_config) => {
+                self.state.new_config_wizard = None;
+                self.load_gui_config(gui_config, path.clone());
+                self.state.status_message = Some(StatusMessage::success(format!(
+                    "Config created at {}. Start the server with it to generate the API key and \
+                     TLS certificate.",
+                    path
+                )));
+            }
+            Err(e) => wizard.error = Some(e),
+        }
+    }
 
-            // On WASM, the browser handles TLS - no certificate needed
-            match LdkServerClient::new(url.clone(), api_key, &[]) {
-                Ok(client) => {
-                    self.state.client = Some(Arc::new(client));
-                    self.state.connection_status = ConnectionStatus::Connected;
-                    self.state.status_message = Some(StatusMessage::success("Connected"));
-                    self.fetch_node_info();
-                    self.fetch_balances();
-                    self.fetch_channels();
+    /// Apply config text given in the wasm Load Config dialog, pasted or fetched from `source`.
+    /// Only the connection fields are taken from it; the files it refers to are not read.
+    pub fn load_config_text(&mut self, source: String, contents: &str) {
+        match config::parse_config_from_str(contents) {
+            Ok(gui_config) => {
+                self.state.session.server_url = gui_config.server_url;
+                if !gui_config.api_key.is_empty() {
+                    self.state.session.api_key = gui_config.api_key;
                 }
-                Err(e) => {
-                    self.state.connection_status = ConnectionStatus::Error(e.clone());
-                    self.state.status_message = Some(StatusMessage::error(e));
+                self.state.session.network = gui_config.network;
+                self.state.session.node_settings = Some(gui_config.node_settings);
+                self.state.status_message =
+                    Some(StatusMessage::success("Config loaded successfully"));
+                self.state.show_load_config_dialog = false;
+                self.state.config_paste_text.clear();
+            }
+            Err(e) => self.state.set_config_load_error(source, e),
+        }
+    }
+
+    /// Load configs (`.toml`) and TLS certificates (`.pem`, `.crt`) dropped onto the window.
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let dropped = ctx.input_mut(|i| std::mem::take(&mut i.raw.dropped_files));
+        for file in dropped {
+            // Native drops only carry the path, web ones the name and contents
+            let name = match &file.path {
+                Some(path) => path.display().to_string(),
+                None => file.name.clone(),
+            };
+            match config::dropped_file_kind(&name) {
+                Some(DroppedFileKind::Config) => self.load_dropped_config(file, name),
+                Some(DroppedFileKind::Certificate) => self.use_dropped_certificate(file, name),
+                None => {
+                    self.state.status_message = Some(StatusMessage::error(format!(
+                        "Cannot load {}: drop a config (.toml) or a TLS certificate (.pem, .crt)",
+                        name
+                    )));
                 }
             }
         }
     }
 
-    pub fn disconnect(&mut self) {
-        self.state.client = None;
-        self.state.connection_status = ConnectionStatus::Disconnected;
-        self.state.node_info = None;
-        self.state.balances = None;
-        self.state.channels = None;
-        self.state.payments = None;
-        self.state.status_message = Some(StatusMessage::success("Disconnected"));
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_dropped_config(&mut self, file: egui::DroppedFile, name: String) {
+        match file.path {
+            Some(path) => self.load_config_file(&path),
+            None => self.state.set_config_load_error(name, "The file has no path".to_string()),
+        }
     }
 
-    /// Spawns an async task using the appropriate runtime for the platform
+    #[cfg(target_arch = "wasm32")]
+    fn load_dropped_config(&mut self, file: egui::DroppedFile, name: String) {
+        let contents = match file.bytes {
+            Some(bytes) => config::config_text_from_bytes(&name, bytes.to_vec()),
+            None => Err(format!("{} could not be read", name)),
+        };
+        match contents {
+            Ok(contents) => self.load_config_text(name, &contents),
+            Err(e) => self.state.set_config_load_error(name, e),
+        }
+    }
+
+    /// Connect with the dropped certificate, as if its path was entered in the connection
+    /// settings.
     #[cfg(not(target_arch = "wasm32"))]
-    fn spawn_task<T, F>(&self, future: F) -> task::ChannelTaskHandle<T>
+    fn use_dropped_certificate(&mut self, file: egui::DroppedFile, name: String) {
+        let Some(path) = file.path else {
+            self.state.status_message =
+                Some(StatusMessage::error(format!("{} has no path", name)));
+            return;
+        };
+        self.state.session.tls_cert_source = TlsCertSource::File;
+        self.state.session.tls_cert_path = path.display().to_string();
+        self.state.connection_field_errors.retain(|(f, _)| *f != ConnectionField::TlsCert);
+        self.state.status_message =
+            Some(StatusMessage::success(format!("Using {} as the TLS certificate", name)));
+    }
+
+    /// The browser verifies the server's certificate itself, so there is nothing to set.
+    #[cfg(target_arch = "wasm32")]
+    fn use_dropped_certificate(&mut self, _file: egui::DroppedFile, name: String) {
+        self.state.status_message = Some(StatusMessage::error(format!(
+            "The browser verifies the server's certificate itself; to trust {}, add it to the \
+            browser's or the system's certificate store",
+            name
+        )));
+    }
+
+    /// Fetch the config from the URL entered in the wasm Load Config dialog, resolved against
+    /// the page so that a relative path finds a config served next to the GUI.
+    #[cfg(target_arch = "wasm32")]
+    pub fn fetch_config_from_url(&mut self) {
+        if self.state.session.tasks.is_pending(TaskKind::ConfigFetch) {
+            return;
+        }
+        let page_url = web_sys::window().and_then(|w| w.location().href().ok()).unwrap_or_default();
+        match config::resolve_config_url(&page_url, &self.state.config_url) {
+            Ok(url) => {
+                let handle = self.spawn_task(config::fetch_config_text(url));
+                self.state.session.tasks.insert(TaskKind::ConfigFetch, handle);
+            }
+            Err(e) => {
+                let url = self.state.config_url.trim().to_string();
+                self.state.set_config_load_error(url, e);
+            }
+        }
+    }
+
+    /// Let the user pick a config file for the wasm Load Config dialog and load it. The task
+    /// waits on the user, so it is neither throttled nor timed out.
+    #[cfg(target_arch = "wasm32")]
+    pub fn upload_config_file(&mut self) {
+        if self.state.session.tasks.is_pending(TaskKind::ConfigUpload) {
+            return;
+        }
+        let upload = async { config::upload_config_text().await.map_err(TaskError::from) };
+        let handle = task::spawn(&*self.spawner, &self.ctx, upload)
+            .with_generation(self.state.session.connection_generation);
+        self.state.session.tasks.insert(TaskKind::ConfigUpload, handle);
+    }
+
+    /// Save the text made by `serialize` to a file the user picks, suggesting `name`; in the
+    /// browser it is downloaded instead. Serializing is part of the task, off the UI thread on
+    /// native, so that a large export doesn't freeze the window. The task waits on the user, so
+    /// it is neither throttled nor timed out.
+    pub fn save_text_file<F>(&mut self, name: String, serialize: F)
     where
-        T: Send + 'static,
-        F: std::future::Future<Output = Result<T, String>> + Send + 'static,
+        F: FnOnce() -> Result<String, String> + MaybeSend + 'static,
     {
-        task::spawn_with_runtime(&self.rt, future)
+        if self.state.session.tasks.is_pending(TaskKind::SaveFile) {
+            return;
+        }
+        let save = async move {
+            let contents = serialize().map_err(TaskError::from)?;
+            export::save_text_file(name, contents).await.map_err(TaskError::from)
+        };
+        let handle = task::spawn(&*self.spawner, &self.ctx, save)
+            .with_generation(self.state.session.connection_generation);
+        self.state.session.tasks.insert(TaskKind::SaveFile, handle);
     }
 
-    #[cfg(target_arch = "wasm32")]
-    fn spawn_task<T, F>(&self, future: F) -> task::ChannelTaskHandle<T>
+    /// Save a snapshot of `response` as JSON, with node ids redacted as chosen for copying it.
+    pub fn save_json<T>(&mut self, stem: &str, response: T)
     where
-        T: 'static,
-        F: std::future::Future<Output = Result<T, String>> + 'static,
+        T: serde::Serialize + MaybeSend + 'static,
     {
-        task::spawn_local(future)
+        let redact_node_ids = self.state.redact_json_node_ids;
+        let name = export::file_name(stem, "json", ui::unix_timestamp_now());
+        self.save_text_file(name, move || export::to_json(&response, redact_node_ids));
     }
 
-    pub fn fetch_node_info(&mut self) {
-        if self.state.tasks.node_info.is_some() {
+    /// Turn OS keyring storage of profile API keys on or off, moving the keys of all saved
+    /// profiles accordingly. Profiles whose key cannot be moved are left as they are and reported.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_use_keyring(&mut self, use_keyring: bool) {
+        self.state.use_keyring = use_keyring;
+        let mut failed = Vec::new();
+        for profile in &mut self.state.connection_profiles {
+            let result = if use_keyring {
+                secrets::move_to_keyring(profile)
+            } else {
+                secrets::move_to_profile(profile)
+            };
+            if let Err(e) = result {
+                failed.push(format!("'{}': {}", profile.name, e));
+            }
+        }
+        self.state.status_message = Some(match (use_keyring, failed.is_empty()) {
+            (true, true) => StatusMessage::success("Profile API keys are stored in the OS keyring"),
+            (false, true) => StatusMessage::success("Profile API keys are stored in the profiles"),
+            (true, false) => StatusMessage::error(format!(
+                "Kept API keys in plaintext for {}",
+                failed.join("; ")
+            )),
+            (false, false) => StatusMessage::error(format!(
+                "Could not move API keys out of the OS keyring for {}",
+                failed.join("; ")
+            )),
+        });
+    }
+
+    /// Pin the new certificate after a fingerprint mismatch, update the selected profile and
+    /// connect.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn trust_new_cert(&mut self) {
+        let Some(fingerprint) = self.state.cert_pin_mismatch.take() else {
             return;
+        };
+        if let Some(name) = &self.state.selected_profile {
+            if let Some(profile) = self.state.connection_profiles.iter_mut().find(|p| &p.name == name) {
+                profile.pinned_cert_fingerprint = Some(fingerprint.clone());
+            }
         }
-        if let Some(client) = &self.state.client {
-            let client = client.clone();
-            self.state.tasks.node_info = Some(self.spawn_task(async move {
-                client.get_node_info(GetNodeInfoRequest {}).await.map_err(|e| e.to_string())
-            }));
+        self.state.session.pinned_cert_fingerprint = Some(fingerprint);
+        self.connect();
+    }
+
+    /// Create a client from the connection settings.
+    fn build_client(&self) -> Result<LdkServerClient, String> {
+        let url = self.state.session.server_url.trim().to_string();
+        let api_key = self.state.session.api_key.clone();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if url.is_empty() || api_key.is_empty() {
+                return Err("Please fill in all connection fields".to_string());
+            }
+
+            let proxy = self.state.session.proxy.trim();
+            if self.state.session.insecure_http {
+                if !config::insecure_http_allowed(&url) {
+                    return Err(
+                        "Insecure HTTP is only allowed for localhost and onion addresses"
+                            .to_string(),
+                    );
+                }
+                if proxy.is_empty() {
+                    return LdkServerClient::new_insecure(url, api_key);
+                }
+                return LdkServerClient::new_insecure_with_proxy(url, api_key, proxy);
+            }
+
+            let cert_data = self.read_tls_cert()?;
+            if proxy.is_empty() {
+                LdkServerClient::new(url, api_key, &cert_data)
+            } else {
+                LdkServerClient::new_with_proxy(url, api_key, &cert_data, proxy)
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            if url.is_empty() || api_key.is_empty() {
+                return Err("Please fill in server URL and API key".to_string());
+            }
+
+            // On WASM, the browser handles TLS - no certificate needed
+            LdkServerClient::new(url, api_key, &[])
         }
     }
 
-    pub fn fetch_balances(&mut self) {
-        if self.state.tasks.balances.is_some() {
-            return;
+    /// Disconnect, asking for confirmation first if operations are still in flight.
+    pub fn request_disconnect(&mut self) {
+        if self.state.session.tasks.pending_operations().is_empty() {
+            self.disconnect();
+        } else {
+            self.state.show_disconnect_dialog = true;
         }
-        if let Some(client) = &self.state.client {
-            let client = client.clone();
-            self.state.tasks.balances = Some(self.spawn_task(async move {
-                client.get_balances(GetBalancesRequest {}).await.map_err(|e| e.to_string())
-            }));
+    }
+
+    /// Disconnect and cancel every pending task so no late result lands in the cleared state.
+    pub fn disconnect(&mut self) {
+        self.state.session.client = None;
+        self.state.session.connection_generation += 1;
+        self.state.session.tasks = AsyncTasks::default();
+        self.state.session.confirmed_network = None;
+        self.state.session.connection_status = ConnectionStatus::Disconnected;
+        self.state.session.connection_health = Default::default();
+        self.state.session.node_info = None;
+        self.state.session.stale_wallet_syncs.clear();
+        self.state.session.public_tip_height = None;
+        self.state.session.blocks_behind = None;
+        self.state.session.liquidity_alerts.clear();
+        self.state.session.balances = None;
+        self.state.session.balance_changes = None;
+        self.state.session.channels = None;
+        self.state.session.payments = None;
+        self.state.status_message = Some(StatusMessage::success("Disconnected"));
+    }
+
+    /// Make another node the active one. The previously active session keeps its client and
+    /// cached data so switching back is instant.
+    pub fn switch_session(&mut self, index: usize) {
+        let active = self.state.active_session;
+        if index == active || index >= self.state.sessions.len() {
+            return;
         }
+        std::mem::swap(&mut self.state.session, &mut self.state.sessions[active]);
+        std::mem::swap(&mut self.state.session, &mut self.state.sessions[index]);
+        self.state.active_session = index;
+        self.state.clear_node_results();
+        self.state.connection_field_errors.clear();
+        self.state.cert_pin_mismatch = None;
+        self.state.status_message = None;
     }
 
-    pub fn fetch_channels(&mut self) {
-        if self.state.tasks.channels.is_some() {
+    /// Start a new, disconnected node session and switch to it.
+    pub fn add_session(&mut self) {
+        self.state.sessions.push(NodeSession::default());
+        self.switch_session(self.state.sessions.len() - 1);
+    }
+
+    /// Close the active node session, dropping its client and cancelling its tasks.
+    pub fn remove_active_session(&mut self) {
+        if self.state.sessions.len() <= 1 {
             return;
         }
-        if let Some(client) = &self.state.client {
-            let client = client.clone();
-            self.state.tasks.channels = Some(self.spawn_task(async move {
-                client.list_channels(ListChannelsRequest {}).await.map_err(|e| e.to_string())
-            }));
+        let removed = self.state.active_session;
+        self.switch_session(if removed == 0 { 1 } else { removed - 1 });
+        self.state.sessions.remove(removed);
+        if removed < self.state.active_session {
+            self.state.active_session -= 1;
+        }
+    }
+
+    /// Spawns an async task using the appropriate runtime for the platform
+    fn spawn_task<T, E, F>(&self, future: F) -> task::ChannelTaskHandle<T>
+    where
+        T: MaybeSend + 'static,
+        E: Into<TaskError>,
+        F: std::future::Future<Output = Result<T, E>> + MaybeSend + 'static,
+    {
+        self.spawn_task_with_timeout(future, self.request_timeout())
+    }
+
+    /// Like [`Self::spawn_task`], for requests that need a different timeout than the one set in
+    /// the settings.
+    fn spawn_task_with_timeout<T, E, F>(
+        &self, future: F, timeout: Duration,
+    ) -> task::ChannelTaskHandle<T>
+    where
+        T: MaybeSend + 'static,
+        E: Into<TaskError>,
+        F: std::future::Future<Output = Result<T, E>> + MaybeSend + 'static,
+    {
+        task::spawn_with_timeout(&*self.spawner, &self.ctx, &self.limiter, future, timeout)
+            .with_generation(self.state.session.connection_generation)
+    }
+
+    /// Like [`Self::spawn_task_with_timeout`], running the future made by `attempt` again after
+    /// a transient failure. Only for read-only requests: a mutation that timed out may still
+    /// have gone through.
+    fn spawn_task_with_retry<T, E, F, Fut>(
+        &self, attempt: F, timeout: Duration,
+    ) -> task::ChannelTaskHandle<T>
+    where
+        T: MaybeSend + 'static,
+        E: Into<TaskError> + MaybeSend + 'static,
+        F: FnMut() -> Fut + MaybeSend + 'static,
+        Fut: std::future::Future<Output = Result<T, E>> + MaybeSend + 'static,
+    {
+        let (spawner, policy) = (&*self.spawner, RetryPolicy::READ_ONLY);
+        task::spawn_with_retry(spawner, &self.ctx, &self.limiter, attempt, timeout, policy)
+            .with_generation(self.state.session.connection_generation)
+    }
+
+    /// Like [`Self::spawn_task`], for multi-step tasks that report their progress. `task` is
+    /// given the sender to report it with; each step is expected to apply its own timeout.
+    fn spawn_task_with_progress<T, F, Fut>(&self, task: F) -> task::ProgressTaskHandle<T>
+    where
+        T: MaybeSend + 'static,
+        F: FnOnce(task::ProgressSender<T>) -> Fut,
+        Fut: std::future::Future<Output = Result<T, TaskError>> + MaybeSend + 'static,
+    {
+        task::spawn_with_progress(&*self.spawner, &self.ctx, &self.limiter, task)
+            .with_generation(self.state.session.connection_generation)
+    }
+
+    fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.state.request_timeout_secs.max(1))
+    }
+
+    pub fn fetch_node_info(&mut self) {
+        if self.state.session.tasks.is_pending(TaskKind::NodeInfo)
+            || !self.state.session.refresh.admit(TaskKind::NodeInfo, ui::unix_timestamp_millis())
+        {
+            return;
+        }
+        if let Some(client) = &self.state.session.client {
+            let client = client.clone();
+            let attempt = move || {
+                let client = client.clone();
+                async move { client.get_node_info(GetNodeInfoRequest {}).await }
+            };
+            let handle = self.spawn_task_with_retry(attempt, self.request_timeout());
+            self.state.session.tasks.insert(TaskKind::NodeInfo, handle);
+        }
+    }
+
+    pub fn fetch_balances(&mut self) {
+        if self.state.session.tasks.is_pending(TaskKind::Balances)
+            || !self.state.session.refresh.admit(TaskKind::Balances, ui::unix_timestamp_millis())
+        {
+            return;
+        }
+        let now = ui::unix_timestamp_now();
+        self.state.session.balances_requested_at = Some(now);
+        self.state.session.refresh.mark_run(TaskKind::Balances, now);
+        if let Some(client) = &self.state.session.client {
+            let client = client.clone();
+            let attempt = move || {
+                let client = client.clone();
+                async move { client.get_balances(GetBalancesRequest {}).await }
+            };
+            let handle = self.spawn_task_with_retry(attempt, self.request_timeout());
+            self.state.session.tasks.insert(TaskKind::Balances, handle);
+        }
+    }
+
+    pub fn fetch_channels(&mut self) {
+        if self.state.session.tasks.is_pending(TaskKind::Channels)
+            || !self.state.session.refresh.admit(TaskKind::Channels, ui::unix_timestamp_millis())
+        {
+            return;
+        }
+        self.state.session.refresh.mark_run(TaskKind::Channels, ui::unix_timestamp_now());
+        if let Some(client) = &self.state.session.client {
+            let client = client.clone();
+            let attempt = move || {
+                let client = client.clone();
+                async move { client.list_channels(ListChannelsRequest {}).await }
+            };
+            let handle = self.spawn_task_with_retry(attempt, self.request_timeout());
+            self.state.session.tasks.insert(TaskKind::Channels, handle);
+        }
+    }
+
+    /// Switch to the Channels tab, scrolled to and highlighting the channel with `channel_id`.
+    pub fn show_channel(&mut self, channel_id: String) {
+        self.state.active_tab = ActiveTab::Channels;
+        self.state.highlighted_channel = Some(channel_id);
+        self.state.scroll_to_highlighted_channel = true;
+    }
+
+    /// Fetch the payments page after `payments_page_token`, or the first page when it is unset.
+    /// Asking for another page while one is loading cancels that one. Only refreshes of the
+    /// first page are subject to the refresh cooldown.
+    pub fn fetch_payments(&mut self) {
+        let page_token = self.state.session.payments_page_token.clone();
+        if page_token.is_none()
+            && !self.state.session.refresh.admit(TaskKind::Payments, ui::unix_timestamp_millis())
+        {
+            return;
+        }
+        let policy = TaskKind::Payments.coalesce_policy();
+        if self.state.session.tasks.request(TaskKind::Payments, page_token.clone(), policy)
+            != Admission::Start
+        {
+            return;
+        }
+        if let Some(client) = &self.state.session.client {
+            let client = client.clone();
+            let is_continuation = page_token.is_some();
+            if !is_continuation {
+                self.state.session.refresh.mark_run(TaskKind::Payments, ui::unix_timestamp_now());
+            }
+            let timeout = self.request_timeout() * PAYMENTS_PAGE_TIMEOUT_FACTOR;
+            let attempt = move || {
+                let client = client.clone();
+                let page_token = page_token.clone();
+                async move {
+                    client
+                        .list_payments(ListPaymentsRequest { page_token })
+                        .await
+                        .map(|response| (response, is_continuation))
+                }
+            };
+            let handle = self.spawn_task_with_retry(attempt, timeout);
+            self.state.session.tasks.insert(TaskKind::Payments, handle);
+        }
+    }
+
+    /// Fetch node info, balances, channels and the first payments page concurrently, as one
+    /// task whose result keeps each fetch's outcome.
+    pub fn sync_all(&mut self) {
+        if self.state.session.tasks.is_pending(TaskKind::SyncAll)
+            || !self.state.session.refresh.admit(TaskKind::SyncAll, ui::unix_timestamp_millis())
+        {
+            return;
+        }
+        let Some(client) = self.state.session.client.clone() else {
+            return;
+        };
+        let now = ui::unix_timestamp_now();
+        self.state.session.balances_requested_at = Some(now);
+        for kind in [TaskKind::Balances, TaskKind::Channels, TaskKind::Payments] {
+            self.state.session.refresh.mark_run(kind, now);
+        }
+
+        let (timeout, policy) = (self.request_timeout(), RetryPolicy::READ_ONLY);
+        let payments_timeout = timeout * PAYMENTS_PAGE_TIMEOUT_FACTOR;
+        let limiter = self.limiter.clone();
+        let sync = async move {
+            let node_info = || client.get_node_info(GetNodeInfoRequest {});
+            let balances = || client.get_balances(GetBalancesRequest {});
+            let channels = || client.list_channels(ListChannelsRequest {});
+            let payments = || client.list_payments(ListPaymentsRequest { page_token: None });
+            let (node_info, balances, channels, payments) = futures_util::future::join4(
+                limiter.throttle(task::with_retry(node_info, timeout, policy)),
+                limiter.throttle(task::with_retry(balances, timeout, policy)),
+                limiter.throttle(task::with_retry(channels, timeout, policy)),
+                limiter.throttle(task::with_retry(payments, payments_timeout, policy)),
+            )
+            .await;
+            Ok(SyncAll { node_info, balances, channels, payments })
+        };
+        // Each fetch waits for its own turn under the limiter and applies its own timeout, so
+        // the task as a whole is spawned without either
+        let handle = task::spawn(&*self.spawner, &self.ctx, sync)
+            .with_generation(self.state.session.connection_generation);
+        self.state.session.tasks.insert(TaskKind::SyncAll, handle);
+    }
+
+    pub fn generate_onchain_address(&mut self) {
+        if self.state.session.tasks.is_pending(TaskKind::OnchainReceive) {
+            return;
+        }
+        if let Some(client) = &self.state.session.client {
+            let client = client.clone();
+            let label = self.state.forms.onchain_receive.address_label.trim().to_string();
+            self.state.session.tasks.insert(TaskKind::OnchainReceive, self.spawn_task(async move {
+                let response = client
+                    .onchain_receive(OnchainReceiveRequest {})
+                    .await
+                    .map_err(TaskError::from)?;
+                Ok::<_, TaskError>((response, label))
+            }));
+        }
+    }
+
+    /// Display `address` in the Receive view, recording it in the address history.
+    pub fn show_onchain_address(&mut self, address: String) {
+        let history = &mut self.state.address_history;
+        let entry = match history.iter().position(|a| a.address == address) {
+            Some(idx) => &mut history[idx],
+            None => {
+                history.push(GeneratedAddress {
+                    address: address.clone(),
+                    created_at: ui::unix_timestamp_now(),
+                    label: String::new(),
+                    times_shown: 0,
+                    copied: false,
+                });
+                history.last_mut().expect("just pushed")
+            }
+        };
+        self.state.onchain_address_reused = entry.copied || entry.times_shown > 0;
+        entry.times_shown += 1;
+        self.state.onchain_address = Some(address);
+        self.address_history_changed();
+    }
+
+    /// Switch the unit amounts are displayed in outside the on-chain forms.
+    pub fn set_display_unit(&mut self, unit: AmountUnit) {
+        self.state.display_unit = unit;
+        self.settings_changed();
+    }
+
+    /// Switch the on-chain amount unit, converting any amount already entered.
+    pub fn set_onchain_unit(&mut self, unit: AmountUnit) {
+        let previous = self.state.onchain_unit;
+        if previous == unit {
+            return;
+        }
+        let forms = &mut self.state.forms;
+        for field in [&mut forms.onchain_send.amount, &mut forms.onchain_receive.amount] {
+            if let Ok(sats) = amount::parse_amount(field, previous) {
+                *field = amount::format_amount_input(sats, unit);
+            }
+        }
+        self.state.onchain_unit = unit;
+
+        #[cfg(target_arch = "wasm32")]
+        storage::store(storage::AMOUNT_UNIT_KEY, &unit);
+    }
+
+    /// Persist the address history. On native this happens in [`App::save`] instead.
+    pub fn address_history_changed(&self) {
+        #[cfg(target_arch = "wasm32")]
+        storage::store(storage::ADDRESS_HISTORY_KEY, &self.state.address_history);
+    }
+
+    /// Persist the connection settings. On native this happens in [`App::save`] instead.
+    pub fn settings_changed(&self) {
+        #[cfg(target_arch = "wasm32")]
+        storage::store(storage::SETTINGS_KEY, &self.state.persisted_settings());
+    }
+
+    /// Persist the address book. On native this happens in [`App::save`] instead.
+    pub fn address_book_changed(&self) {
+        #[cfg(target_arch = "wasm32")]
+        storage::store(storage::ADDRESS_BOOK_KEY, &self.state.address_book);
+    }
+
+    /// Write every persisted value to `store`. The API key and certificate are only part of
+    /// the settings when the user chose to remember them.
+    fn save_to<S: storage::Store + ?Sized>(&self, store: &mut S) {
+        store.set(storage::ADDRESS_HISTORY_KEY, &self.state.address_history);
+        store.set(storage::ADDRESS_BOOK_KEY, &self.state.address_book);
+        store.set(storage::AMOUNT_UNIT_KEY, &self.state.onchain_unit);
+        store.set(storage::SETTINGS_KEY, &self.state.persisted_settings());
+        #[cfg(not(target_arch = "wasm32"))]
+        store.set(storage::CONNECTION_PROFILES_KEY, &self.state.connection_profiles);
+    }
+
+    /// Write everything to localStorage every few seconds, for the settings edited without
+    /// storing them right away, such as the server URL being typed.
+    #[cfg(target_arch = "wasm32")]
+    fn poll_auto_save(&mut self) {
+        let now = ui::unix_timestamp_now();
+        if self.storage_cleared || now < self.saved_at + AUTO_SAVE_INTERVAL_SECS {
+            return;
+        }
+        self.saved_at = now;
+        self.save_to(&mut storage::LocalStorage);
+    }
+
+    /// Remove everything stored in localStorage and reload the page, so that nothing in memory
+    /// is written back.
+    #[cfg(target_arch = "wasm32")]
+    pub fn clear_stored_data(&mut self) {
+        storage::clear();
+        self.storage_cleared = true;
+        if let Some(Err(e)) = web_sys::window().map(|w| w.location().reload()) {
+            self.state.status_message = Some(StatusMessage::error(format!(
+                "Stored data cleared; reload the page to start afresh ({:?})",
+                e
+            )));
+        }
+    }
+
+    pub fn send_onchain(&mut self) {
+        if self.state.session.tasks.is_pending(TaskKind::OnchainSend) {
+            return;
+        }
+        if let Some(client) = &self.state.session.client {
+            let form = &self.state.forms.onchain_send;
+            let address = form.address.trim().to_string();
+            let send_all = if form.send_all { Some(true) } else { None };
+            let fee_rate = form.fee_rate_sat_per_vb.trim().parse::<u64>().ok();
+
+            if address.is_empty() {
+                self.state.status_message = Some(StatusMessage::error("Address is required"));
+                return;
+            }
+
+            let amount_sats = match self.validate_onchain_send_amount() {
+                Ok(amount_sats) => amount_sats,
+                Err(e) => {
+                    self.state.status_message = Some(StatusMessage::error(e));
+                    return;
+                }
+            };
+
+            let client = client.clone();
+            self.state.session.tasks.insert(TaskKind::OnchainSend, self.spawn_task(async move {
+                client
+                    .onchain_send(OnchainSendRequest {
+                        address,
+                        amount_sats,
+                        send_all,
+                        fee_rate_sat_per_vb: fee_rate,
+                    })
+                    .await
+                    .map_err(TaskError::from)
+            }));
+        }
+    }
+
+    /// Parse the on-chain send amount and check it against the spendable balance, if known.
+    /// Returns `None` for send-all.
+    pub fn validate_onchain_send_amount(&self) -> Result<Option<u64>, String> {
+        let form = &self.state.forms.onchain_send;
+        if form.send_all {
+            return Ok(None);
+        }
+
+        let unit = self.state.onchain_unit;
+        let sats = amount::parse_amount(&form.amount, unit)?;
+        if let Ok(fee_rate) = form.fee_rate_sat_per_vb.trim().parse::<u64>() {
+            let fee_sats = fees::estimate_fee_sats(fee_rate);
+            if sats < fee_sats {
+                return Err(format!(
+                    "Amount is below the estimated fee of {}; the output would cost more to \
+                     create than it is worth",
+                    amount::format_amount(fee_sats, unit)
+                ));
+            }
+        }
+        if let Some(balances) = self.state.session.balances.as_ref().map(|b| &b.data) {
+            if sats > balances.spendable_onchain_balance_sats {
+                return Err(format!(
+                    "Amount exceeds the spendable balance of {}",
+                    amount::format_amount(balances.spendable_onchain_balance_sats, unit)
+                ));
+            }
+        }
+        Ok(Some(sats))
+    }
+
+    /// Fetch recommended fee rates from mempool.space (or the configured compatible API).
+    pub fn fetch_fee_estimates(&mut self) {
+        self.state.fee_estimates_requested_at = Some(ui::unix_timestamp_now());
+
+        let custom_url = self.state.mempool_api_url.trim();
+        let network = self.state.session.chain_network().unwrap_or_default();
+        let base_url = if !custom_url.is_empty() {
+            custom_url.to_string()
+        } else if let Some(url) = fees::default_api_url_for_network(network) {
+            url.to_string()
+        } else {
+            self.state.fee_estimates_error =
+                Some(format!("Fee estimates are not available on {}", network));
+            return;
+        };
+
+        let policy = TaskKind::FeeEstimates.coalesce_policy();
+        if self.state.session.tasks.request(TaskKind::FeeEstimates, base_url.clone(), policy)
+            != Admission::Start
+        {
+            return;
+        }
+        self.state.fee_estimates_error = None;
+        let handle = self.spawn_task(fees::fetch_recommended_fees(base_url));
+        self.state.session.tasks.insert(TaskKind::FeeEstimates, handle);
+    }
+
+    /// Fetch the BTC price in the selected fiat currency once the last fetch is older than
+    /// [`fiat::FIAT_RATE_TTL_SECS`]. Does nothing while fiat values are off.
+    pub fn refresh_fiat_rate(&mut self) {
+        let Some(currency) = self.state.fiat_currency.clone() else {
+            return;
+        };
+        let now = ui::unix_timestamp_now();
+        let requested_at = self.state.fiat_rate_requested_at;
+        if requested_at.is_some_and(|t| now.saturating_sub(t) < fiat::FIAT_RATE_TTL_SECS) {
+            return;
+        }
+        let policy = TaskKind::FiatRate.coalesce_policy();
+        if self.state.session.tasks.request(TaskKind::FiatRate, currency.clone(), policy)
+            != Admission::Start
+        {
+            return;
+        }
+        self.state.fiat_rate_requested_at = Some(now);
+        let handle = self.spawn_task(fiat::fetch_btc_price(currency));
+        self.state.session.tasks.insert(TaskKind::FiatRate, handle);
+    }
+
+    /// Show fiat values in `currency`, or turn them off with `None`.
+    pub fn set_fiat_currency(&mut self, currency: Option<String>) {
+        self.state.fiat_currency = currency;
+        self.state.fiat_rate_requested_at = None;
+        self.settings_changed();
+        self.refresh_fiat_rate();
+    }
+
+    /// Esplora compatible API used to watch transactions and look up the public chain tip: the
+    /// node's Esplora chain source if configured, otherwise the fee estimate API.
+    pub fn tx_watch_base_url(&self) -> Option<String> {
+        if let ChainSourceConfig::Esplora { server_url } = &self.state.session.chain_source {
+            return Some(server_url.clone());
+        }
+        let custom_url = self.state.mempool_api_url.trim();
+        if !custom_url.is_empty() {
+            return Some(custom_url.to_string());
+        }
+        let network = self.state.session.chain_network().unwrap_or_default();
+        fees::default_api_url_for_network(network).map(str::to_string)
+    }
+
+    /// Start watching `last_txid` for confirmations.
+    pub fn watch_last_txid(&mut self) {
+        let (Some(txid), Some(base_url)) = (self.state.last_txid.clone(), self.tx_watch_base_url())
+        else {
+            return;
+        };
+        self.state.session.tasks.remove(TaskKind::TxConfirmations);
+        self.state.tx_watch = Some(TxWatch::new(txid, base_url));
+    }
+
+    pub fn stop_tx_watch(&mut self) {
+        self.state.session.tasks.remove(TaskKind::TxConfirmations);
+        self.state.tx_watch = None;
+    }
+
+    /// Run a confirmation lookup for the watched transaction when one is due.
+    fn poll_tx_watch(&mut self, ctx: &egui::Context) {
+        let Some(watch) = &mut self.state.tx_watch else {
+            return;
+        };
+        if watch.is_done() {
+            return;
+        }
+
+        let now = ui::unix_timestamp_now();
+        if !self.state.session.tasks.is_pending(TaskKind::TxConfirmations) && watch.is_due(now) {
+            watch.last_checked = Some(now);
+            let future = esplora::fetch_confirmations(watch.base_url.clone(), watch.txid.clone());
+            self.state.session.tasks.insert(TaskKind::TxConfirmations, self.spawn_task(future));
+        }
+        ctx.request_repaint_after(Duration::from_secs(esplora::TX_WATCH_INTERVAL_SECS));
+    }
+
+    /// While reconnecting, rebuild the client and ping it once each attempt is due. Pings while
+    /// connected are run by [`Self::poll_refresh_schedule`].
+    fn poll_reconnect(&mut self, ctx: &egui::Context) {
+        let ConnectionStatus::Reconnecting { next_attempt_at, .. } =
+            self.state.session.connection_status
+        else {
+            return;
+        };
+        let now = ui::unix_timestamp_now();
+        let checking = self.state.session.tasks.is_pending(TaskKind::HealthCheck);
+        if now >= next_attempt_at && !checking {
+            match self.build_client() {
+                Ok(client) => {
+                    self.state.session.client = Some(Arc::new(client));
+                    self.spawn_health_check(now);
+                }
+                Err(_) => self.schedule_reconnect(),
+            }
+        }
+        // Keep the countdown in the status bar ticking
+        ctx.request_repaint_after(Duration::from_secs(1));
+    }
+
+    /// Start the background refreshes that are due while connected, and the fetches deferred by
+    /// the refresh cooldown, then schedule a repaint for the next one. A resource is skipped
+    /// while a fetch of it is already in flight.
+    fn poll_refresh_schedule(&mut self, ctx: &egui::Context) {
+        if self.state.session.connection_status != ConnectionStatus::Connected {
+            return;
+        }
+        let now = ui::unix_timestamp_now();
+        let foreground = self.state.refresh_in_background || in_foreground(ctx);
+        let session = &mut self.state.session;
+        let pending_channels = session
+            .channels
+            .as_ref()
+            .is_some_and(|c| c.data.channels.iter().any(|ch| !ch.is_channel_ready));
+        let tasks = &session.tasks;
+        let due = session.refresh.take_due(now, &self.state.refresh_schedules, |kind| {
+            tasks.is_pending(kind)
+                || (kind == TaskKind::Channels && !pending_channels)
+                // Only the health check keeps going in the background, to notice disconnects
+                || (!foreground && kind != TaskKind::HealthCheck)
+        });
+
+        for kind in due {
+            match kind {
+                TaskKind::HealthCheck => self.spawn_health_check(now),
+                TaskKind::PublicTip => self.fetch_public_tip(),
+                kind => self.refresh_resource(kind),
+            }
+        }
+
+        let now_ms = ui::unix_timestamp_millis();
+        for kind in self.state.session.refresh.take_deferred(now_ms) {
+            self.refresh_resource(kind);
+        }
+
+        let next = self.state.session.refresh.next_due_in(now, &self.state.refresh_schedules);
+        if let Some(secs) = next {
+            ctx.request_repaint_after(Duration::from_secs(secs.max(1)));
+        }
+        if let Some(left) = self.state.session.refresh.next_deferred_in(now_ms) {
+            ctx.request_repaint_after(left);
+        }
+    }
+
+    /// Fetch a cached resource again; the payments from their first page.
+    fn refresh_resource(&mut self, kind: TaskKind) {
+        match kind {
+            TaskKind::NodeInfo => self.fetch_node_info(),
+            TaskKind::Balances => self.fetch_balances(),
+            TaskKind::Channels => self.fetch_channels(),
+            TaskKind::Payments => {
+                self.state.session.payments_page_token = None;
+                self.fetch_payments();
+            }
+            TaskKind::SyncAll => self.sync_all(),
+            _ => {}
+        }
+    }
+
+    /// Fetch the public chain tip from the API transactions are watched with, to check that the
+    /// node is not falling behind. Skipped on networks without a known Esplora API.
+    fn fetch_public_tip(&mut self) {
+        let Some(base_url) = self.tx_watch_base_url() else {
+            return;
+        };
+        let handle = self.spawn_task(esplora::fetch_tip_height(base_url));
+        self.state.session.tasks.insert(TaskKind::PublicTip, handle);
+    }
+
+    /// Background health check interval, `None` while health checks are paused.
+    fn health_check_interval(&self) -> Option<u64> {
+        let schedule = self.state.refresh_schedules.get(&TaskKind::HealthCheck)?;
+        (!schedule.paused).then_some(schedule.interval_secs)
+    }
+
+    fn spawn_health_check(&mut self, now: u64) {
+        let Some(client) = &self.state.session.client else {
+            return;
+        };
+        self.state.session.connection_health.last_check_at = Some(now);
+        self.state.session.refresh.mark_run(TaskKind::HealthCheck, now);
+        let handle = self.spawn_task(ping(client.clone()));
+        self.state.session.tasks.insert(TaskKind::HealthCheck, handle);
+    }
+
+    /// Keep pinging connected nodes that are not on screen, so the node switcher can show
+    /// whether they are still reachable.
+    fn poll_background_sessions(&mut self, ctx: &egui::Context) {
+        let now = ui::unix_timestamp_now();
+        let interval = self.health_check_interval();
+        let mut any_connected = false;
+        for index in 0..self.state.sessions.len() {
+            if index == self.state.active_session {
+                continue;
+            }
+            let session = &mut self.state.sessions[index];
+            session.tasks.discard_stale(session.connection_generation);
+            if let Some(res) = session.tasks.try_take::<u64>(TaskKind::HealthCheck) {
+                let health = &mut session.connection_health;
+                match res {
+                    Ok(latency_ms) => {
+                        health.latency_ms = Some(latency_ms);
+                        health.last_success_at = Some(now);
+                        health.consecutive_failures = 0;
+                    }
+                    Err(_) => health.consecutive_failures += 1,
+                }
+            }
+            for timing in session.tasks.take_timings() {
+                self.state.task_timings.push(timing);
+            }
+
+            if session.connection_status != ConnectionStatus::Connected {
+                continue;
+            }
+            any_connected = true;
+            let Some(interval) = interval else {
+                continue;
+            };
+            let due = session
+                .connection_health
+                .last_check_at
+                .map_or(true, |t| now.saturating_sub(t) >= interval);
+            let Some(client) = session.client.clone() else {
+                continue;
+            };
+            if !due || session.tasks.is_pending(TaskKind::HealthCheck) {
+                continue;
+            }
+            session.connection_health.last_check_at = Some(now);
+            let generation = session.connection_generation;
+            let handle = self.spawn_task(ping(client)).with_generation(generation);
+            self.state.sessions[index].tasks.insert(TaskKind::HealthCheck, handle);
+        }
+        if let (true, Some(interval)) = (any_connected, interval) {
+            ctx.request_repaint_after(Duration::from_secs(interval));
+        }
+    }
+
+    /// Record a failed request or health check, starting to reconnect after repeated failures.
+    fn note_connection_failure(&mut self) {
+        let health = &mut self.state.session.connection_health;
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= HEALTH_CHECK_MAX_FAILURES
+            && self.state.session.connection_status == ConnectionStatus::Connected
+        {
+            self.schedule_reconnect();
+        }
+    }
+
+    /// Schedule the next reconnection attempt with exponential backoff.
+    fn schedule_reconnect(&mut self) {
+        let attempt = match self.state.session.connection_status {
+            ConnectionStatus::Reconnecting { attempt, .. } => attempt + 1,
+            _ => 1,
+        };
+        let delay = RECONNECT_BASE_DELAY_SECS
+            .saturating_mul(1 << (attempt - 1).min(16))
+            .min(RECONNECT_MAX_DELAY_SECS);
+        self.state.session.connection_status = ConnectionStatus::Reconnecting {
+            attempt,
+            next_attempt_at: ui::unix_timestamp_now() + delay,
+        };
+    }
+
+    /// Verify which network the node is on, see [`esplora::verify_network`]. Runs in the
+    /// background, so node info is shown meanwhile. Skipped when no network is configured.
+    fn check_network(&mut self, node_info: &GetNodeInfoResponse) {
+        self.state.session.confirmed_network = None;
+        let Some(best_block) = &node_info.current_best_block else {
+            return;
+        };
+        let Some(network) = self.state.session.network() else {
+            return;
+        };
+        if self.state.session.tasks.is_pending(TaskKind::NetworkCheck) {
+            return;
+        }
+        let chain_source_url = match &self.state.session.chain_source {
+            ChainSourceConfig::Esplora { server_url } => Some(server_url.clone()),
+            _ => None,
+        };
+        let handle = self.spawn_task(esplora::verify_network(
+            chain_source_url,
+            network,
+            best_block.height,
+            best_block.block_hash.clone(),
+        ));
+        self.state.session.tasks.insert(TaskKind::NetworkCheck, handle);
+    }
+
+    /// Returns the detected network if it disagrees with the configured one.
+    pub fn network_mismatch(&self) -> Option<Network> {
+        let configured = self.state.session.network().unwrap_or_default();
+        self.state.session.confirmed_network.filter(|detected| *detected != configured)
+    }
+
+    /// Note which node wallets have not synced within the stale-sync threshold, for the warning
+    /// banner. Run whenever node info arrives or the threshold changes.
+    pub fn check_wallet_syncs(&mut self) {
+        let session = &mut self.state.session;
+        let threshold_secs = self.state.stale_sync_mins * 60;
+        session.stale_wallet_syncs = match &session.node_info {
+            Some(info) => {
+                stale_wallet_syncs(&info.data, ui::unix_timestamp_now(), threshold_secs)
+            }
+            None => Vec::new(),
+        };
+    }
+
+    /// Compare the node's best block with the last fetched public chain tip.
+    pub fn check_block_lag(&mut self) {
+        let session = &mut self.state.session;
+        let node_height = session
+            .node_info
+            .as_ref()
+            .and_then(|info| info.data.current_best_block.as_ref())
+            .map(|block| block.height);
+        session.blocks_behind = match (node_height, session.public_tip_height) {
+            (Some(node), Some(public)) => {
+                esplora::blocks_behind(node, public, self.state.max_block_lag)
+            }
+            _ => None,
+        };
+    }
+
+    /// Compare the liquidity of the cached channels with the alert thresholds. With `notify`,
+    /// alerts that were not raised at the previous check are also reported in the status bar.
+    pub fn check_liquidity(&mut self, notify: bool) {
+        let session = &mut self.state.session;
+        let thresholds = self.state.liquidity_thresholds;
+        let alerts = match &session.channels {
+            Some(channels) => liquidity::liquidity_alerts(&channels.data.channels, thresholds),
+            None => Vec::new(),
+        };
+        let new_alerts: Vec<&str> = alerts
+            .iter()
+            .filter(|alert| {
+                !session.liquidity_alerts.iter().any(|old| old.direction == alert.direction)
+            })
+            .map(|alert| alert.title())
+            .collect();
+        if notify && !new_alerts.is_empty() {
+            self.state.status_message = Some(StatusMessage::error(format!(
+                "{}; see the Balances tab",
+                new_alerts.join(" and ")
+            )));
+        }
+        session.liquidity_alerts = alerts;
+    }
+
+    /// Switch to the network the node is actually on.
+    pub fn trust_node_network(&mut self) {
+        if let Some(network) = self.state.session.confirmed_network {
+            self.set_network(Some(network));
+        }
+    }
+
+    /// Change the network used for explorer links, fee estimates and address checks. `None`
+    /// clears it, which disables the node network check.
+    pub fn set_network(&mut self, network: Option<Network>) {
+        self.state.session.network = network.map(|n| n.to_string()).unwrap_or_default();
+        self.state.session.confirmed_network = None;
+        self.state.session.public_tip_height = None;
+        self.state.session.blocks_behind = None;
+        self.state.fee_estimates = None;
+        self.state.fee_estimates_error = None;
+        self.stop_tx_watch();
+        self.settings_changed();
+    }
+
+    /// The server stopped accepting the API key (e.g. it was regenerated); stop polling and ask
+    /// for a new one.
+    fn require_reauth(&mut self) {
+        if matches!(
+            self.state.session.connection_status,
+            ConnectionStatus::AuthRequired | ConnectionStatus::Disconnected
+        ) {
+            return;
+        }
+        self.state.session.connection_status = ConnectionStatus::AuthRequired;
+        self.state.reauth_api_key.clear();
+        self.state.reauth_error = None;
+        self.state.session.tasks.remove(TaskKind::HealthCheck);
+    }
+
+    /// Retry with the API key from the re-authentication dialog.
+    pub fn reauthenticate(&mut self) {
+        if self.state.reauth_api_key.trim().is_empty() {
+            self.state.reauth_error = Some("API key is required".to_string());
+            return;
+        }
+        self.state.session.api_key = self.state.reauth_api_key.trim().to_string();
+        self.state.reauth_error = None;
+        self.state.reauthenticating = true;
+        self.connect();
+        if !self.state.session.tasks.is_pending(TaskKind::Connect) {
+            // Rejected before probing, e.g. an invalid certificate
+            self.state.reauthenticating = false;
+        }
+    }
+
+    pub fn stop_reconnecting(&mut self) {
+        self.disconnect();
+        self.state.status_message = Some(StatusMessage::error("Stopped reconnecting"));
+    }
+
+    /// Operations that change a node still running on any node, prefixed with the node's label
+    /// when several nodes are open.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn running_mutations(&self) -> Vec<String> {
+        let several = self.state.sessions.len() > 1;
+        let mut running = Vec::new();
+        for session in self.state.all_sessions() {
+            for name in session.tasks.pending_mutations() {
+                running.push(if several {
+                    format!("{}: {}", session.label(), name)
+                } else {
+                    name.to_string()
+                });
+            }
+        }
+        running
+    }
+
+    /// Hold back closing the window while operations that change a node are running, until the
+    /// user confirms with [`Self::begin_shutdown`]. Closing it again while waiting for them
+    /// closes it right away.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn poll_shutdown(&mut self, ctx: &egui::Context) {
+        match self.state.shutdown {
+            // Nothing left to hold the close back for
+            ShutdownState::Confirming if self.running_mutations().is_empty() => {
+                self.close_window(ctx);
+            }
+            ShutdownState::Running | ShutdownState::Confirming => {
+                if ctx.input(|i| i.viewport().close_requested())
+                    && !self.running_mutations().is_empty()
+                {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                    self.state.shutdown = ShutdownState::Confirming;
+                }
+            }
+            ShutdownState::Draining { deadline_ms } => {
+                // Background nodes' results would never be shown, they are only waited for
+                for session in self.state.all_sessions_mut().skip(1) {
+                    session.tasks.poll();
+                }
+                let now = ui::unix_timestamp_millis();
+                if self.running_mutations().is_empty() || now >= deadline_ms {
+                    self.close_window(ctx);
+                } else {
+                    // Each second, for the countdown in the dialog
+                    ctx.request_repaint_after(Duration::from_millis((deadline_ms - now).min(1000)));
+                }
+            }
+            ShutdownState::Closing => {}
         }
     }
 
-    pub fn fetch_payments(&mut self) {
-        if self.state.tasks.payments.is_some() {
-            return;
-        }
-        if let Some(client) = &self.state.client {
-            let client = client.clone();
-            let page_token = self.state.payments_page_token.clone();
-            self.state.tasks.payments = Some(self.spawn_task(async move {
-                client
-                    .list_payments(ListPaymentsRequest { page_token })
-                    .await
-                    .map_err(|e| e.to_string())
-            }));
+    /// Close the window once the user confirmed: read-only requests are cancelled, the others
+    /// get [`SHUTDOWN_GRACE_PERIOD_SECS`] to finish so their outcome is known.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn begin_shutdown(&mut self) {
+        for session in self.state.all_sessions_mut() {
+            let read_only: Vec<_> = session.tasks.pending().filter(|k| !k.is_mutating()).collect();
+            for kind in read_only {
+                session.tasks.remove(kind);
+            }
         }
+        let deadline_ms = ui::unix_timestamp_millis() + SHUTDOWN_GRACE_PERIOD_SECS * 1000;
+        self.state.shutdown = ShutdownState::Draining { deadline_ms };
     }
 
-    pub fn generate_onchain_address(&mut self) {
-        if self.state.tasks.onchain_receive.is_some() {
-            return;
-        }
-        if let Some(client) = &self.state.client {
-            let client = client.clone();
-            self.state.tasks.onchain_receive = Some(self.spawn_task(async move {
-                client.onchain_receive(OnchainReceiveRequest {}).await.map_err(|e| e.to_string())
-            }));
-        }
+    /// Close the window without waiting for anything.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn close_window(&mut self, ctx: &egui::Context) {
+        self.state.shutdown = ShutdownState::Closing;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
     }
 
-    pub fn send_onchain(&mut self) {
-        if self.state.tasks.onchain_send.is_some() {
+    /// Refresh balances periodically after an on-chain send until the total changes.
+    fn poll_balance_refresh(&mut self, ctx: &egui::Context) {
+        let Some(refresh) = &self.state.session.balance_refresh else {
+            return;
+        };
+
+        let now = ui::unix_timestamp_now();
+        let total = self.state.session.balances.as_ref().map(|b| b.data.total_onchain_balance_sats);
+        let changed = refresh.baseline_total_sats.is_some() && total != refresh.baseline_total_sats;
+        if changed || now.saturating_sub(refresh.started_at) >= BALANCE_REFRESH_DURATION_SECS {
+            self.state.session.balance_refresh = None;
             return;
         }
-        if let Some(client) = &self.state.client {
-            let form = &self.state.forms.onchain_send;
-            let address = form.address.trim().to_string();
-            let amount_sats = form.amount_sats.trim().parse::<u64>().ok();
-            let send_all = if form.send_all { Some(true) } else { None };
-            let fee_rate = form.fee_rate_sat_per_vb.trim().parse::<u64>().ok();
 
-            if address.is_empty() {
-                self.state.status_message = Some(StatusMessage::error("Address is required"));
-                return;
+        let due = now.saturating_sub(refresh.last_requested_at) >= BALANCE_REFRESH_INTERVAL_SECS;
+        if due && !self.state.session.tasks.is_pending(TaskKind::Balances) {
+            self.fetch_balances();
+            if let Some(refresh) = &mut self.state.session.balance_refresh {
+                refresh.last_requested_at = now;
             }
-
-            let client = client.clone();
-            self.state.tasks.onchain_send = Some(self.spawn_task(async move {
-                client
-                    .onchain_send(OnchainSendRequest {
-                        address,
-                        amount_sats,
-                        send_all,
-                        fee_rate_sat_per_vb: fee_rate,
-                    })
-                    .await
-                    .map_err(|e| e.to_string())
-            }));
         }
+        ctx.request_repaint_after(Duration::from_secs(BALANCE_REFRESH_INTERVAL_SECS));
     }
 
     pub fn generate_bolt11_invoice(&mut self) {
-        if self.state.tasks.bolt11_receive.is_some() {
+        if self.state.session.tasks.is_pending(TaskKind::Bolt11Receive) {
             return;
         }
-        if let Some(client) = &self.state.client {
+        if let Some(client) = &self.state.session.client {
             let form = &self.state.forms.bolt11_receive;
-            let amount_msat = form.amount_msat.trim().parse::<u64>().ok();
+            let amount_msat = match amount::parse_optional_msat(&form.amount_msat) {
+                Ok(v) => v,
+                Err(e) => {
+                    self.state.status_message = Some(StatusMessage::error(e));
+                    return;
+                }
+            };
             let description = form.description.trim().to_string();
             let expiry_secs = form.expiry_secs.trim().parse::<u32>().unwrap_or(86400);
 
@@ -268,8 +1759,9 @@ impl LdkServerApp {
                 None
             };
 
+            let submitted_form = form.clone();
             let client = client.clone();
-            self.state.tasks.bolt11_receive = Some(self.spawn_task(async move {
+            self.state.session.tasks.insert(TaskKind::Bolt11Receive, self.spawn_task(async move {
                 client
                     .bolt11_receive(Bolt11ReceiveRequest {
                         amount_msat,
@@ -277,19 +1769,26 @@ impl LdkServerApp {
                         expiry_secs,
                     })
                     .await
-                    .map_err(|e| e.to_string())
+                    .map(|response| (response, submitted_form))
+                    .map_err(TaskError::from)
             }));
         }
     }
 
     pub fn send_bolt11(&mut self) {
-        if self.state.tasks.bolt11_send.is_some() {
+        if self.state.session.tasks.is_pending(TaskKind::Bolt11Send) {
             return;
         }
-        if let Some(client) = &self.state.client {
+        if let Some(client) = &self.state.session.client {
             let form = &self.state.forms.bolt11_send;
             let invoice = form.invoice.trim().to_string();
-            let amount_msat = form.amount_msat.trim().parse::<u64>().ok();
+            let amount_msat = match amount::parse_optional_msat(&form.amount_msat) {
+                Ok(v) => v,
+                Err(e) => {
+                    self.state.status_message = Some(StatusMessage::error(e));
+                    return;
+                }
+            };
 
             if invoice.is_empty() {
                 self.state.status_message = Some(StatusMessage::error("Invoice is required"));
@@ -297,23 +1796,62 @@ impl LdkServerApp {
             }
 
             let client = client.clone();
-            self.state.tasks.bolt11_send = Some(self.spawn_task(async move {
+            self.state.session.tasks.insert(TaskKind::Bolt11Send, self.spawn_task(async move {
                 client
                     .bolt11_send(Bolt11SendRequest { invoice, amount_msat, route_parameters: None })
                     .await
-                    .map_err(|e| e.to_string())
+                    .map_err(TaskError::from)
             }));
         }
     }
 
+    /// Resolve the lightning address or LNURL in the BOLT11 send form into an invoice.
+    /// The invoice is only paid once the user confirms it via [`Self::confirm_lnurl_pay`].
+    pub fn resolve_lnurl_pay(&mut self) {
+        if self.state.session.tasks.is_pending(TaskKind::LnurlPay) {
+            return;
+        }
+        let form = &self.state.forms.bolt11_send;
+        let target = form.invoice.trim().to_string();
+        let amount_msat = match amount::parse_optional_msat(&form.amount_msat) {
+            Ok(Some(v)) if v > 0 => v,
+            _ => {
+                self.state.status_message = Some(StatusMessage::error(
+                    "Amount (msat) is required for lightning address payments",
+                ));
+                return;
+            }
+        };
+
+        self.state.pending_lnurl_invoice = None;
+        let handle = self.spawn_task(lnurl::fetch_invoice(target, amount_msat));
+        self.state.session.tasks.insert(TaskKind::LnurlPay, handle);
+    }
+
+    /// Pay the invoice previously fetched by [`Self::resolve_lnurl_pay`].
+    pub fn confirm_lnurl_pay(&mut self) {
+        if let Some(pending) = self.state.pending_lnurl_invoice.take() {
+            self.state.forms.bolt11_send.invoice = pending.invoice;
+            // The invoice already carries the amount
+            self.state.forms.bolt11_send.amount_msat.clear();
+            self.send_bolt11();
+        }
+    }
+
     pub fn generate_bolt12_offer(&mut self) {
-        if self.state.tasks.bolt12_receive.is_some() {
+        if self.state.session.tasks.is_pending(TaskKind::Bolt12Receive) {
             return;
         }
-        if let Some(client) = &self.state.client {
+        if let Some(client) = &self.state.session.client {
             let form = &self.state.forms.bolt12_receive;
             let description = form.description.trim().to_string();
-            let amount_msat = form.amount_msat.trim().parse::<u64>().ok();
+            let amount_msat = match amount::parse_optional_msat(&form.amount_msat) {
+                Ok(v) => v,
+                Err(e) => {
+                    self.state.status_message = Some(StatusMessage::error(e));
+                    return;
+                }
+            };
             let expiry_secs = form.expiry_secs.trim().parse::<u32>().ok();
             let quantity = form.quantity.trim().parse::<u64>().ok();
 
@@ -322,8 +1860,9 @@ impl LdkServerApp {
                 return;
             }
 
+            let submitted_form = form.clone();
             let client = client.clone();
-            self.state.tasks.bolt12_receive = Some(self.spawn_task(async move {
+            self.state.session.tasks.insert(TaskKind::Bolt12Receive, self.spawn_task(async move {
                 client
                     .bolt12_receive(Bolt12ReceiveRequest {
                         description,
@@ -332,19 +1871,26 @@ impl LdkServerApp {
                         quantity,
                     })
                     .await
-                    .map_err(|e| e.to_string())
+                    .map(|response| (response, submitted_form))
+                    .map_err(TaskError::from)
             }));
         }
     }
 
     pub fn send_bolt12(&mut self) {
-        if self.state.tasks.bolt12_send.is_some() {
+        if self.state.session.tasks.is_pending(TaskKind::Bolt12Send) {
             return;
         }
-        if let Some(client) = &self.state.client {
+        if let Some(client) = &self.state.session.client {
             let form = &self.state.forms.bolt12_send;
             let offer = form.offer.trim().to_string();
-            let amount_msat = form.amount_msat.trim().parse::<u64>().ok();
+            let amount_msat = match amount::parse_optional_msat(&form.amount_msat) {
+                Ok(v) => v,
+                Err(e) => {
+                    self.state.status_message = Some(StatusMessage::error(e));
+                    return;
+                }
+            };
             let quantity = form.quantity.trim().parse::<u64>().ok();
             let payer_note = if form.payer_note.trim().is_empty() {
                 None
@@ -358,7 +1904,7 @@ impl LdkServerApp {
             }
 
             let client = client.clone();
-            self.state.tasks.bolt12_send = Some(self.spawn_task(async move {
+            self.state.session.tasks.insert(TaskKind::Bolt12Send, self.spawn_task(async move {
                 client
                     .bolt12_send(Bolt12SendRequest {
                         offer,
@@ -368,16 +1914,16 @@ impl LdkServerApp {
                         route_parameters: None,
                     })
                     .await
-                    .map_err(|e| e.to_string())
+                    .map_err(TaskError::from)
             }));
         }
     }
 
     pub fn open_channel(&mut self) {
-        if self.state.tasks.open_channel.is_some() {
+        if self.state.session.tasks.is_pending(TaskKind::OpenChannel) {
             return;
         }
-        if let Some(client) = &self.state.client {
+        if let Some(client) = &self.state.session.client {
             let form = &self.state.forms.open_channel;
             let node_pubkey = form.node_pubkey.trim().to_string();
             let address = form.address.trim().to_string();
@@ -406,7 +1952,7 @@ impl LdkServerApp {
             }
 
             let client = client.clone();
-            self.state.tasks.open_channel = Some(self.spawn_task(async move {
+            self.state.session.tasks.insert(TaskKind::OpenChannel, self.spawn_task(async move {
                 client
                     .open_channel(OpenChannelRequest {
                         node_pubkey,
@@ -417,16 +1963,16 @@ impl LdkServerApp {
                         announce_channel,
                     })
                     .await
-                    .map_err(|e| e.to_string())
+                    .map_err(TaskError::from)
             }));
         }
     }
 
     pub fn close_channel(&mut self) {
-        if self.state.tasks.close_channel.is_some() {
+        if self.state.session.tasks.is_pending(TaskKind::CloseChannel) {
             return;
         }
-        if let Some(client) = &self.state.client {
+        if let Some(client) = &self.state.session.client {
             let form = &self.state.forms.close_channel;
             let user_channel_id = form.user_channel_id.trim().to_string();
             let counterparty_node_id = form.counterparty_node_id.trim().to_string();
@@ -439,20 +1985,20 @@ impl LdkServerApp {
             }
 
             let client = client.clone();
-            self.state.tasks.close_channel = Some(self.spawn_task(async move {
+            self.state.session.tasks.insert(TaskKind::CloseChannel, self.spawn_task(async move {
                 client
                     .close_channel(CloseChannelRequest { user_channel_id, counterparty_node_id })
                     .await
-                    .map_err(|e| e.to_string())
+                    .map_err(TaskError::from)
             }));
         }
     }
 
     pub fn force_close_channel(&mut self) {
-        if self.state.tasks.force_close_channel.is_some() {
+        if self.state.session.tasks.is_pending(TaskKind::ForceCloseChannel) {
             return;
         }
-        if let Some(client) = &self.state.client {
+        if let Some(client) = &self.state.session.client {
             let form = &self.state.forms.close_channel;
             let user_channel_id = form.user_channel_id.trim().to_string();
             let counterparty_node_id = form.counterparty_node_id.trim().to_string();
@@ -470,7 +2016,7 @@ impl LdkServerApp {
             }
 
             let client = client.clone();
-            self.state.tasks.force_close_channel = Some(self.spawn_task(async move {
+            let handle = self.spawn_task(async move {
                 client
                     .force_close_channel(ForceCloseChannelRequest {
                         user_channel_id,
@@ -478,16 +2024,17 @@ impl LdkServerApp {
                         force_close_reason,
                     })
                     .await
-                    .map_err(|e| e.to_string())
-            }));
+                    .map_err(TaskError::from)
+            });
+            self.state.session.tasks.insert(TaskKind::ForceCloseChannel, handle);
         }
     }
 
     pub fn splice_in(&mut self) {
-        if self.state.tasks.splice_in.is_some() {
+        if self.state.session.tasks.is_pending(TaskKind::SpliceIn) {
             return;
         }
-        if let Some(client) = &self.state.client {
+        if let Some(client) = &self.state.session.client {
             let form = &self.state.forms.splice_in;
             let user_channel_id = form.user_channel_id.trim().to_string();
             let counterparty_node_id = form.counterparty_node_id.trim().to_string();
@@ -508,7 +2055,7 @@ impl LdkServerApp {
             }
 
             let client = client.clone();
-            self.state.tasks.splice_in = Some(self.spawn_task(async move {
+            self.state.session.tasks.insert(TaskKind::SpliceIn, self.spawn_task(async move {
                 client
                     .splice_in(SpliceInRequest {
                         user_channel_id,
@@ -516,16 +2063,16 @@ impl LdkServerApp {
                         splice_amount_sats,
                     })
                     .await
-                    .map_err(|e| e.to_string())
+                    .map_err(TaskError::from)
             }));
         }
     }
 
     pub fn splice_out(&mut self) {
-        if self.state.tasks.splice_out.is_some() {
+        if self.state.session.tasks.is_pending(TaskKind::SpliceOut) {
             return;
         }
-        if let Some(client) = &self.state.client {
+        if let Some(client) = &self.state.session.client {
             let form = &self.state.forms.splice_out;
             let user_channel_id = form.user_channel_id.trim().to_string();
             let counterparty_node_id = form.counterparty_node_id.trim().to_string();
@@ -548,7 +2095,7 @@ impl LdkServerApp {
             }
 
             let client = client.clone();
-            self.state.tasks.splice_out = Some(self.spawn_task(async move {
+            self.state.session.tasks.insert(TaskKind::SpliceOut, self.spawn_task(async move {
                 client
                     .splice_out(SpliceOutRequest {
                         user_channel_id,
@@ -557,16 +2104,18 @@ impl LdkServerApp {
                         splice_amount_sats,
                     })
                     .await
-                    .map_err(|e| e.to_string())
+                    .map_err(TaskError::from)
             }));
         }
     }
 
     pub fn update_channel_config(&mut self) {
-        if self.state.tasks.update_channel_config.is_some() {
+        if self.state.session.tasks.is_pending(TaskKind::UpdateChannelConfig)
+            || self.state.session.tasks.is_pending(TaskKind::UpdateAllChannelConfigs)
+        {
             return;
         }
-        if let Some(client) = &self.state.client {
+        if let Some(client) = &self.state.session.client {
             let form = &self.state.forms.update_channel_config;
             let user_channel_id = form.user_channel_id.trim().to_string();
             let counterparty_node_id = form.counterparty_node_id.trim().to_string();
@@ -584,6 +2133,10 @@ impl LdkServerApp {
                 max_dust_htlc_exposure: None,
             };
 
+            if form.all_channels {
+                self.update_all_channel_configs(channel_config);
+                return;
+            }
             if user_channel_id.is_empty() || counterparty_node_id.is_empty() {
                 self.state.status_message = Some(StatusMessage::error(
                     "Channel ID and counterparty node ID are required",
@@ -592,7 +2145,7 @@ impl LdkServerApp {
             }
 
             let client = client.clone();
-            self.state.tasks.update_channel_config = Some(self.spawn_task(async move {
+            let handle = self.spawn_task(async move {
                 client
                     .update_channel_config(UpdateChannelConfigRequest {
                         user_channel_id,
@@ -600,16 +2153,54 @@ impl LdkServerApp {
                         channel_config: Some(channel_config),
                     })
                     .await
-                    .map_err(|e| e.to_string())
-            }));
+                    .map_err(TaskError::from)
+            });
+            self.state.session.tasks.insert(TaskKind::UpdateChannelConfig, handle);
+        }
+    }
+
+    /// Apply `channel_config` to every listed channel, one at a time.
+    fn update_all_channel_configs(&mut self, channel_config: ChannelConfig) {
+        let Some(client) = self.state.session.client.clone() else {
+            return;
+        };
+        let channels: Vec<(String, String)> = self
+            .state
+            .session
+            .channels
+            .iter()
+            .flat_map(|list| &list.data.channels)
+            .map(|ch| (ch.user_channel_id.clone(), ch.counterparty_node_id.clone()))
+            .collect();
+        if channels.is_empty() {
+            self.state.status_message = Some(StatusMessage::error("No channels to update"));
+            return;
         }
+
+        let timeout = self.request_timeout();
+        let handle = self.spawn_task_with_progress(move |progress| async move {
+            let total = channels.len() as u32;
+            for (done, (user_channel_id, counterparty_node_id)) in channels.into_iter().enumerate()
+            {
+                let message = format!("Updating {}", ui::truncate_id(&user_channel_id, 6, 4));
+                progress.report(done as u32, total, message);
+                let request = UpdateChannelConfigRequest {
+                    user_channel_id,
+                    counterparty_node_id,
+                    channel_config: Some(channel_config.clone()),
+                };
+                task::with_timeout(client.update_channel_config(request), timeout).await?;
+            }
+            Ok::<_, TaskError>(total)
+        });
+        self.state.session.tasks.insert_with_progress(TaskKind::UpdateAllChannelConfigs, handle);
     }
 
     pub fn connect_peer(&mut self) {
-        if self.state.tasks.connect_peer.is_some() {
+        if self.state.session.tasks.is_pending(TaskKind::ConnectPeer) {
             return;
         }
-        if let Some(client) = &self.state.client {
+        if let Some(client) = &self.state.session.client {
             let form = &self.state.forms.connect_peer;
             let node_pubkey = form.node_pubkey.trim().to_string();
             let address = form.address.trim().to_string();
@@ -622,134 +2213,478 @@ impl LdkServerApp {
             }
 
             let client = client.clone();
-            self.state.tasks.connect_peer = Some(self.spawn_task(async move {
+            self.state.session.tasks.insert(TaskKind::ConnectPeer, self.spawn_task(async move {
                 client
                     .connect_peer(ConnectPeerRequest { node_pubkey, address, persist })
                     .await
-                    .map_err(|e| e.to_string())
+                    .map_err(TaskError::from)
             }));
         }
     }
 
     fn poll_tasks(&mut self, _ctx: &egui::Context) {
-        macro_rules! poll_task {
-            ($task:expr => |$val:ident| $handler:expr) => {
-                if let Some(t) = &mut $task {
-                    if let Some(res) = t.try_take() {
-                        $task = None;
-                        match res {
-                            Ok($val) => {
-                                $handler
-                            }
-                            Err(e) => {
-                                self.state.status_message = Some(StatusMessage::error(e));
-                            }
+        self.state.session.tasks.discard_stale(self.state.session.connection_generation);
+        // Tasks request a repaint when they complete, so results are handled on the next frame
+        for (kind, progress) in self.state.session.tasks.take_progress() {
+            self.state.session.task_progress.insert(kind, progress);
+        }
+        let now_ms = ui::unix_timestamp_millis();
+        for (kind, result) in self.state.session.tasks.poll() {
+            self.state.session.refresh.mark_completed(kind, now_ms);
+            self.complete_task(kind, result);
+        }
+        for timing in self.state.session.tasks.take_timings() {
+            self.state.task_timings.push(timing);
+        }
+        self.run_queued_requests();
+        let session = &mut self.state.session;
+        let tasks = &session.tasks;
+        session.task_progress.retain(|kind, _| tasks.is_pending(*kind));
+    }
+
+    /// Start the requests that were queued behind a task of the same kind that has now finished.
+    fn run_queued_requests(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let queued: Option<ChainSourceConfig> =
+                self.state.session.tasks.take_queued(TaskKind::ChainSourceCheck);
+            if queued.is_some() {
+                self.check_chain_source();
+            }
+        }
+    }
+
+    /// Handle the result of a finished task. Server requests report failures in the status bar
+    /// and count them towards the connection health; the other kinds handle their own errors.
+    fn complete_task(&mut self, kind: TaskKind, result: Result<TaskOutput, TaskError>) {
+        let output = match (kind, result) {
+            // Cancelled from the UI, which already told the user
+            (_, Err(TaskError::Cancelled)) => return,
+
+            (TaskKind::Connect, result) => {
+                let result: Result<(Arc<LdkServerClient>, GetNodeInfoResponse), _> =
+                    result.map(TaskOutput::take);
+                match result {
+                    Ok((client, node_info)) => {
+                        self.state.reauthenticating = false;
+                        self.state.session.client = Some(client);
+                        self.check_network(&node_info);
+                        self.state.session.node_info = Some(Cached::new(node_info));
+                        self.check_wallet_syncs();
+                        self.check_block_lag();
+                        self.state.session.connection_status = ConnectionStatus::Connected;
+                        self.state.session.connection_health = Default::default();
+                        self.state.cors_blocked = false;
+                        self.state.status_message = Some(StatusMessage::success("Connected"));
+                        let url = self.state.session.server_url.clone();
+                        self.state.remember_server_url(&url);
+                        self.settings_changed();
+                        self.sync_all();
+                    }
+                    Err(e) => {
+                        let message = errors::describe(&e);
+                        self.state.cors_blocked =
+                            errors::classify(&e) == Some(ErrorKind::CrossOriginBlocked);
+                        if std::mem::take(&mut self.state.reauthenticating)
+                            && matches!(e, TaskError::Auth { .. })
+                        {
+                            self.state.session.connection_status = ConnectionStatus::AuthRequired;
+                            self.state.reauth_error = Some(message);
+                        } else {
+                            self.state.session.connection_status =
+                                ConnectionStatus::Error(message.clone());
+                            self.state.status_message = Some(StatusMessage::error(message));
                         }
                     }
-                    // Note: repaint is handled by update() with request_repaint_after()
                 }
-            };
-        }
-
-        poll_task!(self.state.tasks.node_info => |v| {
-            self.state.node_info = Some(v);
-        });
+                return;
+            }
 
-        poll_task!(self.state.tasks.balances => |v| {
-            self.state.balances = Some(v);
-        });
+            // Best effort; an inconclusive check just leaves the banner hidden
+            (TaskKind::NetworkCheck, result) => {
+                self.state.session.confirmed_network = result.ok().map(TaskOutput::take);
+                return;
+            }
 
-        poll_task!(self.state.tasks.channels => |v| {
-            self.state.channels = Some(v);
-        });
+            // Health check failures only change the connection status, never the status bar
+            (TaskKind::HealthCheck, result) => {
+                let health = &mut self.state.session.connection_health;
+                match result.map(TaskOutput::take) {
+                    Ok(latency_ms) => {
+                        health.latency_ms = Some(latency_ms);
+                        health.last_success_at = Some(ui::unix_timestamp_now());
+                        health.consecutive_failures = 0;
+                        if matches!(
+                            self.state.session.connection_status,
+                            ConnectionStatus::Reconnecting { .. }
+                        ) {
+                            self.state.session.connection_status = ConnectionStatus::Connected;
+                            self.state.status_message = Some(StatusMessage::success("Reconnected"));
+                            self.sync_all();
+                        }
+                    }
+                    Err(e) => {
+                        if errors::classify(&e) == Some(ErrorKind::AuthFailed) {
+                            self.require_reauth();
+                        } else if matches!(
+                            self.state.session.connection_status,
+                            ConnectionStatus::Reconnecting { .. }
+                        ) {
+                            self.schedule_reconnect();
+                        } else {
+                            self.note_connection_failure();
+                        }
+                    }
+                }
+                return;
+            }
 
-        poll_task!(self.state.tasks.payments => |v| {
-            self.state.payments_page_token = v.next_page_token.clone();
-            self.state.payments = Some(v);
-        });
+            // Confirmation lookups are best-effort and retried, so failures are shown inline
+            (TaskKind::TxConfirmations, result) => {
+                if let Some(watch) = &mut self.state.tx_watch {
+                    match result.map(TaskOutput::take::<(String, u32)>) {
+                        Ok((txid, confirmations)) if txid == watch.txid => {
+                            watch.confirmations = Some(confirmations);
+                            watch.error = None;
+                        }
+                        Ok(_) => {}
+                        Err(e) => watch.error = Some(e.to_string()),
+                    }
+                }
+                return;
+            }
 
-        poll_task!(self.state.tasks.onchain_receive => |v| {
-            self.state.onchain_address = Some(v.address);
-            self.state.status_message = Some(StatusMessage::success("Address generated"));
-        });
+            // Each part is handled as if fetched alone, but a failed sync counts once towards the
+            // connection health, and the status bar sums up the outcome of every part
+            (TaskKind::SyncAll, Ok(output)) => {
+                let now_ms = ui::unix_timestamp_millis();
+                let mut failures = Vec::new();
+                for (kind, part) in output.take::<SyncAll>().into_parts() {
+                    self.state.session.refresh.mark_completed(kind, now_ms);
+                    match part {
+                        Ok(output) => self.complete_task(kind, Ok(output)),
+                        Err(e) => failures.push((kind, e)),
+                    }
+                }
+                if let Some((kind, e)) = failures.first() {
+                    self.complete_task(*kind, Err(e.clone()));
+                }
+                self.state.status_message = Some(sync_status(&failures));
+                return;
+            }
 
-        poll_task!(self.state.tasks.onchain_send => |v| {
-            self.state.last_txid = Some(v.txid.clone());
-            self.state.status_message = Some(StatusMessage::success(format!("Sent! TXID: {}", v.txid)));
-            self.state.forms.onchain_send = Default::default();
-        });
+            // Fee estimates are best-effort, so failures are shown inline rather than in the
+            // status bar
+            (TaskKind::FeeEstimates, result) => {
+                match result.map(TaskOutput::take) {
+                    Ok(estimates) => self.state.fee_estimates = Some(estimates),
+                    Err(e) => self.state.fee_estimates_error = Some(e.to_string()),
+                }
+                return;
+            }
 
-        poll_task!(self.state.tasks.bolt11_receive => |v| {
-            self.state.generated_invoice = Some(v.invoice);
-            self.state.status_message = Some(StatusMessage::success("Invoice generated"));
-        });
+            // Tip lookups are best-effort: a failure leaves the lag unchecked until the next one,
+            // and the error is left to the log
+            (TaskKind::PublicTip, result) => {
+                if let Ok(height) = result.map(TaskOutput::take) {
+                    self.state.session.public_tip_height = Some(height);
+                    self.check_block_lag();
+                }
+                return;
+            }
 
-        poll_task!(self.state.tasks.bolt11_send => |v| {
-            self.state.last_payment_id = Some(v.payment_id.clone());
-            self.state.status_message = Some(StatusMessage::success(format!("Payment sent! ID: {}", v.payment_id)));
-            self.state.forms.bolt11_send = Default::default();
-        });
+            // Fiat values are an extra: on failure amounts are only shown in sats until the next
+            // attempt, and the error is left to the log
+            (TaskKind::FiatRate, result) => {
+                if let Ok(rate) = result.map(TaskOutput::take) {
+                    self.state.fiat_rate = Some(rate);
+                }
+                return;
+            }
 
-        poll_task!(self.state.tasks.bolt12_receive => |v| {
-            self.state.generated_offer = Some(v.offer);
-            self.state.status_message = Some(StatusMessage::success("Offer generated"));
-        });
+            // Chain source checks are unrelated to the server connection, so they are shown next
+            // to the editor and never count as connection failures
+            (TaskKind::ChainSourceCheck, result) => {
+                self.state.chain_source_check_result =
+                    Some(result.map(TaskOutput::take).map_err(|e| e.to_string()));
+                return;
+            }
 
-        poll_task!(self.state.tasks.bolt12_send => |v| {
-            self.state.last_payment_id = Some(v.payment_id.clone());
-            self.state.status_message = Some(StatusMessage::success(format!("Payment sent! ID: {}", v.payment_id)));
-            self.state.forms.bolt12_send = Default::default();
-        });
+            // Fetched config problems go to the config error dialog like pasted ones
+            (TaskKind::ConfigFetch, result) => {
+                match result.map(TaskOutput::take::<(String, String)>) {
+                    Ok((url, contents)) => self.load_config_text(url, &contents),
+                    Err(e) => {
+                        let url = self.state.config_url.trim().to_string();
+                        self.state.set_config_load_error(url, e.to_string());
+                    }
+                }
+                return;
+            }
 
-        poll_task!(self.state.tasks.open_channel => |v| {
-            self.state.last_channel_id = Some(v.user_channel_id.clone());
-            self.state.status_message = Some(StatusMessage::success(format!("Channel opened! ID: {}", v.user_channel_id)));
-            self.state.forms.open_channel = Default::default();
-            self.state.show_open_channel_dialog = false;
-            self.fetch_channels();
-        });
+            // The invoice comes from a third-party LNURL server, so its failures say nothing about
+            // the connection to ldk-server
+            (TaskKind::LnurlPay, result) => {
+                match result.map(TaskOutput::take) {
+                    Ok(invoice) => {
+                        self.state.pending_lnurl_invoice = Some(invoice);
+                        self.state.status_message =
+                            Some(StatusMessage::success("Invoice received, please confirm"));
+                    }
+                    Err(e) => {
+                        self.state.status_message = Some(StatusMessage::error(e.to_string()));
+                    }
+                }
+                return;
+            }
 
-        poll_task!(self.state.tasks.close_channel => |_v| {
-            self.state.status_message = Some(StatusMessage::success("Channel close initiated"));
-            self.state.forms.close_channel = Default::default();
-            self.state.show_close_channel_dialog = false;
-            self.fetch_channels();
-        });
+            (TaskKind::SaveFile, result) => {
+                match result.map(TaskOutput::take::<Option<String>>) {
+                    Ok(Some(location)) => {
+                        self.state.status_message =
+                            Some(StatusMessage::success(format!("Saved {}", location)));
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.state.status_message = Some(StatusMessage::error(e.to_string()));
+                    }
+                }
+                return;
+            }
 
-        poll_task!(self.state.tasks.force_close_channel => |_v| {
-            self.state.status_message = Some(StatusMessage::success("Force close initiated"));
-            self.state.forms.close_channel = Default::default();
-            self.state.show_close_channel_dialog = false;
-            self.fetch_channels();
-        });
+            (TaskKind::ConfigUpload, result) => {
+                match result.map(TaskOutput::take::<Option<(String, String)>>) {
+                    Ok(Some((name, contents))) => self.load_config_text(name, &contents),
+                    Ok(None) => {}
+                    Err(e) => {
+                        let source = "the uploaded file".to_string();
+                        self.state.set_config_load_error(source, e.to_string());
+                    }
+                }
+                return;
+            }
 
-        poll_task!(self.state.tasks.splice_in => |_v| {
-            self.state.status_message = Some(StatusMessage::success("Splice-in initiated"));
-            self.state.forms.splice_in = Default::default();
-            self.state.show_splice_in_dialog = false;
-            self.fetch_channels();
-        });
+            (_, Err(e)) => {
+                let classified = errors::classify(&e);
+                self.state.cors_blocked = classified == Some(ErrorKind::CrossOriginBlocked);
+                match classified {
+                    Some(ErrorKind::AuthFailed) => self.require_reauth(),
+                    Some(error_kind) if error_kind.is_connection_failure() => {
+                        self.note_connection_failure()
+                    }
+                    _ => {}
+                }
+                self.state.status_message = Some(StatusMessage::error(errors::describe(&e)));
+                return;
+            }
+            (_, Ok(output)) => output,
+        };
+        self.state.session.connection_health.consecutive_failures = 0;
 
-        poll_task!(self.state.tasks.splice_out => |v| {
-            self.state.status_message = Some(StatusMessage::success(format!("Splice-out initiated to {}", v.address)));
-            self.state.forms.splice_out = Default::default();
-            self.state.show_splice_out_dialog = false;
-            self.fetch_channels();
-        });
+        match kind {
+            TaskKind::NodeInfo => {
+                self.state.session.node_info = Some(Cached::new(output.take()));
+                self.check_wallet_syncs();
+                self.check_block_lag();
+            }
+            TaskKind::Balances => {
+                let balances: GetBalancesResponse = output.take();
+                let session = &mut self.state.session;
+                session.balance_changes = session
+                    .balances
+                    .as_ref()
+                    .map(|previous| BalanceChanges::between(&previous.data, &balances));
+                session.balances = Some(Cached::new(balances));
+            }
+            TaskKind::Channels => {
+                self.state.session.channels = Some(Cached::new(output.take()));
+                self.check_liquidity(true);
+            }
+            TaskKind::Payments => {
+                let (response, is_continuation): (ListPaymentsResponse, bool) = output.take();
+                self.state.session.payments_page_token = response.next_page_token.clone();
+                match &mut self.state.session.payments {
+                    Some(loaded) if is_continuation => {
+                        loaded.data.payments.extend(response.payments);
+                        loaded.data.next_page_token = response.next_page_token;
+                    }
+                    _ => self.state.session.payments = Some(Cached::new(response)),
+                }
+            }
+            TaskKind::OnchainReceive => {
+                let (response, label): (OnchainReceiveResponse, String) = output.take();
+                self.show_onchain_address(response.address.clone());
+                if !label.is_empty() {
+                    if let Some(entry) = self
+                        .state
+                        .address_history
+                        .iter_mut()
+                        .find(|a| a.address == response.address)
+                    {
+                        entry.label = label;
+                        self.address_history_changed();
+                    }
+                    self.state.forms.onchain_receive.address_label.clear();
+                }
+                self.state.status_message = Some(StatusMessage::success("Address generated"));
+            }
+            TaskKind::OnchainSend => {
+                let response: OnchainSendResponse = output.take();
+                self.state.last_txid = Some(response.txid.clone());
+                self.state.status_message =
+                    Some(StatusMessage::success(format!("Sent! TXID: {}", response.txid)));
+                let form = std::mem::take(&mut self.state.forms.onchain_send);
+                self.state.last_sent_address = Some(form.address.trim().to_string());
+                self.watch_last_txid();
+
+                let now = ui::unix_timestamp_now();
+                self.state.session.balance_refresh = Some(BalanceRefresh {
+                    started_at: now,
+                    last_requested_at: now,
+                    baseline_total_sats: self
+                        .state
+                        .session
+                        .balances
+                        .as_ref()
+                        .map(|b| b.data.total_onchain_balance_sats),
+                });
+                self.fetch_balances();
+            }
+            TaskKind::Bolt11Receive => {
+                let (response, form): (Bolt11ReceiveResponse, _) = output.take();
+                self.state.generated_invoice = Some(response.invoice);
+                self.state.generated_invoice_form = Some(form);
+                self.state.status_message = Some(StatusMessage::success("Invoice generated"));
+            }
+            TaskKind::Bolt11Send => {
+                let response: Bolt11SendResponse = output.take();
+                self.state.last_payment_id = Some(response.payment_id.clone());
+                self.state.status_message = Some(StatusMessage::success(format!(
+                    "Payment sent! ID: {}",
+                    response.payment_id
+                )));
+                self.state.forms.bolt11_send = Default::default();
+            }
+            TaskKind::Bolt12Receive => {
+                let (response, form): (Bolt12ReceiveResponse, _) = output.take();
+                self.state.generated_offer = Some(response.offer);
+                self.state.generated_offer_form = Some(form);
+                self.state.status_message = Some(StatusMessage::success("Offer generated"));
+            }
+            TaskKind::Bolt12Send => {
+                let response: Bolt12SendResponse = output.take();
+                self.state.last_payment_id = Some(response.payment_id.clone());
+                self.state.status_message = Some(StatusMessage::success(format!(
+                    "Payment sent! ID: {}",
+                    response.payment_id
+                )));
+                self.state.forms.bolt12_send = Default::default();
+            }
+            TaskKind::OpenChannel => {
+                let response: OpenChannelResponse = output.take();
+                self.state.last_channel_id = Some(response.user_channel_id.clone());
+                self.state.status_message = Some(StatusMessage::success(format!(
+                    "Channel opened! ID: {}",
+                    response.user_channel_id
+                )));
+                self.state.forms.open_channel = Default::default();
+                self.state.show_open_channel_dialog = false;
+                self.fetch_channels();
+            }
+            TaskKind::CloseChannel => {
+                self.state.status_message = Some(StatusMessage::success("Channel close initiated"));
+                self.state.forms.close_channel = Default::default();
+                self.state.show_close_channel_dialog = false;
+                self.fetch_channels();
+            }
+            TaskKind::ForceCloseChannel => {
+                self.state.status_message = Some(StatusMessage::success("Force close initiated"));
+                self.state.forms.close_channel = Default::default();
+                self.state.show_close_channel_dialog = false;
+                self.fetch_channels();
+            }
+            TaskKind::SpliceIn => {
+                self.state.status_message = Some(StatusMessage::success("Splice-in initiated"));
+                self.state.forms.splice_in = Default::default();
+                self.state.show_splice_in_dialog = false;
+                self.fetch_channels();
+            }
+            TaskKind::SpliceOut => {
+                let response: SpliceOutResponse = output.take();
+                self.state.status_message = Some(StatusMessage::success(format!(
+                    "Splice-out initiated to {}",
+                    response.address
+                )));
+                self.state.forms.splice_out = Default::default();
+                self.state.show_splice_out_dialog = false;
+                self.fetch_channels();
+            }
+            TaskKind::UpdateChannelConfig => {
+                self.state.status_message = Some(StatusMessage::success("Channel config updated"));
+                self.state.forms.update_channel_config = Default::default();
+                self.state.show_update_config_dialog = false;
+                self.fetch_channels();
+            }
+            TaskKind::UpdateAllChannelConfigs => {
+                let updated: u32 = output.take();
+                self.state.status_message = Some(StatusMessage::success(format!(
+                    "Config updated on {} channel(s)",
+                    updated
+                )));
+                self.state.forms.update_channel_config = Default::default();
+                self.state.show_update_config_dialog = false;
+                self.fetch_channels();
+            }
+            TaskKind::ConnectPeer => {
+                self.state.status_message =
+                    Some(StatusMessage::success("Peer connected successfully"));
+                self.state.forms.connect_peer = Default::default();
+                self.state.show_connect_peer_dialog = false;
+            }
+            // Handled above
+            TaskKind::Connect
+            | TaskKind::NetworkCheck
+            | TaskKind::HealthCheck
+            | TaskKind::TxConfirmations
+            | TaskKind::FeeEstimates
+            | TaskKind::PublicTip
+            | TaskKind::FiatRate
+            | TaskKind::ChainSourceCheck
+            | TaskKind::ConfigFetch
+            | TaskKind::LnurlPay
+            | TaskKind::ConfigUpload
+            | TaskKind::SaveFile
+            | TaskKind::SyncAll => {}
+        }
+    }
+}
 
-        poll_task!(self.state.tasks.update_channel_config => |_v| {
-            self.state.status_message = Some(StatusMessage::success("Channel config updated"));
-            self.state.forms.update_channel_config = Default::default();
-            self.state.show_update_config_dialog = false;
-            self.fetch_channels();
-        });
+/// Status bar message summing up a sync: the parts that failed, with the first error.
+fn sync_status(failures: &[(TaskKind, TaskError)]) -> StatusMessage {
+    let Some((_, first)) = failures.first() else {
+        return StatusMessage::success("Synced node info, balances, channels and payments");
+    };
+    let parts: Vec<_> = failures
+        .iter()
+        .map(|(kind, _)| match kind {
+            TaskKind::NodeInfo => "node info",
+            TaskKind::Balances => "balances",
+            TaskKind::Channels => "channels",
+            _ => "payments",
+        })
+        .collect();
+    StatusMessage::error(format!(
+        "Could not sync {}: {}",
+        parts.join(", "),
+        errors::describe(first)
+    ))
+}
 
-        poll_task!(self.state.tasks.connect_peer => |_v| {
-            self.state.status_message = Some(StatusMessage::success("Peer connected successfully"));
-            self.state.forms.connect_peer = Default::default();
-            self.state.show_connect_peer_dialog = false;
-        });
-    }
+/// Fetch node info as a liveness check, resolving to the round-trip time in milliseconds.
+async fn ping(client: Arc<LdkServerClient>) -> Result<u64, TaskError> {
+    let start = ui::unix_timestamp_millis();
+    client.get_node_info(GetNodeInfoRequest {}).await?;
+    Ok(ui::unix_timestamp_millis().saturating_sub(start))
 }
 
 fn build_channel_config(fee_prop: &str, fee_base: &str, cltv: &str) -> Option<ChannelConfig> {
@@ -771,52 +2706,136 @@ fn build_channel_config(fee_prop: &str, fee_base: &str, cltv: &str) -> Option<Ch
     })
 }
 
+/// Whether the user can see the app: natively when its window has focus, on the web while the
+/// page is visible. Resources due meanwhile are refreshed as soon as it is back.
+fn in_foreground(ctx: &egui::Context) -> bool {
+    #[cfg(target_arch = "wasm32")]
+    let foreground = {
+        let _ = ctx;
+        let document = web_sys::window().and_then(|window| window.document());
+        document.map_or(true, |document| !document.hidden())
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let foreground = ctx.input(|i| i.focused);
+
+    foreground
+}
+
+/// The navigation as a menu, for compact mode where there is no side panel.
+fn render_nav_menu(ui: &mut egui::Ui, state: &mut AppState) {
+    let current = NAV_TABS.iter().find(|(tab, _)| *tab == state.active_tab).map_or("", |t| t.1);
+    ui.menu_button(format!("☰ {}", current), |ui| {
+        for (tab, label) in NAV_TABS {
+            if ui.selectable_label(state.active_tab == tab, label).clicked() {
+                state.active_tab = tab;
+                ui.close_menu();
+            }
+        }
+        ui.separator();
+        render_doc_links(ui);
+    });
+}
+
+fn render_doc_links(ui: &mut egui::Ui) {
+    ui.hyperlink_to("LDK Server", "https://github.com/lightningdevkit/ldk-server");
+    ui.hyperlink_to("LDK Node", "https://docs.rs/ldk-node/latest/ldk_node/");
+    ui.hyperlink_to("Rust Lightning", "https://docs.rs/lightning/latest/lightning/");
+    ui.hyperlink_to("BDK", "https://docs.rs/bdk_wallet/latest/bdk_wallet/");
+}
+
 impl App for LdkServerApp {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save(&mut self, eframe_storage: &mut dyn eframe::Storage) {
+        self.save_to(eframe_storage);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
+        amount::set_display_unit(self.state.display_unit);
+        ui::layout::apply_spacing(ctx);
         self.poll_tasks(ctx);
-
-        if self.state.tasks.any_pending() {
-            ctx.request_repaint_after(Duration::from_millis(100));
+        #[cfg(not(target_arch = "wasm32"))]
+        self.poll_shutdown(ctx);
+        self.poll_reconnect(ctx);
+        self.poll_refresh_schedule(ctx);
+        self.poll_background_sessions(ctx);
+        self.poll_tx_watch(ctx);
+        self.poll_balance_refresh(ctx);
+        self.handle_dropped_files(ctx);
+        #[cfg(target_arch = "wasm32")]
+        self.poll_auto_save();
+        #[cfg(target_arch = "wasm32")]
+        if let Some(e) = crate::clipboard::take_failure() {
+            self.state.status_message = Some(StatusMessage::error(e));
         }
 
+        // Narrow windows get the navigation as a menu in the top bar, which wraps
+        let compact = ui::layout::is_compact(ctx);
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                ui.heading("LDK Server GUI");
+            ui.horizontal_wrapped(|ui| {
+                if compact {
+                    render_nav_menu(ui, &mut self.state);
+                } else {
+                    ui.heading("LDK Server GUI");
+                }
                 ui.separator();
+                ui::connection::render_node_switcher(ui, self);
+                ui::connection::render_sync_button(ui, self);
                 ui::connection::render_status(ui, &self.state);
+                ui::connection::render_health_status(ui, &self.state);
+                ui::connection::render_request_activity(ui, &self.limiter);
+                if compact {
+                    ui::connection::render_display_unit_toggle(ui, self);
+                } else {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui::connection::render_display_unit_toggle(ui, self);
+                    });
+                }
             });
         });
 
-        egui::SidePanel::left("nav_panel").resizable(false).default_width(140.0).show(ctx, |ui| {
-            ui.add_space(10.0);
-            ui.heading("Navigation");
-            ui.separator();
-
-            let tabs = [
-                (ActiveTab::NodeInfo, "Node Info"),
-                (ActiveTab::Balances, "Balances"),
-                (ActiveTab::Channels, "Channels"),
-                (ActiveTab::Payments, "Payment History"),
-                (ActiveTab::Lightning, "Lightning"),
-                (ActiveTab::Onchain, "On-chain"),
-            ];
+        if self.network_mismatch().is_some() {
+            egui::TopBottomPanel::top("network_warning_panel").show(ctx, |ui| {
+                ui::connection::render_network_mismatch(ui, self);
+            });
+        }
 
-            for (tab, label) in tabs {
-                if ui.selectable_label(self.state.active_tab == tab, label).clicked() {
-                    self.state.active_tab = tab;
+        let connected = matches!(self.state.session.connection_status, ConnectionStatus::Connected);
+        let session = &self.state.session;
+        if connected && (!session.stale_wallet_syncs.is_empty() || session.blocks_behind.is_some())
+        {
+            egui::TopBottomPanel::top("stale_sync_panel").show(ctx, |ui| {
+                if !self.state.session.stale_wallet_syncs.is_empty() {
+                    ui::connection::render_stale_sync_warning(ui, self);
                 }
-            }
+                if let Some(blocks) = self.state.session.blocks_behind {
+                    ui::connection::render_block_lag_warning(ui, self, blocks);
+                }
+            });
+        }
 
-            ui.add_space(20.0);
-            ui.separator();
-            ui.label(egui::RichText::new("Documentation").small().strong());
-            ui.add_space(5.0);
+        ui::connection::render_reauth_dialog(ctx, self);
 
-            ui.hyperlink_to("LDK Server", "https://github.com/lightningdevkit/ldk-server");
-            ui.hyperlink_to("LDK Node", "https://docs.rs/ldk-node/latest/ldk_node/");
-            ui.hyperlink_to("Rust Lightning", "https://docs.rs/lightning/latest/lightning/");
-            ui.hyperlink_to("BDK", "https://docs.rs/bdk_wallet/latest/bdk_wallet/");
-        });
+        if !compact {
+            let nav_panel = egui::SidePanel::left("nav_panel").resizable(false);
+            nav_panel.default_width(140.0).show(ctx, |ui| {
+                ui.add_space(10.0);
+                ui.heading("Navigation");
+                ui.separator();
+
+                for (tab, label) in NAV_TABS {
+                    if ui.selectable_label(self.state.active_tab == tab, label).clicked() {
+                        self.state.active_tab = tab;
+                    }
+                }
+
+                ui.add_space(20.0);
+                ui.separator();
+                ui.label(egui::RichText::new("Documentation").small().strong());
+                ui.add_space(5.0);
+                render_doc_links(ui);
+            });
+        }
 
         egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
@@ -829,21 +2848,40 @@ impl App for LdkServerApp {
                 } else {
                     ui.label("Ready");
                 }
+                #[cfg(target_arch = "wasm32")]
+                if self.state.cors_blocked && ui.link("How to allow this page…").clicked() {
+                    self.state.show_cors_help = true;
+                }
             });
         });
 
         egui::CentralPanel::default().show(ctx, |ui| {
             match self.state.active_tab {
+                ActiveTab::Overview => ui::overview::render(ui, self),
                 ActiveTab::NodeInfo => ui::node_info::render(ui, self),
                 ActiveTab::Balances => ui::balances::render(ui, self),
                 ActiveTab::Channels => ui::channels::render(ui, self),
                 ActiveTab::Payments => ui::payments::render(ui, self),
                 ActiveTab::Lightning => ui::lightning::render(ui, self),
                 ActiveTab::Onchain => ui::onchain::render(ui, self),
+                ActiveTab::Logs => ui::logs::render(ui, self),
             }
         });
 
         ui::channels::render_dialogs(ctx, self);
+        ui::lightning::render_dialogs(ctx, self);
+        ui::onchain::render_dialogs(ctx, self);
+        #[cfg(target_arch = "wasm32")]
         ui::connection::render_load_config_dialog(ctx, self);
+        #[cfg(target_arch = "wasm32")]
+        ui::connection::render_cors_help_dialog(ctx, self);
+        ui::connection::render_config_error_dialog(ctx, self);
+        #[cfg(not(target_arch = "wasm32"))]
+        ui::connection::render_config_save_dialog(ctx, self);
+        #[cfg(not(target_arch = "wasm32"))]
+        ui::connection::render_new_config_wizard(ctx, self);
+        #[cfg(not(target_arch = "wasm32"))]
+        ui::connection::render_shutdown_dialog(ctx, self);
+        ui::connection::render_drop_overlay(ctx);
     }
 }
@@ -0,0 +1,84 @@
+//! Changes in the node's balances between two refreshes.
+
+use ldk_server_client::ldk_server_protos::api::GetBalancesResponse;
+
+/// Change of each balance figure since the previous refresh, in sats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BalanceChanges {
+    pub total_onchain: i64,
+    pub spendable_onchain: i64,
+    pub anchor_reserve: i64,
+    pub total_lightning: i64,
+}
+
+impl BalanceChanges {
+    /// Changes from `previous` to `current`.
+    pub fn between(previous: &GetBalancesResponse, current: &GetBalancesResponse) -> Self {
+        Self {
+            total_onchain: change(
+                previous.total_onchain_balance_sats,
+                current.total_onchain_balance_sats,
+            ),
+            spendable_onchain: change(
+                previous.spendable_onchain_balance_sats,
+                current.spendable_onchain_balance_sats,
+            ),
+            anchor_reserve: change(
+                previous.total_anchor_channels_reserve_sats,
+                current.total_anchor_channels_reserve_sats,
+            ),
+            total_lightning: change(
+                previous.total_lightning_balance_sats,
+                current.total_lightning_balance_sats,
+            ),
+        }
+    }
+
+    /// Change of the on-chain and lightning totals combined.
+    pub fn total(&self) -> i64 {
+        self.total_onchain.saturating_add(self.total_lightning)
+    }
+}
+
+/// Signed difference between two amounts, saturating at the bounds of `i64`.
+fn change(previous: u64, current: u64) -> i64 {
+    if current >= previous {
+        i64::try_from(current - previous).unwrap_or(i64::MAX)
+    } else {
+        i64::try_from(previous - current).map_or(i64::MIN, |decrease| -decrease)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balances(onchain: u64, spendable: u64, lightning: u64) -> GetBalancesResponse {
+        GetBalancesResponse {
+            total_onchain_balance_sats: onchain,
+            spendable_onchain_balance_sats: spendable,
+            total_lightning_balance_sats: lightning,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_balance_changes() {
+        let previous = balances(100_000, 90_000, 50_000);
+        let current = balances(112_400, 90_000, 40_000);
+        let changes = BalanceChanges::between(&previous, &current);
+        assert_eq!(changes.total_onchain, 12_400);
+        assert_eq!(changes.spendable_onchain, 0);
+        assert_eq!(changes.total_lightning, -10_000);
+        assert_eq!(changes.total(), 2_400);
+
+        assert_eq!(BalanceChanges::between(&current, &current), BalanceChanges::default());
+    }
+
+    #[test]
+    fn test_change_saturates() {
+        assert_eq!(change(0, u64::MAX), i64::MAX);
+        assert_eq!(change(u64::MAX, 0), i64::MIN);
+        assert_eq!(change(5, 3), -2);
+    }
+}
@@ -0,0 +1,188 @@
+//! BIP21 `bitcoin:` URI handling.
+
+use crate::amount;
+use crate::ui::sats_to_btc_string;
+
+const SCHEME: &str = "bitcoin:";
+
+/// The parts of a BIP21 URI relevant to sending.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Bip21Uri {
+    pub address: String,
+    pub amount_sats: Option<u64>,
+    pub label: Option<String>,
+    pub message: Option<String>,
+    /// BOLT11 invoice from a unified (BIP21 + lightning) URI.
+    pub lightning: Option<String>,
+}
+
+/// Build a BIP21 URI for `address`, optionally requesting `amount_sats` and attaching a
+/// `label`. Empty parameters are omitted.
+pub fn build_uri(address: &str, amount_sats: Option<u64>, label: Option<&str>) -> String {
+    let mut params = Vec::new();
+    if let Some(sats) = amount_sats {
+        params.push(format!("amount={}", sats_to_btc_string(sats)));
+    }
+    if let Some(label) = label.map(str::trim).filter(|l| !l.is_empty()) {
+        params.push(format!("label={}", percent_encode(label)));
+    }
+
+    if params.is_empty() {
+        format!("bitcoin:{}", address)
+    } else {
+        format!("bitcoin:{}?{}", address, params.join("&"))
+    }
+}
+
+/// Returns true if the input starts with the `bitcoin:` scheme.
+pub fn is_bip21_uri(input: &str) -> bool {
+    input.trim().get(..SCHEME.len()).is_some_and(|s| s.eq_ignore_ascii_case(SCHEME))
+}
+
+/// Parse a BIP21 URI.
+///
+/// Unknown parameters are ignored, except for `req-` parameters which BIP21 requires us to
+/// reject when not understood.
+pub fn parse_uri(input: &str) -> Result<Bip21Uri, String> {
+    let input = input.trim();
+    if !is_bip21_uri(input) {
+        return Err("Not a bitcoin: URI".to_string());
+    }
+    let rest = &input[SCHEME.len()..];
+    let (address, query) = match rest.split_once('?') {
+        Some((address, query)) => (address, Some(query)),
+        None => (rest, None),
+    };
+    let address = percent_decode(address)?;
+    if address.is_empty() {
+        return Err("URI does not contain an address".to_string());
+    }
+
+    let mut uri = Bip21Uri { address, ..Default::default() };
+    for param in query.into_iter().flat_map(|q| q.split('&')).filter(|p| !p.is_empty()) {
+        let (key, value) = param.split_once('=').unwrap_or((param, ""));
+        let value = percent_decode(value)?;
+        match key.to_ascii_lowercase().as_str() {
+            "amount" => uri.amount_sats = Some(amount::parse_btc(&value)?),
+            "label" => uri.label = Some(value),
+            "message" => uri.message = Some(value),
+            "lightning" => uri.lightning = Some(value),
+            other if other.starts_with("req-") => {
+                return Err(format!("Unsupported required parameter '{}'", key));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(uri)
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+fn percent_decode(s: &str) -> Result<String, String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = s
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| format!("Invalid percent-encoding in '{}'", s))?;
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).map_err(|_| format!("Invalid UTF-8 in '{}'", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_address_only() {
+        let uri = parse_uri("bitcoin:bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq").unwrap();
+        assert_eq!(uri.address, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq");
+        assert_eq!(uri.amount_sats, None);
+        assert_eq!(uri.label, None);
+    }
+
+    #[test]
+    fn test_parse_amount_and_label() {
+        let uri = parse_uri("BITCOIN:bc1qxyz?amount=0.001&label=Invoice%2042&message=Thanks%21")
+            .unwrap();
+        assert_eq!(uri.address, "bc1qxyz");
+        assert_eq!(uri.amount_sats, Some(100_000));
+        assert_eq!(uri.label.as_deref(), Some("Invoice 42"));
+        assert_eq!(uri.message.as_deref(), Some("Thanks!"));
+    }
+
+    #[test]
+    fn test_parse_lightning_param() {
+        let uri = parse_uri("bitcoin:bc1qxyz?amount=1.5&lightning=lnbc15u1pxyz").unwrap();
+        assert_eq!(uri.amount_sats, Some(150_000_000));
+        assert_eq!(uri.lightning.as_deref(), Some("lnbc15u1pxyz"));
+    }
+
+    #[test]
+    fn test_unknown_params() {
+        let uri = parse_uri("bitcoin:bc1qxyz?foo=bar&amount=.5").unwrap();
+        assert_eq!(uri.amount_sats, Some(50_000_000));
+        assert!(parse_uri("bitcoin:bc1qxyz?req-somethingyoudontunderstand=50").is_err());
+    }
+
+    #[test]
+    fn test_invalid_uris() {
+        assert!(parse_uri("bc1qxyz").is_err());
+        assert!(parse_uri("bitcoin:").is_err());
+        assert!(parse_uri("bitcoin:bc1qxyz?amount=0.123456789").is_err());
+        assert!(parse_uri("bitcoin:bc1qxyz?amount=1e5").is_err());
+        assert!(parse_uri("bitcoin:bc1qxyz?label=%zz").is_err());
+    }
+
+    #[test]
+    fn test_build_uri_roundtrip() {
+        let uri = build_uri("bc1qxyz", Some(150_000), None);
+        assert_eq!(uri, "bitcoin:bc1qxyz?amount=0.0015");
+        assert_eq!(parse_uri(&uri).unwrap().amount_sats, Some(150_000));
+
+        assert_eq!(build_uri("bc1qxyz", None, None), "bitcoin:bc1qxyz");
+        assert_eq!(build_uri("bc1qxyz", None, Some("  ")), "bitcoin:bc1qxyz");
+    }
+
+    #[test]
+    fn test_build_uri_label_encoding() {
+        let cases = [
+            ("Coffee shop", "Coffee%20shop"),
+            ("Tom & Jerry", "Tom%20%26%20Jerry"),
+            ("a=b?c#d", "a%3Db%3Fc%23d"),
+            ("100%", "100%25"),
+            ("Café ₿", "Caf%C3%A9%20%E2%82%BF"),
+        ];
+        for (label, encoded) in cases {
+            let uri = build_uri("bc1qxyz", Some(1_000), Some(label));
+            assert_eq!(uri, format!("bitcoin:bc1qxyz?amount=0.00001&label={}", encoded));
+
+            let parsed = parse_uri(&uri).unwrap();
+            assert_eq!(parsed.label.as_deref(), Some(label));
+            assert_eq!(parsed.amount_sats, Some(1_000));
+        }
+
+        assert_eq!(build_uri("bc1qxyz", None, Some("x")), "bitcoin:bc1qxyz?label=x");
+    }
+}
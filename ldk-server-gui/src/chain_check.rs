@@ -0,0 +1,236 @@
+//! Connectivity checks for chain source settings, run before they are saved to the config file.
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{self, ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+use crate::config::ChainSourceConfig;
+use crate::errors::{self, ErrorKind};
+
+/// Why a chain source check failed, with a hint specific to chain sources.
+fn failure(kind: ErrorKind, detail: impl std::fmt::Display) -> String {
+    let hint = match kind {
+        ErrorKind::AuthFailed => "Check the RPC user and password.",
+        ErrorKind::TlsError => "Check that the server's certificate is valid for its host name.",
+        ErrorKind::Unreachable => "Check the address and that the service is running.",
+        ErrorKind::BadRequest | ErrorKind::ServerError => "Check the URL points at the service.",
+    };
+    format!("{}: {}. {}", kind.label(), detail, hint)
+}
+
+/// Classify a transport error by the text of its source chain.
+fn connection_failure(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut text = error.to_string();
+    let mut source = error.source();
+    while let Some(e) = source {
+        text.push_str(&format!(": {}", e));
+        source = e.source();
+    }
+    let kind =
+        if errors::mentions_tls(&text) { ErrorKind::TlsError } else { ErrorKind::Unreachable };
+    failure(kind, text)
+}
+
+/// Check that `chain_source` can be reached with the entered settings, returning a short
+/// description of what answered.
+pub async fn check_chain_source(chain_source: ChainSourceConfig) -> Result<String, String> {
+    match chain_source {
+        ChainSourceConfig::None => Err("No chain source selected".to_string()),
+        ChainSourceConfig::Bitcoind { rpc_address, rpc_user, rpc_password } => {
+            check_bitcoind(&rpc_address, &rpc_user, &rpc_password).await
+        }
+        ChainSourceConfig::Esplora { server_url } => check_esplora(&server_url).await,
+        ChainSourceConfig::Electrum { server_url } => check_electrum(&server_url).await,
+    }
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct BlockchainInfo {
+    chain: String,
+    blocks: u64,
+}
+
+async fn check_bitcoind(address: &str, user: &str, password: &str) -> Result<String, String> {
+    let address = address.trim();
+    let url = if address.contains("://") {
+        address.to_string()
+    } else {
+        format!("http://{}", address)
+    };
+    let body = serde_json::json!({
+        "jsonrpc": "1.0",
+        "id": "ldk-server-gui",
+        "method": "getblockchaininfo",
+        "params": [],
+    });
+
+    let response = reqwest::Client::new()
+        .post(url)
+        .basic_auth(user, Some(password))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| connection_failure(&e))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err(failure(ErrorKind::AuthFailed, format!("bitcoind answered {}", status)));
+    }
+    // bitcoind reports RPC errors with a 500 status and a JSON body
+    let body = response.text().await.map_err(|e| connection_failure(&e))?;
+    let response: RpcResponse<BlockchainInfo> = serde_json::from_str(&body)
+        .map_err(|e| failure(ErrorKind::ServerError, format!("Unexpected response: {}", e)))?;
+    match (response.result, response.error) {
+        (_, Some(error)) => Err(failure(ErrorKind::ServerError, error.message)),
+        (Some(info), None) => {
+            Ok(format!("Bitcoin Core ({}) at height {}", info.chain, info.blocks))
+        }
+        (None, None) => Err(failure(ErrorKind::ServerError, "Empty response")),
+    }
+}
+
+async fn check_esplora(server_url: &str) -> Result<String, String> {
+    let url = format!("{}/blocks/tip/height", server_url.trim().trim_end_matches('/'));
+    let response = reqwest::get(url).await.map_err(|e| connection_failure(&e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(failure(ErrorKind::BadRequest, format!("Esplora answered {}", status)));
+    }
+    let text = response.text().await.map_err(|e| connection_failure(&e))?;
+    let height: u64 = text
+        .trim()
+        .parse()
+        .map_err(|_| failure(ErrorKind::BadRequest, "Response is not a block height"))?;
+    Ok(format!("Esplora tip height {}", height))
+}
+
+/// Split an Electrum server URL like `ssl://host:50002` into whether to use TLS, the host and
+/// the `host:port` address to connect to. Without a scheme, TLS is assumed.
+fn parse_electrum_url(server_url: &str) -> Result<(bool, String, String), String> {
+    let url = server_url.trim();
+    let (use_tls, address) = match url.split_once("://") {
+        Some(("ssl", rest)) => (true, rest),
+        Some(("tcp", rest)) => (false, rest),
+        Some((scheme, _)) => return Err(format!("Unsupported Electrum scheme '{}'", scheme)),
+        None => (true, url),
+    };
+    let address = address.trim_end_matches('/');
+    crate::config::validate_host_port(address)?;
+    let host = match address.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => address.rsplit_once(':').map_or(address, |(host, _)| host),
+    };
+    Ok((use_tls, host.to_string(), address.to_string()))
+}
+
+#[derive(Deserialize)]
+struct ElectrumResponse {
+    result: Option<(String, String)>,
+    error: Option<serde_json::Value>,
+}
+
+async fn check_electrum(server_url: &str) -> Result<String, String> {
+    let (use_tls, host, address) = parse_electrum_url(server_url)?;
+    let stream = TcpStream::connect(&address).await.map_err(|e| connection_failure(&e))?;
+
+    if !use_tls {
+        return electrum_server_version(stream).await;
+    }
+
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| failure(ErrorKind::TlsError, e))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let server_name = ServerName::try_from(host).map_err(|e| failure(ErrorKind::TlsError, e))?;
+    let stream = TlsConnector::from(Arc::new(config))
+        .connect(server_name, stream)
+        .await
+        .map_err(|e| failure(ErrorKind::TlsError, e))?;
+    electrum_server_version(stream).await
+}
+
+/// Send `server.version` and return the server software and protocol version it reports.
+async fn electrum_server_version<S>(stream: S) -> Result<String, String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut stream = BufReader::new(stream);
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "server.version",
+        "params": ["ldk-server-gui", "1.4"],
+    });
+    let mut line = request.to_string();
+    line.push('\n');
+    stream.write_all(line.as_bytes()).await.map_err(|e| connection_failure(&e))?;
+    stream.flush().await.map_err(|e| connection_failure(&e))?;
+
+    let mut reply = String::new();
+    stream.read_line(&mut reply).await.map_err(|e| connection_failure(&e))?;
+    if reply.is_empty() {
+        return Err(failure(ErrorKind::Unreachable, "Connection closed by the server"));
+    }
+    let response: ElectrumResponse = serde_json::from_str(&reply)
+        .map_err(|e| failure(ErrorKind::BadRequest, format!("Unexpected response: {}", e)))?;
+    match (response.result, response.error) {
+        (_, Some(error)) => Err(failure(ErrorKind::ServerError, error)),
+        (Some((software, protocol)), None) => {
+            Ok(format!("{} (protocol {})", software, protocol))
+        }
+        (None, None) => Err(failure(ErrorKind::ServerError, "Empty response")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_electrum_url() {
+        let host = "electrum.blockstream.info";
+        assert_eq!(
+            parse_electrum_url("ssl://electrum.blockstream.info:50002").unwrap(),
+            (true, host.to_string(), format!("{}:50002", host))
+        );
+        assert_eq!(
+            parse_electrum_url("tcp://127.0.0.1:50001").unwrap(),
+            (false, "127.0.0.1".to_string(), "127.0.0.1:50001".to_string())
+        );
+        assert_eq!(
+            parse_electrum_url("[::1]:50002").unwrap(),
+            (true, "::1".to_string(), "[::1]:50002".to_string())
+        );
+        assert!(parse_electrum_url("http://example.com:50001").is_err());
+        assert!(parse_electrum_url("ssl://example.com").is_err());
+    }
+
+    #[test]
+    fn test_failure_message() {
+        let message = failure(ErrorKind::AuthFailed, "bitcoind answered 401 Unauthorized");
+        assert!(message.starts_with("Authentication failed: bitcoind answered 401"));
+        assert!(message.ends_with("Check the RPC user and password."));
+    }
+}
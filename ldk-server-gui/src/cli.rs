@@ -0,0 +1,223 @@
+//! Command-line options for the native binary.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+use crate::state::ActiveTab;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::state::{ConnectionField, NodeSession, TlsCertSource};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub const USAGE: &str = "\
+Usage: ldk-server-gui [OPTIONS]
+
+Options:
+  --config <PATH>  Load this config file instead of searching the default locations
+  --connect        Connect to the server immediately after loading the config
+  --tab <TAB>      Initial tab: overview, node-info, balances, channels, payments, lightning,
+                   onchain, logs
+  -h, --help       Print this help
+
+Environment:
+  LDK_SERVER_URL       Server URL, taking precedence over the config file
+  LDK_SERVER_API_KEY   API key (hex), taking precedence over the config file
+  LDK_SERVER_TLS_CERT  Path to the server's TLS certificate
+  LDK_SERVER_CONNECT   Set to 1 to connect on startup, like --connect";
+
+/// Environment variables that override connection settings.
+#[cfg(not(target_arch = "wasm32"))]
+pub const SERVER_URL_ENV: &str = "LDK_SERVER_URL";
+#[cfg(not(target_arch = "wasm32"))]
+pub const API_KEY_ENV: &str = "LDK_SERVER_API_KEY";
+#[cfg(not(target_arch = "wasm32"))]
+pub const TLS_CERT_ENV: &str = "LDK_SERVER_TLS_CERT";
+#[cfg(not(target_arch = "wasm32"))]
+const CONNECT_ENV: &str = "LDK_SERVER_CONNECT";
+
+/// Options that affect how the app starts.
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct StartupOptions {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub config_path: Option<PathBuf>,
+    pub connect: bool,
+    pub tab: Option<ActiveTab>,
+}
+
+/// Result of parsing the command line.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, PartialEq)]
+pub enum ParsedArgs {
+    Run(StartupOptions),
+    Help,
+}
+
+/// Parse command-line arguments, excluding the program name.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<ParsedArgs, String> {
+    let mut options = StartupOptions::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => {
+                let path = args.next().ok_or("--config requires a path")?;
+                options.config_path = Some(PathBuf::from(path));
+            }
+            "--connect" => options.connect = true,
+            "--tab" => {
+                let name = args.next().ok_or("--tab requires a tab name")?;
+                let tab = ActiveTab::from_name(&name)
+                    .ok_or_else(|| format!("Unknown tab '{}'", name))?;
+                options.tab = Some(tab);
+            }
+            "-h" | "--help" => return Ok(ParsedArgs::Help),
+            other => return Err(format!("Unknown argument '{}'", other)),
+        }
+    }
+
+    Ok(ParsedArgs::Run(options))
+}
+
+/// Connection settings given through environment variables, for containers and demo setups
+/// without a config file.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Default, PartialEq)]
+pub struct EnvOverrides {
+    pub server_url: Option<String>,
+    pub api_key: Option<String>,
+    pub tls_cert_path: Option<String>,
+    pub connect: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl EnvOverrides {
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Read the overrides through `lookup`; empty values count as unset.
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let get = |name| lookup(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        Self {
+            server_url: get(SERVER_URL_ENV),
+            api_key: get(API_KEY_ENV),
+            tls_cert_path: get(TLS_CERT_ENV),
+            connect: get(CONNECT_ENV).is_some_and(|v| matches!(v.as_str(), "1" | "true" | "yes")),
+        }
+    }
+
+    /// Overwrite the session's connection fields with the values that are set, which therefore
+    /// take precedence over saved settings and the config file. Returns the overridden fields.
+    pub fn apply(&self, session: &mut NodeSession) -> Vec<ConnectionField> {
+        let mut overridden = Vec::new();
+        if let Some(url) = &self.server_url {
+            session.server_url = url.clone();
+            overridden.push(ConnectionField::ServerUrl);
+        }
+        if let Some(api_key) = &self.api_key {
+            session.api_key = api_key.clone();
+            overridden.push(ConnectionField::ApiKey);
+        }
+        if let Some(path) = &self.tls_cert_path {
+            session.tls_cert_path = path.clone();
+            session.tls_cert_source = TlsCertSource::File;
+            session.insecure_http = false;
+            overridden.push(ConnectionField::TlsCert);
+        }
+        overridden
+    }
+}
+
+/// The environment variable a connection field can be set from.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn env_var_for(field: ConnectionField) -> Option<&'static str> {
+    match field {
+        ConnectionField::ServerUrl => Some(SERVER_URL_ENV),
+        ConnectionField::ApiKey => Some(API_KEY_ENV),
+        ConnectionField::TlsCert => Some(TLS_CERT_ENV),
+        ConnectionField::Proxy => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<ParsedArgs, String> {
+        parse_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse(&[]), Ok(ParsedArgs::Run(StartupOptions::default())));
+        assert_eq!(
+            parse(&["--config", "my.toml", "--connect", "--tab", "payments"]),
+            Ok(ParsedArgs::Run(StartupOptions {
+                config_path: Some(PathBuf::from("my.toml")),
+                connect: true,
+                tab: Some(ActiveTab::Payments),
+            }))
+        );
+        assert_eq!(parse(&["--help"]), Ok(ParsedArgs::Help));
+
+        assert!(parse(&["--config"]).is_err());
+        assert!(parse(&["--tab", "nope"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+    }
+
+    fn env(vars: &[(&str, &str)]) -> EnvOverrides {
+        EnvOverrides::from_lookup(|name| {
+            vars.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn test_env_overrides_take_precedence() {
+        let mut session = NodeSession::default();
+        session.server_url = "config.example.com:3002".to_string();
+        session.api_key = "from-config".to_string();
+        session.tls_cert_path = "/config/tls.crt".to_string();
+
+        let overrides =
+            env(&[("LDK_SERVER_URL", "env.example.com:3002"), ("LDK_SERVER_API_KEY", "")]);
+        assert_eq!(overrides.apply(&mut session), vec![ConnectionField::ServerUrl]);
+        assert_eq!(session.server_url, "env.example.com:3002");
+        // Empty variables do not clear configured values
+        assert_eq!(session.api_key, "from-config");
+        assert_eq!(session.tls_cert_path, "/config/tls.crt");
+        assert!(!overrides.connect);
+    }
+
+    #[test]
+    fn test_env_overrides_all_fields() {
+        let mut session = NodeSession::default();
+        session.insecure_http = true;
+        session.tls_cert_source = TlsCertSource::Pem;
+
+        let overrides = env(&[
+            ("LDK_SERVER_URL", " localhost:3002 "),
+            ("LDK_SERVER_API_KEY", "abcd"),
+            ("LDK_SERVER_TLS_CERT", "/env/tls.crt"),
+            ("LDK_SERVER_CONNECT", "1"),
+        ]);
+        assert_eq!(
+            overrides.apply(&mut session),
+            vec![ConnectionField::ServerUrl, ConnectionField::ApiKey, ConnectionField::TlsCert]
+        );
+        assert_eq!(session.server_url, "localhost:3002");
+        assert_eq!(session.api_key, "abcd");
+        assert_eq!(session.tls_cert_path, "/env/tls.crt");
+        assert!(session.tls_cert_source == TlsCertSource::File);
+        assert!(!session.insecure_http);
+        assert!(overrides.connect);
+    }
+
+    #[test]
+    fn test_no_env_overrides() {
+        let mut session = NodeSession::default();
+        assert_eq!(env(&[]), EnvOverrides::default());
+        assert!(env(&[]).apply(&mut session).is_empty());
+        assert_eq!(session.server_url, NodeSession::default().server_url);
+    }
+}
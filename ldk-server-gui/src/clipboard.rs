@@ -0,0 +1,50 @@
+//! System clipboard on the web, written through the async Clipboard API. Text egui copies from
+//! inside the canvas doesn't reliably reach the clipboard in every browser.
+
+use std::cell::RefCell;
+
+use wasm_bindgen_futures::JsFuture;
+
+thread_local! {
+    /// Why the last write failed, until taken by [`take_failure`]
+    static FAILURE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Start writing `text` to the clipboard, calling `on_copied` once it is written. A failure is
+/// reported by [`take_failure`] instead, after which `ctx` is repainted to show it.
+pub fn write_text(
+    ctx: &egui::Context, text: String, on_copied: impl FnOnce(&egui::Context) + 'static,
+) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    // The API only exists in secure contexts
+    if !window.is_secure_context() {
+        fail(ctx, "Copying needs the page to be served over HTTPS or from localhost".to_string());
+        return;
+    }
+    let promise = window.navigator().clipboard().write_text(&text);
+    let ctx = ctx.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        match JsFuture::from(promise).await {
+            Ok(_) => {
+                on_copied(&ctx);
+                ctx.request_repaint();
+            }
+            Err(e) => {
+                let reason = e.as_string().unwrap_or_else(|| format!("{:?}", e));
+                fail(&ctx, format!("Copying to the clipboard failed: {}", reason));
+            }
+        }
+    });
+}
+
+fn fail(ctx: &egui::Context, message: String) {
+    FAILURE.with(|failure| *failure.borrow_mut() = Some(message));
+    ctx.request_repaint();
+}
+
+/// Why the last clipboard write failed, if it did since the last call.
+pub fn take_failure() -> Option<String> {
+    FAILURE.with(|failure| failure.borrow_mut().take())
+}
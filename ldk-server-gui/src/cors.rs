@@ -0,0 +1,86 @@
+//! Reverse proxy configs that let the web build reach the server from its origin.
+//!
+//! The server itself only allows `http://localhost:8080`, the Trunk development server, so a
+//! GUI served from anywhere else needs a proxy in front of the server that answers CORS
+//! preflight requests and allows the GUI's origin.
+
+/// Methods and headers of the requests the GUI makes.
+const ALLOW_METHODS: &str = "GET, POST, OPTIONS";
+const ALLOW_HEADERS: &str = "Content-Type, X-Auth";
+
+/// The server's REST address in the example configs.
+const UPSTREAM: &str = "https://127.0.0.1:3002";
+
+/// nginx `location` allowing requests from `origin`, proxied to the server.
+pub fn nginx_snippet(origin: &str) -> String {
+    format!(
+        r#"location / {{
+    if ($request_method = OPTIONS) {{
+        add_header Access-Control-Allow-Origin "{origin}" always;
+        add_header Access-Control-Allow-Methods "{methods}" always;
+        add_header Access-Control-Allow-Headers "{headers}" always;
+        add_header Access-Control-Max-Age 86400 always;
+        return 204;
+    }}
+    # Replace the server's own header, which only allows http://localhost:8080
+    proxy_hide_header Access-Control-Allow-Origin;
+    add_header Access-Control-Allow-Origin "{origin}" always;
+    # ldk-server's rest_service_address, with its self-signed certificate
+    proxy_pass {upstream};
+    proxy_ssl_verify off;
+}}
+"#,
+        origin = origin,
+        methods = ALLOW_METHODS,
+        headers = ALLOW_HEADERS,
+        upstream = UPSTREAM,
+    )
+}
+
+/// Caddy site allowing requests from `origin`, proxied to the server.
+pub fn caddy_snippet(origin: &str) -> String {
+    format!(
+        r#"node.example.com {{
+    header Access-Control-Allow-Origin "{origin}"
+    header Access-Control-Allow-Methods "{methods}"
+    header Access-Control-Allow-Headers "{headers}"
+
+    @preflight method OPTIONS
+    respond @preflight 204
+
+    # ldk-server's rest_service_address, with its self-signed certificate
+    reverse_proxy {upstream} {{
+        # Drop the server's own header, which only allows http://localhost:8080
+        header_down -Access-Control-Allow-Origin
+        transport http {{
+            tls_insecure_skip_verify
+        }}
+    }}
+}}
+"#,
+        origin = origin,
+        methods = ALLOW_METHODS,
+        headers = ALLOW_HEADERS,
+        upstream = UPSTREAM,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGIN: &str = "https://gui.example.com";
+
+    #[test]
+    fn test_snippets_allow_origin() {
+        for snippet in [nginx_snippet(ORIGIN), caddy_snippet(ORIGIN)] {
+            assert!(snippet.contains("Access-Control-Allow-Origin \"https://gui.example.com\""));
+            assert!(snippet.contains("\"Content-Type, X-Auth\""));
+            assert!(snippet.contains("https://127.0.0.1:3002"));
+            // Braces are balanced once the format escapes are resolved
+            assert_eq!(snippet.matches('{').count(), snippet.matches('}').count());
+        }
+        assert!(nginx_snippet(ORIGIN).contains("return 204;"));
+        assert!(caddy_snippet(ORIGIN).contains("respond @preflight 204"));
+    }
+}
@@ -0,0 +1,148 @@
+//! Line-based diff, used to preview config file changes before they are written.
+
+/// Number of unchanged lines shown around each change.
+pub const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiffOp {
+    Same,
+    Removed,
+    Added,
+}
+
+/// Diff `old` against `new` line by line, using the longest common subsequence so unchanged
+/// lines are kept together.
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<(DiffOp, &'a str)> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            ops.push((DiffOp::Same, old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push((DiffOp::Removed, old[i]));
+            i += 1;
+        } else {
+            ops.push((DiffOp::Added, new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|line| (DiffOp::Removed, *line)));
+    ops.extend(new[j..].iter().map(|line| (DiffOp::Added, *line)));
+    ops
+}
+
+/// Format the changes between `old` and `new` as a unified diff with `@@` hunk headers.
+/// Returns an empty string when both are identical.
+pub fn unified_diff(old: &str, new: &str) -> String {
+    let ops = diff_lines(old, new);
+    let changes = ops.iter().enumerate().filter(|(_, (op, _))| *op != DiffOp::Same);
+
+    // Group changes whose context overlaps into the same hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (index, _) in changes {
+        let start = index.saturating_sub(CONTEXT_LINES);
+        let end = (index + CONTEXT_LINES + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut output = String::new();
+    for (start, end) in hunks {
+        // Number of lines preceding the hunk in the old and new file
+        let old_before = ops[..start].iter().filter(|(op, _)| *op != DiffOp::Added).count();
+        let new_before = ops[..start].iter().filter(|(op, _)| *op != DiffOp::Removed).count();
+        let old_len = ops[start..end].iter().filter(|(op, _)| *op != DiffOp::Added).count();
+        let new_len = ops[start..end].iter().filter(|(op, _)| *op != DiffOp::Removed).count();
+        // An empty range is numbered after the line it follows, like `diff -u` does
+        let first_line = |before: usize, len: usize| if len == 0 { before } else { before + 1 };
+        output.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            first_line(old_before, old_len),
+            old_len,
+            first_line(new_before, new_len),
+            new_len
+        ));
+        for (op, line) in &ops[start..end] {
+            let prefix = match op {
+                DiffOp::Same => ' ',
+                DiffOp::Removed => '-',
+                DiffOp::Added => '+',
+            };
+            output.push(prefix);
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_has_no_diff() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n"), "");
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let ops = diff_lines("a\nb\nc", "a\nc\nd");
+        assert_eq!(
+            ops,
+            vec![
+                (DiffOp::Same, "a"),
+                (DiffOp::Removed, "b"),
+                (DiffOp::Same, "c"),
+                (DiffOp::Added, "d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_hunks() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n10\n11\n12\n13\n";
+        let expected = "\
+@@ -2,7 +2,7 @@
+ 2
+ 3
+ 4
+-5
++five
+ 6
+ 7
+ 8
+@@ -10,3 +10,4 @@
+ 10
+ 11
+ 12
++13
+";
+        assert_eq!(unified_diff(old, new), expected);
+    }
+
+    #[test]
+    fn test_unified_diff_from_empty() {
+        assert_eq!(unified_diff("", "a\nb"), "@@ -0,0 +1,2 @@\n+a\n+b\n");
+    }
+}
@@ -0,0 +1,335 @@
+//! Errors of background tasks, and their classification into categories with a suggested fix.
+
+use std::fmt;
+use std::time::Duration;
+
+use ldk_server_client::error::{LdkServerError, LdkServerErrorCode};
+
+/// Why a task failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskError {
+    /// The server rejected the API key.
+    Auth { message: String },
+    /// No answer within the request timeout.
+    Timeout { after: Duration },
+    /// The server could not be reached, including TLS failures.
+    Network { message: String },
+    /// The server answered with an error, or with a response the client could not read.
+    Server { code: LdkServerErrorCode, message: String },
+    /// Cancelled from the UI before it finished.
+    Cancelled,
+    /// The task ended without a result, e.g. because it panicked.
+    Lost,
+    /// A failure outside the LDK Server API, e.g. of an Esplora or LNURL request, with a message
+    /// meant for the user.
+    Other(String),
+}
+
+impl From<LdkServerError> for TaskError {
+    fn from(error: LdkServerError) -> Self {
+        let LdkServerError { message, error_code } = error;
+        match error_code {
+            LdkServerErrorCode::AuthError => TaskError::Auth { message },
+            // Transport failures are reported by the client as internal errors
+            LdkServerErrorCode::InternalError if message.starts_with("HTTP request failed") => {
+                TaskError::Network { message }
+            }
+            code => TaskError::Server { code, message },
+        }
+    }
+}
+
+impl From<String> for TaskError {
+    fn from(message: String) -> Self {
+        TaskError::Other(message)
+    }
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskError::Auth { message }
+            | TaskError::Network { message }
+            | TaskError::Server { message, .. }
+            | TaskError::Other(message) => f.write_str(message),
+            TaskError::Timeout { after } => {
+                write!(f, "Request timed out after {}s", after.as_secs())
+            }
+            TaskError::Cancelled => f.write_str("Cancelled"),
+            TaskError::Lost => f.write_str("Task failed unexpectedly"),
+        }
+    }
+}
+
+impl TaskError {
+    /// Whether the failure is likely to go away by itself, e.g. while the server restarts, so
+    /// that repeating a read-only request is worth it. TLS failures are not.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            TaskError::Timeout { .. } => true,
+            TaskError::Network { message } => !mentions_tls(message),
+            _ => false,
+        }
+    }
+}
+
+/// What most likely went wrong with a request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorKind {
+    AuthFailed,
+    TlsError,
+    Unreachable,
+    BadRequest,
+    ServerError,
+    /// The browser blocked the request, most likely for lack of CORS headers. A page isn't told
+    /// why a request failed, so an unreachable server looks the same (web only).
+    CrossOriginBlocked,
+}
+
+impl ErrorKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ErrorKind::AuthFailed => "Authentication failed",
+            ErrorKind::TlsError => "TLS error",
+            ErrorKind::Unreachable => "Server unreachable",
+            ErrorKind::BadRequest => "Invalid request",
+            ErrorKind::ServerError => "Server error",
+            ErrorKind::CrossOriginBlocked => "Request blocked by the browser",
+        }
+    }
+
+    pub fn suggestion(&self) -> &'static str {
+        match self {
+            ErrorKind::AuthFailed => "Check your API key.",
+            ErrorKind::TlsError => "Check that the TLS certificate matches the server.",
+            ErrorKind::Unreachable => "Is the server running? Check the URL and port.",
+            ErrorKind::BadRequest => "Check the values you entered.",
+            ErrorKind::ServerError => "Check the server logs for details.",
+            ErrorKind::CrossOriginBlocked => {
+                "The server, or the reverse proxy in front of it, must allow this page's origin \
+                 with CORS headers. If it does, check that the server is running."
+            }
+        }
+    }
+
+    /// Whether the error means the server could not be reached at all, as opposed to the server
+    /// answering with an error.
+    pub fn is_connection_failure(&self) -> bool {
+        matches!(
+            self,
+            ErrorKind::TlsError | ErrorKind::Unreachable | ErrorKind::CrossOriginBlocked
+        )
+    }
+}
+
+const TLS_MARKERS: [&str; 5] = ["certificate", "tls", "ssl", "handshake", "unknownissuer"];
+
+/// Whether a transport error's text points at a TLS problem rather than a plain connection
+/// failure.
+pub fn mentions_tls(error: &str) -> bool {
+    let lower = error.to_lowercase();
+    TLS_MARKERS.iter().any(|m| lower.contains(m))
+}
+
+/// How browsers word a failed fetch: Chromium, Firefox and Safari.
+const FETCH_FAILURE_MARKERS: [&str; 3] = ["failed to fetch", "networkerror", "load failed"];
+
+/// Whether a transport error's text is a browser's generic fetch failure, all a page learns of
+/// a request blocked for lack of CORS headers.
+pub fn is_fetch_failure(error: &str) -> bool {
+    let lower = error.to_lowercase();
+    FETCH_FAILURE_MARKERS.iter().any(|m| lower.contains(m))
+}
+
+/// Origin of the page the GUI is served from, e.g. `https://gui.example.com`, which the server
+/// has to allow.
+#[cfg(target_arch = "wasm32")]
+pub fn page_origin() -> Option<String> {
+    web_sys::window().and_then(|w| w.location().origin().ok())
+}
+
+/// Native builds are not served from an origin.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn page_origin() -> Option<String> {
+    None
+}
+
+/// Classify a task error, returning `None` for errors that did not come from the server or the
+/// connection to it.
+pub fn classify(error: &TaskError) -> Option<ErrorKind> {
+    match error {
+        TaskError::Auth { .. } => Some(ErrorKind::AuthFailed),
+        TaskError::Timeout { .. } => Some(ErrorKind::Unreachable),
+        TaskError::Network { message } if mentions_tls(message) => Some(ErrorKind::TlsError),
+        TaskError::Network { message }
+            if cfg!(target_arch = "wasm32") && is_fetch_failure(message) =>
+        {
+            Some(ErrorKind::CrossOriginBlocked)
+        }
+        TaskError::Network { .. } => Some(ErrorKind::Unreachable),
+        TaskError::Server { code: LdkServerErrorCode::InvalidRequestError, .. } => {
+            Some(ErrorKind::BadRequest)
+        }
+        TaskError::Server { .. } => Some(ErrorKind::ServerError),
+        TaskError::Cancelled | TaskError::Lost | TaskError::Other(_) => None,
+    }
+}
+
+/// Message shown to the user: the category, the error itself and the suggested fix, along with
+/// the origin to allow for a blocked request. Unclassified errors are shown as they are.
+pub fn describe(error: &TaskError) -> String {
+    let message = error.to_string();
+    match classify(error) {
+        Some(kind) => {
+            let mut text = format!(
+                "{}: {}. {}",
+                kind.label(),
+                message.trim_end_matches('.'),
+                kind.suggestion()
+            );
+            if let (ErrorKind::CrossOriginBlocked, Some(origin)) = (kind, page_origin()) {
+                text.push_str(&format!(" The origin to allow is {}.", origin));
+            }
+            text
+        }
+        None => message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_error(code: LdkServerErrorCode, message: &str) -> TaskError {
+        LdkServerError::new(code, message).into()
+    }
+
+    #[test]
+    fn test_from_ldk_server_error() {
+        assert_eq!(
+            server_error(LdkServerErrorCode::AuthError, "Invalid credentials"),
+            TaskError::Auth { message: "Invalid credentials".to_string() }
+        );
+        let refused = "HTTP request failed: error sending request for url \
+                       (https://localhost:3000/getNodeInfo): client error (Connect): tcp connect \
+                       error: Connection refused (os error 111)";
+        assert_eq!(
+            server_error(LdkServerErrorCode::InternalError, refused),
+            TaskError::Network { message: refused.to_string() }
+        );
+        let undecodable = "Failed to decode success response: buffer underflow";
+        assert_eq!(
+            server_error(LdkServerErrorCode::InternalError, undecodable),
+            TaskError::Server {
+                code: LdkServerErrorCode::InternalError,
+                message: undecodable.to_string()
+            }
+        );
+        assert_eq!(
+            server_error(LdkServerErrorCode::LightningError, "Insufficient funds"),
+            TaskError::Server {
+                code: LdkServerErrorCode::LightningError,
+                message: "Insufficient funds".to_string()
+            }
+        );
+        let invalid = "Invalid amount".to_string();
+        assert_eq!(TaskError::from(invalid.clone()), TaskError::Other(invalid));
+    }
+
+    #[test]
+    fn test_classify() {
+        let cases = [
+            (
+                server_error(LdkServerErrorCode::AuthError, "Invalid credentials"),
+                ErrorKind::AuthFailed,
+            ),
+            (
+                server_error(
+                    LdkServerErrorCode::InternalError,
+                    "HTTP request failed: error sending request for url \
+                     (https://localhost:3000/getNodeInfo): client error (Connect): invalid peer \
+                     certificate: UnknownIssuer",
+                ),
+                ErrorKind::TlsError,
+            ),
+            (
+                server_error(
+                    LdkServerErrorCode::InternalError,
+                    "HTTP request failed: error sending request for url \
+                     (https://localhost:3000/getNodeInfo): client error (Connect): tcp connect \
+                     error: Connection refused (os error 111)",
+                ),
+                ErrorKind::Unreachable,
+            ),
+            (TaskError::Timeout { after: Duration::from_secs(30) }, ErrorKind::Unreachable),
+            (
+                server_error(LdkServerErrorCode::InvalidRequestError, "Invalid address"),
+                ErrorKind::BadRequest,
+            ),
+            (
+                server_error(LdkServerErrorCode::LightningError, "Insufficient funds"),
+                ErrorKind::ServerError,
+            ),
+            (
+                server_error(LdkServerErrorCode::InternalServerError, "Database error"),
+                ErrorKind::ServerError,
+            ),
+            (
+                server_error(
+                    LdkServerErrorCode::InternalError,
+                    "Failed to decode success response: buffer underflow",
+                ),
+                ErrorKind::ServerError,
+            ),
+        ];
+        for (error, kind) in cases {
+            assert_eq!(classify(&error), Some(kind), "{}", error);
+        }
+
+        assert_eq!(classify(&TaskError::Other("Invalid amount".to_string())), None);
+        assert_eq!(classify(&TaskError::Cancelled), None);
+        assert_eq!(classify(&TaskError::Lost), None);
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            describe(&server_error(LdkServerErrorCode::AuthError, "Invalid credentials.")),
+            "Authentication failed: Invalid credentials. Check your API key."
+        );
+        assert_eq!(
+            describe(&TaskError::Timeout { after: Duration::from_secs(30) }),
+            "Server unreachable: Request timed out after 30s. Is the server running? Check the URL \
+             and port."
+        );
+        assert_eq!(describe(&TaskError::Other("Invalid amount".to_string())), "Invalid amount");
+    }
+
+    #[test]
+    fn test_is_fetch_failure() {
+        let chromium = "HTTP request failed: error sending request for url \
+            (https://node.example.com:3002/GetNodeInfo): JsValue(TypeError: Failed to fetch)";
+        assert!(is_fetch_failure(chromium));
+        assert!(is_fetch_failure("TypeError: NetworkError when attempting to fetch resource."));
+        assert!(is_fetch_failure("TypeError: Load failed"));
+        assert!(!is_fetch_failure("HTTP request failed: tcp connect error: Connection refused"));
+
+        // Only the web build can't tell a blocked request from an unreachable server
+        let error = server_error(LdkServerErrorCode::InternalError, chromium);
+        assert_eq!(classify(&error), Some(ErrorKind::Unreachable));
+    }
+
+    #[test]
+    fn test_is_transient() {
+        assert!(TaskError::Timeout { after: Duration::from_secs(30) }.is_transient());
+        let refused = "HTTP request failed: tcp connect error: Connection refused (os error 111)";
+        assert!(server_error(LdkServerErrorCode::InternalError, refused).is_transient());
+
+        let tls = "HTTP request failed: invalid peer certificate: UnknownIssuer";
+        assert!(!server_error(LdkServerErrorCode::InternalError, tls).is_transient());
+        assert!(!server_error(LdkServerErrorCode::AuthError, "Invalid credentials").is_transient());
+        assert!(!server_error(LdkServerErrorCode::LightningError, "No route").is_transient());
+        assert!(!TaskError::Other("Invalid amount".to_string()).is_transient());
+        assert!(!TaskError::Cancelled.is_transient());
+    }
+}
@@ -0,0 +1,187 @@
+//! Transaction confirmation lookups against an Esplora compatible HTTP API.
+
+use serde::Deserialize;
+
+use crate::fees;
+use crate::network::Network;
+
+/// How often a watched transaction is re-checked.
+pub const TX_WATCH_INTERVAL_SECS: u64 = 30;
+
+/// Number of confirmations after which a watched transaction is no longer polled.
+pub const TX_WATCH_TARGET_CONFIRMATIONS: u32 = 6;
+
+/// A transaction being watched for confirmations.
+#[derive(Debug, Clone)]
+pub struct TxWatch {
+    pub txid: String,
+    pub base_url: String,
+    /// Latest known confirmation count, `None` until the first lookup completes.
+    pub confirmations: Option<u32>,
+    pub error: Option<String>,
+    pub last_checked: Option<u64>,
+}
+
+impl TxWatch {
+    pub fn new(txid: String, base_url: String) -> Self {
+        Self { txid, base_url, confirmations: None, error: None, last_checked: None }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.confirmations.is_some_and(|c| c >= TX_WATCH_TARGET_CONFIRMATIONS)
+    }
+
+    /// Returns true if the next lookup is due at `now`.
+    pub fn is_due(&self, now: u64) -> bool {
+        !self.is_done()
+            && self.last_checked.map_or(true, |t| now.saturating_sub(t) >= TX_WATCH_INTERVAL_SECS)
+    }
+
+    pub fn status_text(&self) -> String {
+        match self.confirmations {
+            None => "checking...".to_string(),
+            Some(0) => "unconfirmed".to_string(),
+            Some(1) => "1 confirmation".to_string(),
+            Some(n) => format!("{} confirmations", n),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TxStatus {
+    confirmed: bool,
+    block_height: Option<u32>,
+}
+
+/// Look up the number of confirmations of `txid`. Transactions the server has not seen yet are
+/// reported as unconfirmed.
+pub async fn fetch_confirmations(base_url: String, txid: String) -> Result<(String, u32), String> {
+    let base_url = base_url.trim_end_matches('/');
+
+    let response = reqwest::get(format!("{}/tx/{}/status", base_url, txid))
+        .await
+        .map_err(|e| format!("Confirmation lookup failed: {}", e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok((txid, 0));
+    }
+    if !response.status().is_success() {
+        return Err(format!("Confirmation lookup failed: {}", response.status()));
+    }
+    let body = response.text().await.map_err(|e| format!("Failed to read tx status: {}", e))?;
+    let status: TxStatus =
+        serde_json::from_str(&body).map_err(|e| format!("Invalid tx status: {}", e))?;
+
+    let height = match status.block_height {
+        Some(height) if status.confirmed => height,
+        _ => return Ok((txid, 0)),
+    };
+
+    let tip = fetch_tip_height(base_url.to_string()).await?;
+    Ok((txid, tip.saturating_sub(height) + 1))
+}
+
+/// Look up the height of the chain tip.
+pub async fn fetch_tip_height(base_url: String) -> Result<u32, String> {
+    let base_url = base_url.trim_end_matches('/');
+    let response = reqwest::get(format!("{}/blocks/tip/height", base_url))
+        .await
+        .map_err(|e| format!("Tip height lookup failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Tip height lookup failed: {}", response.status()));
+    }
+    let body = response.text().await.map_err(|e| format!("Failed to read tip height: {}", e))?;
+    body.trim().parse().map_err(|_| format!("Invalid tip height '{}'", body.trim()))
+}
+
+/// Blocks `node_height` trails `public_height` by, if more than `max_lag`. A node ahead of the
+/// public tip, e.g. because the explorer is slower, is not behind.
+pub fn blocks_behind(node_height: u32, public_height: u32, max_lag: u32) -> Option<u32> {
+    let behind = public_height.saturating_sub(node_height);
+    (behind > max_lag).then_some(behind)
+}
+
+/// Networks with a public explorer that can be used to identify a node's chain.
+const PUBLIC_NETWORKS: [Network; 4] =
+    [Network::Bitcoin, Network::Testnet, Network::Testnet4, Network::Signet];
+
+/// Hash of the block at `height`, or `None` if the server does not know that height yet.
+async fn fetch_block_hash(base_url: &str, height: u32) -> Result<Option<String>, String> {
+    let response = reqwest::get(format!("{}/block-height/{}", base_url.trim_end_matches('/'), height))
+        .await
+        .map_err(|e| format!("Block lookup failed: {}", e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(format!("Block lookup failed: {}", response.status()));
+    }
+    let body = response.text().await.map_err(|e| format!("Failed to read block hash: {}", e))?;
+    Ok(Some(body.trim().to_string()))
+}
+
+/// Confirm the network a node is on. The genesis block of the node's Esplora chain source, if
+/// any, settles it; otherwise a regtest best block is recognized by its hash, and a public
+/// network by looking the best block up on the explorers with [`detect_network`].
+pub async fn verify_network(
+    chain_source_url: Option<String>,
+    expected: Network,
+    height: u32,
+    block_hash: String,
+) -> Result<Network, String> {
+    if let Some(base_url) = chain_source_url {
+        // An unreachable chain source leaves it to the other checks
+        if let Ok(Some(genesis_hash)) = fetch_block_hash(&base_url, 0).await {
+            if let Some(network) = Network::from_genesis_hash(&genesis_hash) {
+                return Ok(network);
+            }
+        }
+    }
+    if let Some(network) = Network::from_block_hash(&block_hash) {
+        return Ok(network);
+    }
+    detect_network(expected, height, block_hash).await
+}
+
+/// Identify the network a node is on by looking up its best block on each public explorer,
+/// starting with `expected`. Returns regtest when no public chain has the block.
+///
+/// Fails rather than guessing if `expected` does not know the height yet, since the node may
+/// simply be a block ahead of the explorer.
+pub async fn detect_network(
+    expected: Network,
+    height: u32,
+    block_hash: String,
+) -> Result<Network, String> {
+    let mut candidates = PUBLIC_NETWORKS.to_vec();
+    if let Some(pos) = candidates.iter().position(|n| *n == expected) {
+        let network = candidates.remove(pos);
+        let base_url = fees::default_api_url_for_network(network).expect("public network");
+        match fetch_block_hash(base_url, height).await? {
+            Some(hash) if hash == block_hash => return Ok(network),
+            Some(_) => {}
+            None => return Err(format!("Block {} is not known on {} yet", height, network)),
+        }
+    }
+
+    for network in candidates {
+        let base_url = fees::default_api_url_for_network(network).expect("public network");
+        if fetch_block_hash(base_url, height).await?.as_deref() == Some(block_hash.as_str()) {
+            return Ok(network);
+        }
+    }
+    Ok(Network::Regtest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_behind() {
+        assert_eq!(blocks_behind(850_000, 850_000, 6), None);
+        assert_eq!(blocks_behind(849_994, 850_000, 6), None);
+        assert_eq!(blocks_behind(849_950, 850_000, 6), Some(50));
+        // The explorer may lag the node
+        assert_eq!(blocks_behind(850_002, 850_000, 0), None);
+    }
+}
@@ -0,0 +1,46 @@
+//! Block explorer links.
+
+use crate::network::Network;
+
+const MEMPOOL_SPACE_URL: &str = "https://mempool.space";
+
+/// URL of the transaction page for `txid` on the block explorer for `network`, or `None` if the
+/// network has no public explorer (e.g. regtest).
+pub fn tx_url(network: Network, txid: &str) -> Option<String> {
+    let path = match network {
+        Network::Bitcoin => "",
+        Network::Testnet => "/testnet",
+        Network::Testnet4 => "/testnet4",
+        Network::Signet => "/signet",
+        Network::Regtest => return None,
+    };
+    Some(format!("{}{}/tx/{}", MEMPOOL_SPACE_URL, path, txid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TXID: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+    #[test]
+    fn test_tx_url() {
+        assert_eq!(
+            tx_url(Network::Bitcoin, TXID),
+            Some(format!("https://mempool.space/tx/{}", TXID))
+        );
+        assert_eq!(
+            tx_url(Network::Testnet, TXID),
+            Some(format!("https://mempool.space/testnet/tx/{}", TXID))
+        );
+        assert_eq!(
+            tx_url(Network::Testnet4, TXID),
+            Some(format!("https://mempool.space/testnet4/tx/{}", TXID))
+        );
+        assert_eq!(
+            tx_url(Network::Signet, TXID),
+            Some(format!("https://mempool.space/signet/tx/{}", TXID))
+        );
+        assert_eq!(tx_url(Network::Regtest, TXID), None);
+    }
+}
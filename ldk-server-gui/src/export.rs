@@ -0,0 +1,161 @@
+//! JSON snapshots of API responses, e.g. to paste into a support request, and saving exports
+//! to files.
+//!
+//! Responses are serialized through the protos' serde support, as the CLI prints them.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Characters kept at each end of a redacted node id.
+const REDACTED_NODE_ID_KEPT: usize = 8;
+
+/// Object keys holding node ids, which [`to_json`] can redact.
+const NODE_ID_KEYS: [&str; 2] = ["node_id", "counterparty_node_id"];
+
+/// Pretty-printed JSON of `response`, nested messages included. With `redact_node_ids`, the
+/// middle of every node id is replaced by `…`.
+pub fn to_json<T: Serialize>(response: &T, redact_node_ids: bool) -> Result<String, String> {
+    let mut value =
+        serde_json::to_value(response).map_err(|e| format!("Failed to serialize: {}", e))?;
+    if redact_node_ids {
+        redact_node_id_fields(&mut value);
+    }
+    serde_json::to_string_pretty(&value).map_err(|e| format!("Failed to serialize: {}", e))
+}
+
+/// Keep the first and last characters of a node id, e.g. `02eadbd9…ea3b8c`.
+pub fn redact_node_id(node_id: &str) -> String {
+    if node_id.len() <= 2 * REDACTED_NODE_ID_KEPT || !node_id.is_ascii() {
+        return node_id.to_string();
+    }
+    format!(
+        "{}…{}",
+        &node_id[..REDACTED_NODE_ID_KEPT],
+        &node_id[node_id.len() - REDACTED_NODE_ID_KEPT..]
+    )
+}
+
+/// Name suggested for an export made at `now`, e.g. `payments-2024-06-01.csv`.
+pub fn file_name(stem: &str, extension: &str, now: u64) -> String {
+    let (year, month, day) = utc_date(now);
+    format!("{}-{:04}-{:02}-{:02}.{}", stem, year, month, day, extension)
+}
+
+/// Year, month and day in UTC of `secs` since the epoch.
+fn utc_date(secs: u64) -> (u64, u64, u64) {
+    // Days to civil date, counting in 400-year eras that start on March 1st so that leap days
+    // come last
+    let days = secs / 86_400 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Save `contents` to a file the user picks in a save dialog suggesting `name`. Returns where
+/// it was saved, `None` if the dialog was cancelled.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn save_text_file(name: String, contents: String) -> Result<Option<String>, String> {
+    let Some(file) = rfd::AsyncFileDialog::new().set_file_name(&name).save_file().await else {
+        return Ok(None);
+    };
+    crate::config::write_file_atomically(file.path(), &contents)
+        .map_err(|e| format!("Failed to save {}: {}", file.path().display(), e))?;
+    Ok(Some(file.path().display().to_string()))
+}
+
+/// Download `contents` as a file named `name`, through a temporary link to a blob holding it.
+/// Where it ends up is up to the browser, so the name is returned.
+#[cfg(target_arch = "wasm32")]
+pub async fn save_text_file(name: String, contents: String) -> Result<Option<String>, String> {
+    use wasm_bindgen::JsCast;
+
+    let error = |e: wasm_bindgen::JsValue| format!("Failed to download {}: {:?}", name, e);
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| format!("Failed to download {}: no document", name))?;
+    let parts = web_sys::js_sys::Array::of1(&wasm_bindgen::JsValue::from_str(&contents));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("text/plain;charset=utf-8");
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options).map_err(error)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(error)?;
+    let anchor = document
+        .create_element("a")
+        .map_err(error)?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(|element| error(element.into()))?;
+    anchor.set_href(&url);
+    anchor.set_download(&name);
+    anchor.click();
+    let _ = web_sys::Url::revoke_object_url(&url);
+    Ok(Some(name))
+}
+
+fn redact_node_id_fields(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                match field {
+                    Value::String(id) if NODE_ID_KEYS.contains(&key.as_str()) => {
+                        *id = redact_node_id(id);
+                    }
+                    _ => redact_node_id_fields(field),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_node_id_fields),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NODE_ID: &str = "02eadbd9e7557375161df8b646776a547c5cbc2e95b3071ec81553f8ec2cea3b8c";
+
+    #[test]
+    fn test_redact_node_id() {
+        assert_eq!(redact_node_id(NODE_ID), "02eadbd9…2cea3b8c");
+        assert_eq!(redact_node_id("02eadbd9"), "02eadbd9");
+        assert_eq!(redact_node_id(""), "");
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("payments", "csv", 1_717_200_000), "payments-2024-06-01.csv");
+        assert_eq!(file_name("addresses", "csv", 0), "addresses-1970-01-01.csv");
+        // Leap day, and the last second of a year
+        assert_eq!(utc_date(1_709_251_199), (2024, 2, 29));
+        assert_eq!(utc_date(1_735_689_599), (2024, 12, 31));
+        assert_eq!(utc_date(951_782_400), (2000, 2, 29));
+    }
+
+    #[test]
+    fn test_to_json_redacts_nested_node_ids() {
+        let response = serde_json::json!({
+            "node_id": NODE_ID,
+            "lightning_balances": [
+                {"claimable_on_channel_close": {"counterparty_node_id": NODE_ID, "amount": 5}},
+            ],
+            "channel_id": NODE_ID,
+        });
+
+        let json = to_json(&response, false).unwrap();
+        assert_eq!(json.matches(NODE_ID).count(), 3);
+
+        let redacted: Value = serde_json::from_str(&to_json(&response, true).unwrap()).unwrap();
+        assert_eq!(redacted["node_id"], "02eadbd9…2cea3b8c");
+        let balance = &redacted["lightning_balances"][0]["claimable_on_channel_close"];
+        assert_eq!(balance["counterparty_node_id"], "02eadbd9…2cea3b8c");
+        assert_eq!(balance["amount"], 5);
+        // Other ids are kept
+        assert_eq!(redacted["channel_id"], NODE_ID);
+    }
+}
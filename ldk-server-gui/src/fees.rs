@@ -0,0 +1,208 @@
+//! On-chain fee rate estimates fetched from a mempool.space compatible API.
+
+use serde::Deserialize;
+
+use crate::network::Network;
+
+/// Default mempool.space API base URL for mainnet.
+pub const DEFAULT_MEMPOOL_API_URL: &str = "https://mempool.space/api";
+
+/// How long fetched estimates are considered fresh before being refetched.
+pub const FEE_ESTIMATES_TTL_SECS: u64 = 300;
+
+/// Rough virtual size of a typical send (1 P2WPKH input, 2 outputs), used for fee estimates.
+pub const ESTIMATED_TX_VBYTES: u64 = 140;
+
+/// Fee share of the send amount above which a warning is shown.
+pub const HIGH_FEE_WARNING_PERCENT: u64 = 10;
+
+/// Fee rates below the economy estimate divided by this are flagged as likely to get stuck.
+pub const LOW_FEE_RATE_DIVISOR: u64 = 2;
+
+/// Fee rates above the fastest estimate multiplied by this are flagged as overpaying.
+pub const HIGH_FEE_RATE_MULTIPLIER: u64 = 10;
+
+/// Response of the `/v1/fees/recommended` endpoint, in sat/vB.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendedFees {
+    pub fastest_fee: u64,
+    pub half_hour_fee: u64,
+    pub hour_fee: u64,
+    pub economy_fee: u64,
+    pub minimum_fee: u64,
+}
+
+impl RecommendedFees {
+    /// Preset labels paired with their fee rates, fastest first.
+    pub fn presets(&self) -> [(&'static str, u64); 4] {
+        [
+            ("Fast", self.fastest_fee),
+            ("Half hour", self.half_hour_fee),
+            ("Hour", self.hour_fee),
+            ("Economy", self.economy_fee),
+        ]
+    }
+}
+
+/// Fee estimates along with the time they were fetched.
+#[derive(Debug, Clone)]
+pub struct FeeEstimates {
+    pub fees: RecommendedFees,
+    pub fetched_at: u64,
+}
+
+/// Returns the default API base URL for the given network, or `None` if the network has no
+/// public mempool instance (e.g. regtest).
+pub fn default_api_url_for_network(network: Network) -> Option<&'static str> {
+    match network {
+        Network::Bitcoin => Some(DEFAULT_MEMPOOL_API_URL),
+        Network::Testnet => Some("https://mempool.space/testnet/api"),
+        Network::Testnet4 => Some("https://mempool.space/testnet4/api"),
+        Network::Signet => Some("https://mempool.space/signet/api"),
+        Network::Regtest => None,
+    }
+}
+
+/// Fetch the recommended fee rates from `{base_url}/v1/fees/recommended`.
+pub async fn fetch_recommended_fees(base_url: String) -> Result<FeeEstimates, String> {
+    let url = format!("{}/v1/fees/recommended", base_url.trim_end_matches('/'));
+    let response =
+        reqwest::get(&url).await.map_err(|e| format!("Fee estimate request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Fee estimate request failed: {}", response.status()));
+    }
+    let body =
+        response.text().await.map_err(|e| format!("Failed to read fee estimates: {}", e))?;
+    let fees: RecommendedFees =
+        serde_json::from_str(&body).map_err(|e| format!("Invalid fee estimates: {}", e))?;
+
+    Ok(FeeEstimates { fees, fetched_at: crate::ui::unix_timestamp_now() })
+}
+
+/// Estimate the absolute fee of a typical send at the given fee rate.
+///
+/// This assumes [`ESTIMATED_TX_VBYTES`]; the real fee depends on the inputs the wallet selects.
+pub fn estimate_fee_sats(fee_rate_sat_per_vb: u64) -> u64 {
+    fee_rate_sat_per_vb.saturating_mul(ESTIMATED_TX_VBYTES)
+}
+
+/// Potential problems with an on-chain send given its estimated fee.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeeWarning {
+    /// The fee is a large share of the amount being sent.
+    HighRelativeFee { percent: u64 },
+    /// Amount plus fee is more than the spendable balance.
+    ExceedsSpendable { shortfall_sats: u64 },
+}
+
+/// Check an on-chain send for fee-related problems. `amount_sats` is `None` for send-all.
+pub fn fee_warnings(
+    amount_sats: Option<u64>,
+    fee_sats: u64,
+    spendable_sats: Option<u64>,
+) -> Vec<FeeWarning> {
+    let mut warnings = Vec::new();
+    let Some(amount_sats) = amount_sats else {
+        return warnings;
+    };
+
+    if amount_sats > 0 {
+        let percent = fee_sats.saturating_mul(100) / amount_sats;
+        if percent > HIGH_FEE_WARNING_PERCENT {
+            warnings.push(FeeWarning::HighRelativeFee { percent });
+        }
+    }
+
+    if let Some(spendable) = spendable_sats {
+        let total = amount_sats.saturating_add(fee_sats);
+        if total > spendable {
+            warnings.push(FeeWarning::ExceedsSpendable { shortfall_sats: total - spendable });
+        }
+    }
+
+    warnings
+}
+
+/// An entered fee rate that looks unreasonable compared to current estimates.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeeRateWarning {
+    /// The rate is far below the economy estimate and may take days to confirm.
+    TooLow { economy_fee: u64 },
+    /// The rate is far above the fastest estimate.
+    TooHigh { fastest_fee: u64 },
+}
+
+/// Compare an entered fee rate against the recommended fees.
+pub fn fee_rate_warning(fee_rate_sat_per_vb: u64, fees: &RecommendedFees) -> Option<FeeRateWarning> {
+    if fee_rate_sat_per_vb < fees.minimum_fee
+        || fee_rate_sat_per_vb.saturating_mul(LOW_FEE_RATE_DIVISOR) < fees.economy_fee
+    {
+        return Some(FeeRateWarning::TooLow { economy_fee: fees.economy_fee });
+    }
+    if fee_rate_sat_per_vb > fees.fastest_fee.saturating_mul(HIGH_FEE_RATE_MULTIPLIER) {
+        return Some(FeeRateWarning::TooHigh { fastest_fee: fees.fastest_fee });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_fee_sats() {
+        assert_eq!(estimate_fee_sats(0), 0);
+        assert_eq!(estimate_fee_sats(15), 15 * ESTIMATED_TX_VBYTES);
+        assert_eq!(estimate_fee_sats(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_fee_warnings() {
+        // Send-all has nothing to compare against
+        assert!(fee_warnings(None, 10_000, Some(1_000)).is_empty());
+
+        assert!(fee_warnings(Some(100_000), 2_100, Some(200_000)).is_empty());
+        assert_eq!(
+            fee_warnings(Some(10_000), 2_100, None),
+            vec![FeeWarning::HighRelativeFee { percent: 21 }]
+        );
+        assert_eq!(
+            fee_warnings(Some(100_000), 2_100, Some(101_000)),
+            vec![FeeWarning::ExceedsSpendable { shortfall_sats: 1_100 }]
+        );
+        assert_eq!(
+            fee_warnings(Some(0), 2_100, Some(0)),
+            vec![FeeWarning::ExceedsSpendable { shortfall_sats: 2_100 }]
+        );
+    }
+
+    #[test]
+    fn test_fee_rate_warning() {
+        let fees = RecommendedFees {
+            fastest_fee: 40,
+            half_hour_fee: 30,
+            hour_fee: 20,
+            economy_fee: 10,
+            minimum_fee: 2,
+        };
+
+        assert_eq!(fee_rate_warning(1, &fees), Some(FeeRateWarning::TooLow { economy_fee: 10 }));
+        assert_eq!(fee_rate_warning(4, &fees), Some(FeeRateWarning::TooLow { economy_fee: 10 }));
+        assert_eq!(fee_rate_warning(5, &fees), None);
+        assert_eq!(fee_rate_warning(40, &fees), None);
+        assert_eq!(fee_rate_warning(400, &fees), None);
+        assert_eq!(fee_rate_warning(401, &fees), Some(FeeRateWarning::TooHigh { fastest_fee: 40 }));
+
+        // In a quiet mempool everything at or above the minimum is fine
+        let quiet = RecommendedFees {
+            fastest_fee: 1,
+            half_hour_fee: 1,
+            hour_fee: 1,
+            economy_fee: 1,
+            minimum_fee: 1,
+        };
+        assert_eq!(fee_rate_warning(1, &quiet), None);
+        assert_eq!(fee_rate_warning(0, &quiet), Some(FeeRateWarning::TooLow { economy_fee: 1 }));
+    }
+}
@@ -0,0 +1,116 @@
+//! Approximate fiat value of amounts, from the BTC price published by CoinGecko.
+
+use std::collections::HashMap;
+
+/// CoinGecko API base URL.
+pub const RATE_API_URL: &str = "https://api.coingecko.com/api/v3";
+
+/// Currencies offered in the settings, as CoinGecko `vs_currencies` codes.
+pub const FIAT_CURRENCIES: [&str; 8] = ["usd", "eur", "gbp", "jpy", "cad", "chf", "aud", "brl"];
+
+/// How long a fetched rate is used before being refetched.
+pub const FIAT_RATE_TTL_SECS: u64 = 300;
+
+/// Rates older than this are no longer shown, e.g. while the API keeps failing.
+pub const FIAT_RATE_MAX_AGE_SECS: u64 = 3600;
+
+const SATS_PER_BTC: f64 = 100_000_000.0;
+
+/// Price of one bitcoin in a fiat currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiatRate {
+    /// Lowercase currency code, e.g. `usd`
+    pub currency: String,
+    pub btc_price: f64,
+    /// When CoinGecko last updated the price, or when it was fetched if it doesn't say
+    pub updated_at: u64,
+}
+
+impl FiatRate {
+    /// Whether the rate is in `currency` and recent enough to show at `now`.
+    pub fn is_usable(&self, currency: &str, now: u64) -> bool {
+        self.currency == currency && now.saturating_sub(self.updated_at) < FIAT_RATE_MAX_AGE_SECS
+    }
+
+    /// Approximate value of `sats`, e.g. `≈ 1,234.56 USD`.
+    pub fn format_sats(&self, sats: u64) -> String {
+        format!("≈ {}", format_fiat(sats as f64 / SATS_PER_BTC * self.btc_price, &self.currency))
+    }
+}
+
+/// Format `value` with two decimals and thousands separators, followed by the currency code.
+pub fn format_fiat(value: f64, currency: &str) -> String {
+    let cents = (value * 100.0).round() as u64;
+    format!(
+        "{}.{:02} {}",
+        crate::amount::group_digits(cents / 100),
+        cents % 100,
+        currency.to_uppercase()
+    )
+}
+
+/// Fetch the BTC price in `currency` from `{RATE_API_URL}/simple/price`.
+pub async fn fetch_btc_price(currency: String) -> Result<FiatRate, String> {
+    let url = format!(
+        "{}/simple/price?ids=bitcoin&vs_currencies={}&include_last_updated_at=true",
+        RATE_API_URL, currency
+    );
+    let response =
+        reqwest::get(&url).await.map_err(|e| format!("BTC price request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("BTC price request failed: {}", response.status()));
+    }
+    let body = response.text().await.map_err(|e| format!("Failed to read BTC price: {}", e))?;
+    parse_simple_price(&body, &currency, crate::ui::unix_timestamp_now())
+}
+
+/// Parse a `/simple/price` response such as
+/// `{"bitcoin":{"usd":67012.5,"last_updated_at":1718000000}}`.
+fn parse_simple_price(body: &str, currency: &str, fetched_at: u64) -> Result<FiatRate, String> {
+    let response: HashMap<String, HashMap<String, f64>> =
+        serde_json::from_str(body).map_err(|e| format!("Invalid BTC price: {}", e))?;
+    let prices = response.get("bitcoin").ok_or("No BTC price in the response")?;
+    let btc_price = prices
+        .get(currency)
+        .copied()
+        .filter(|price| price.is_finite() && *price > 0.0)
+        .ok_or_else(|| format!("No BTC price in {}", currency.to_uppercase()))?;
+    let updated_at = prices.get("last_updated_at").map_or(fetched_at, |t| *t as u64);
+    Ok(FiatRate { currency: currency.to_string(), btc_price, updated_at })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_price() {
+        let body = r#"{"bitcoin":{"eur":61234.5,"last_updated_at":1718000000}}"#;
+        let rate = parse_simple_price(body, "eur", 1718000100).unwrap();
+        assert_eq!(rate.btc_price, 61234.5);
+        assert_eq!(rate.updated_at, 1718000000);
+
+        let rate = parse_simple_price(r#"{"bitcoin":{"usd":67000}}"#, "usd", 1718000100).unwrap();
+        assert_eq!(rate.updated_at, 1718000100);
+
+        assert!(parse_simple_price(r#"{"bitcoin":{"usd":67000}}"#, "eur", 0).is_err());
+        assert!(parse_simple_price(r#"{"bitcoin":{"usd":0}}"#, "usd", 0).is_err());
+        assert!(parse_simple_price(r#"{"error_code":429}"#, "usd", 0).is_err());
+    }
+
+    #[test]
+    fn test_format_sats() {
+        let rate = FiatRate { currency: "usd".to_string(), btc_price: 65000.0, updated_at: 0 };
+        assert_eq!(rate.format_sats(0), "≈ 0.00 USD");
+        assert_eq!(rate.format_sats(1_000), "≈ 0.65 USD");
+        assert_eq!(rate.format_sats(250_000_000), "≈ 162,500.00 USD");
+    }
+
+    #[test]
+    fn test_is_usable() {
+        let rate = FiatRate { currency: "eur".to_string(), btc_price: 60000.0, updated_at: 1000 };
+        assert!(rate.is_usable("eur", 1000 + FIAT_RATE_MAX_AGE_SECS - 1));
+        assert!(!rate.is_usable("eur", 1000 + FIAT_RATE_MAX_AGE_SECS));
+        assert!(!rate.is_usable("usd", 1000));
+    }
+}
@@ -0,0 +1,142 @@
+//! At-a-glance health of a node, from the timestamps in its node info.
+
+use ldk_server_client::ldk_server_protos::api::GetNodeInfoResponse;
+
+/// Something the node does periodically and reports the last time of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthCheck {
+    ChainSync,
+    LightningSync,
+    FeeCache,
+    RgsSnapshot,
+    NodeAnnouncement,
+}
+
+impl HealthCheck {
+    pub const ALL: [HealthCheck; 5] = [
+        HealthCheck::ChainSync,
+        HealthCheck::LightningSync,
+        HealthCheck::FeeCache,
+        HealthCheck::RgsSnapshot,
+        HealthCheck::NodeAnnouncement,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HealthCheck::ChainSync => "Chain sync fresh",
+            HealthCheck::LightningSync => "Lightning sync fresh",
+            HealthCheck::FeeCache => "Fee cache fresh",
+            HealthCheck::RgsSnapshot => "RGS snapshot recent",
+            HealthCheck::NodeAnnouncement => "Node announcement recent",
+        }
+    }
+
+    /// Ages in seconds past which the check is a warning and a failure. The node syncs its
+    /// wallets every minute or two, updates fees every few minutes, and fetches gossip snapshots
+    /// and rebroadcasts its announcement about hourly, so these leave room for a few misses.
+    pub fn thresholds(&self) -> (u64, u64) {
+        match self {
+            HealthCheck::ChainSync | HealthCheck::LightningSync => (10 * 60, 60 * 60),
+            HealthCheck::FeeCache => (30 * 60, 2 * 60 * 60),
+            HealthCheck::RgsSnapshot => (6 * 60 * 60, 48 * 60 * 60),
+            HealthCheck::NodeAnnouncement => (3 * 60 * 60, 24 * 60 * 60),
+        }
+    }
+
+    /// When the node last did it, `None` if it never did or doesn't, e.g. an unannounced node.
+    fn timestamp(&self, info: &GetNodeInfoResponse) -> Option<u64> {
+        match self {
+            HealthCheck::ChainSync => info.latest_onchain_wallet_sync_timestamp,
+            HealthCheck::LightningSync => info.latest_lightning_wallet_sync_timestamp,
+            HealthCheck::FeeCache => info.latest_fee_rate_cache_update_timestamp,
+            HealthCheck::RgsSnapshot => info.latest_rgs_snapshot_timestamp,
+            HealthCheck::NodeAnnouncement => info.latest_node_announcement_broadcast_timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Failing,
+    /// The node reports no time for it, as it may not use it. Doesn't count against the node.
+    NotReported,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckResult {
+    pub check: HealthCheck,
+    pub status: CheckStatus,
+    /// Seconds since the node last did it
+    pub age_secs: Option<u64>,
+}
+
+/// Evaluate every [`HealthCheck`] against its thresholds at `now`.
+pub fn health_checks(info: &GetNodeInfoResponse, now: u64) -> Vec<CheckResult> {
+    HealthCheck::ALL
+        .into_iter()
+        .map(|check| {
+            let age_secs = check.timestamp(info).map(|ts| now.saturating_sub(ts));
+            let (warn_after_secs, fail_after_secs) = check.thresholds();
+            let status = match age_secs {
+                None => CheckStatus::NotReported,
+                Some(age) if age > fail_after_secs => CheckStatus::Failing,
+                Some(age) if age > warn_after_secs => CheckStatus::Warning,
+                Some(_) => CheckStatus::Ok,
+            };
+            CheckResult { check, status, age_secs }
+        })
+        .collect()
+}
+
+/// Whether every reported check passed.
+pub fn is_healthy(results: &[CheckResult]) -> bool {
+    results.iter().all(|r| matches!(r.status, CheckStatus::Ok | CheckStatus::NotReported))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn status(results: &[CheckResult], check: HealthCheck) -> CheckStatus {
+        results.iter().find(|r| r.check == check).unwrap().status
+    }
+
+    #[test]
+    fn test_health_checks() {
+        let info = GetNodeInfoResponse {
+            latest_onchain_wallet_sync_timestamp: Some(NOW - 60),
+            latest_lightning_wallet_sync_timestamp: Some(NOW - 20 * 60),
+            latest_fee_rate_cache_update_timestamp: Some(NOW - 3 * 60 * 60),
+            latest_rgs_snapshot_timestamp: None,
+            latest_node_announcement_broadcast_timestamp: Some(NOW + 5),
+            ..Default::default()
+        };
+        let results = health_checks(&info, NOW);
+        assert_eq!(results.len(), HealthCheck::ALL.len());
+        assert_eq!(status(&results, HealthCheck::ChainSync), CheckStatus::Ok);
+        assert_eq!(status(&results, HealthCheck::LightningSync), CheckStatus::Warning);
+        assert_eq!(status(&results, HealthCheck::FeeCache), CheckStatus::Failing);
+        assert_eq!(status(&results, HealthCheck::RgsSnapshot), CheckStatus::NotReported);
+        // A clock behind the node's counts as just now
+        assert_eq!(status(&results, HealthCheck::NodeAnnouncement), CheckStatus::Ok);
+        assert_eq!(results[0].age_secs, Some(60));
+        assert!(!is_healthy(&results));
+    }
+
+    #[test]
+    fn test_is_healthy() {
+        let (warn_after_secs, _) = HealthCheck::ChainSync.thresholds();
+        let info = GetNodeInfoResponse {
+            latest_onchain_wallet_sync_timestamp: Some(NOW - warn_after_secs),
+            ..Default::default()
+        };
+        // At the threshold and with nothing else reported
+        assert!(is_healthy(&health_checks(&info, NOW)));
+        assert!(!is_healthy(&health_checks(&info, NOW + 1)));
+        assert!(is_healthy(&health_checks(&GetNodeInfoResponse::default(), NOW)));
+    }
+}
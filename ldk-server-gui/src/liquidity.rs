@@ -0,0 +1,156 @@
+//! Alerts for when the node's channels can no longer send or receive much.
+
+use ldk_server_client::ldk_server_protos::types::Channel;
+use serde::{Deserialize, Serialize};
+
+/// Liquidity below which an alert is shown, in sats; zero turns an alert off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiquidityThresholds {
+    pub outbound_below_sats: u64,
+    pub inbound_below_sats: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidityDirection {
+    Outbound,
+    Inbound,
+}
+
+/// Liquidity of usable channels in one direction that fell below its threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiquidityAlert {
+    pub direction: LiquidityDirection,
+    pub available_sats: u64,
+    pub threshold_sats: u64,
+}
+
+impl LiquidityAlert {
+    pub fn title(&self) -> &'static str {
+        match self.direction {
+            LiquidityDirection::Outbound => "Low outbound liquidity",
+            LiquidityDirection::Inbound => "Low inbound liquidity",
+        }
+    }
+
+    /// What the node can no longer do well, to follow the available amount.
+    pub fn consequence(&self) -> &'static str {
+        match self.direction {
+            LiquidityDirection::Outbound => "Larger lightning payments will fail to send.",
+            LiquidityDirection::Inbound => "Larger lightning payments can't be received.",
+        }
+    }
+
+    pub fn suggestions(&self) -> &'static [&'static str] {
+        match self.direction {
+            LiquidityDirection::Outbound => &[
+                "Open a channel funded from the on-chain balance.",
+                "Splice on-chain funds into an existing channel.",
+                "Rebalance from a channel with spare outbound liquidity by paying yourself.",
+            ],
+            LiquidityDirection::Inbound => &[
+                "Request inbound liquidity from an LSP, e.g. a just-in-time channel over LSPS2.",
+                "Ask a well-connected node to open a channel to you.",
+                "Rebalance by spending or swapping out lightning funds to on-chain.",
+            ],
+        }
+    }
+}
+
+/// Alerts for each direction whose liquidity over usable channels is below its threshold.
+pub fn liquidity_alerts(
+    channels: &[Channel], thresholds: LiquidityThresholds,
+) -> Vec<LiquidityAlert> {
+    let usable = || channels.iter().filter(|channel| channel.is_usable);
+    let outbound_sats: u64 = usable().map(|channel| channel.outbound_capacity_msat / 1000).sum();
+    let inbound_sats: u64 = usable().map(|channel| channel.inbound_capacity_msat / 1000).sum();
+    [
+        (LiquidityDirection::Outbound, outbound_sats, thresholds.outbound_below_sats),
+        (LiquidityDirection::Inbound, inbound_sats, thresholds.inbound_below_sats),
+    ]
+    .into_iter()
+    .filter(|(_, available_sats, threshold_sats)| available_sats < threshold_sats)
+    .map(|(direction, available_sats, threshold_sats)| LiquidityAlert {
+        direction,
+        available_sats,
+        threshold_sats,
+    })
+    .collect()
+}
+
+/// Liquidity and reserves summed over channels, usable or not.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChannelLiquidityTotals {
+    pub outbound_msat: u64,
+    pub inbound_msat: u64,
+    /// Our side's reserves, which can't be spent while the channels are open
+    pub reserve_sats: u64,
+}
+
+pub fn channel_liquidity_totals(channels: &[Channel]) -> ChannelLiquidityTotals {
+    channels.iter().fold(ChannelLiquidityTotals::default(), |totals, channel| {
+        ChannelLiquidityTotals {
+            outbound_msat: totals.outbound_msat.saturating_add(channel.outbound_capacity_msat),
+            inbound_msat: totals.inbound_msat.saturating_add(channel.inbound_capacity_msat),
+            reserve_sats: totals
+                .reserve_sats
+                .saturating_add(channel.unspendable_punishment_reserve.unwrap_or(0)),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(outbound_sats: u64, inbound_sats: u64, is_usable: bool) -> Channel {
+        Channel {
+            outbound_capacity_msat: outbound_sats * 1000,
+            inbound_capacity_msat: inbound_sats * 1000,
+            is_usable,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_liquidity_alerts() {
+        let channels = [channel(30_000, 5_000, true), channel(500_000, 500_000, false)];
+        let thresholds = LiquidityThresholds { outbound_below_sats: 50_000, inbound_below_sats: 0 };
+        assert_eq!(
+            liquidity_alerts(&channels, thresholds),
+            vec![LiquidityAlert {
+                direction: LiquidityDirection::Outbound,
+                available_sats: 30_000,
+                threshold_sats: 50_000,
+            }]
+        );
+
+        let thresholds =
+            LiquidityThresholds { outbound_below_sats: 30_000, inbound_below_sats: 10_000 };
+        let alerts = liquidity_alerts(&channels, thresholds);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].direction, LiquidityDirection::Inbound);
+        assert_eq!(alerts[0].available_sats, 5_000);
+
+        // No channels at all is below any threshold, and zero turns the alerts off
+        let thresholds = LiquidityThresholds { outbound_below_sats: 1, inbound_below_sats: 1 };
+        assert_eq!(liquidity_alerts(&[], thresholds).len(), 2);
+        assert!(liquidity_alerts(&[], LiquidityThresholds::default()).is_empty());
+    }
+
+    #[test]
+    fn test_channel_liquidity_totals() {
+        let mut with_reserve = channel(30_000, 5_000, true);
+        with_reserve.unspendable_punishment_reserve = Some(1_000);
+        let channels = [with_reserve, channel(500_000, 500_000, false)];
+        assert_eq!(
+            channel_liquidity_totals(&channels),
+            ChannelLiquidityTotals {
+                outbound_msat: 530_000_000,
+                inbound_msat: 505_000_000,
+                reserve_sats: 1_000,
+            }
+        );
+        assert_eq!(channel_liquidity_totals(&[]), ChannelLiquidityTotals::default());
+    }
+}
@@ -0,0 +1,323 @@
+//! Lightning Address (LUD-16) and LNURL-pay (LUD-06) resolution.
+//!
+//! Resolution happens in two HTTP round-trips: the pay parameters are fetched from the
+//! well-known endpoint, then the callback is asked for a BOLT11 invoice for the chosen amount.
+//! The resulting invoice is handed back to the regular BOLT11 send path after user confirmation.
+
+use serde::Deserialize;
+
+/// Prefix of bech32-encoded LNURL strings.
+const LNURL_HRP: &str = "lnurl";
+
+/// A BOLT11 invoice obtained from an LNURL-pay service, awaiting user confirmation.
+#[derive(Debug, Clone)]
+pub struct LnurlInvoice {
+    /// The lightning address or LNURL the user entered.
+    pub target: String,
+    /// The BOLT11 invoice returned by the callback.
+    pub invoice: String,
+    /// The amount requested from the service.
+    pub amount_msat: u64,
+    /// The `text/plain` entry from the service metadata, if present.
+    pub description: Option<String>,
+}
+
+/// Response of the well-known LNURL-pay endpoint (or an error response).
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PayParams {
+    status: Option<String>,
+    reason: Option<String>,
+    tag: Option<String>,
+    callback: Option<String>,
+    min_sendable: Option<u64>,
+    max_sendable: Option<u64>,
+    metadata: Option<String>,
+}
+
+/// Response of the LNURL-pay callback (or an error response).
+#[derive(Deserialize)]
+struct CallbackResponse {
+    status: Option<String>,
+    reason: Option<String>,
+    pr: Option<String>,
+}
+
+/// Returns true if the input looks like a lightning address or an LNURL rather than an invoice.
+pub fn is_lnurl_pay_input(input: &str) -> bool {
+    resolve_url(input).is_ok()
+}
+
+/// Resolve a lightning address or LNURL into the URL of its pay parameters.
+pub fn resolve_url(input: &str) -> Result<String, String> {
+    let input = input.trim();
+    let input = strip_prefix_ignore_case(input, "lightning:").unwrap_or(input);
+
+    if strip_prefix_ignore_case(input, LNURL_HRP).is_some_and(|rest| !rest.is_empty()) {
+        let (hrp, data) = bech32_decode(input)?;
+        if hrp != LNURL_HRP {
+            return Err(format!("Unexpected LNURL prefix '{}'", hrp));
+        }
+        return String::from_utf8(data).map_err(|_| "LNURL does not contain a valid URL".to_string());
+    }
+
+    if let Some(rest) = strip_prefix_ignore_case(input, "lnurlp://") {
+        let scheme = if is_onion(rest) { "http" } else { "https" };
+        return Ok(format!("{}://{}", scheme, rest));
+    }
+
+    parse_lightning_address(input)
+}
+
+/// Map `user@domain` to `https://domain/.well-known/lnurlp/user` as specified by LUD-16.
+fn parse_lightning_address(input: &str) -> Result<String, String> {
+    let (user, domain) =
+        input.split_once('@').ok_or_else(|| "Not a lightning address or LNURL".to_string())?;
+
+    let user_valid = !user.is_empty()
+        && user.chars().all(|c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.' | '+')
+        });
+    let domain_valid = domain.contains('.')
+        && domain.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | ':'));
+
+    if !user_valid || !domain_valid {
+        return Err("Invalid lightning address".to_string());
+    }
+
+    let scheme = if is_onion(domain) { "http" } else { "https" };
+    Ok(format!("{}://{}/.well-known/lnurlp/{}", scheme, domain, user))
+}
+
+fn is_onion(host: &str) -> bool {
+    host.split(['/', ':']).next().is_some_and(|h| h.ends_with(".onion"))
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    match s.get(..prefix.len()) {
+        Some(head) if head.eq_ignore_ascii_case(prefix) => Some(&s[prefix.len()..]),
+        _ => None,
+    }
+}
+
+/// Append the `amount` query parameter to the callback URL.
+fn callback_url(callback: &str, amount_msat: u64) -> String {
+    let separator = if callback.contains('?') { '&' } else { '?' };
+    format!("{}{}amount={}", callback, separator, amount_msat)
+}
+
+/// Extract the `text/plain` description from the LNURL-pay metadata string.
+fn metadata_description(metadata: &str) -> Option<String> {
+    let entries: Vec<Vec<serde_json::Value>> = serde_json::from_str(metadata).ok()?;
+    entries.into_iter().find_map(|entry| match entry.as_slice() {
+        [kind, value] if kind.as_str() == Some("text/plain") => value.as_str().map(String::from),
+        _ => None,
+    })
+}
+
+/// Decode the amount encoded in the human-readable part of a BOLT11 invoice.
+///
+/// Returns `Ok(None)` for zero-amount invoices.
+pub fn bolt11_amount_msat(invoice: &str) -> Result<Option<u64>, String> {
+    let invoice = invoice.trim().to_ascii_lowercase();
+    let invoice = invoice.strip_prefix("lightning:").unwrap_or(&invoice);
+    let hrp_end = invoice.rfind('1').ok_or_else(|| "Invalid invoice".to_string())?;
+    let hrp = invoice[..hrp_end]
+        .strip_prefix("ln")
+        .ok_or_else(|| "Invalid invoice prefix".to_string())?;
+
+    let amount_start = match hrp.find(|c: char| c.is_ascii_digit()) {
+        Some(idx) => idx,
+        None => return Ok(None),
+    };
+    let amount = &hrp[amount_start..];
+    let (digits, multiplier) = match amount.chars().last() {
+        Some(c) if c.is_ascii_digit() => (amount, None),
+        Some(c) => (&amount[..amount.len() - 1], Some(c)),
+        None => return Ok(None),
+    };
+    let value: u64 = digits.parse().map_err(|_| "Invalid invoice amount".to_string())?;
+
+    let msat = match multiplier {
+        None => value.checked_mul(100_000_000_000),
+        Some('m') => value.checked_mul(100_000_000),
+        Some('u') => value.checked_mul(100_000),
+        Some('n') => value.checked_mul(100),
+        Some('p') if value % 10 == 0 => Some(value / 10),
+        _ => None,
+    };
+    msat.map(Some).ok_or_else(|| "Invalid invoice amount".to_string())
+}
+
+/// Resolve the target, validate the amount against the service limits, and fetch an invoice.
+pub async fn fetch_invoice(target: String, amount_msat: u64) -> Result<LnurlInvoice, String> {
+    let url = resolve_url(&target)?;
+
+    let params: PayParams = http_get_json(&url).await?;
+    if params.status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("ERROR")) {
+        return Err(format!(
+            "LNURL service error: {}",
+            params.reason.unwrap_or_else(|| "unknown reason".to_string())
+        ));
+    }
+    if params.tag.as_deref() != Some("payRequest") {
+        return Err("LNURL is not a pay request".to_string());
+    }
+    let callback =
+        params.callback.ok_or_else(|| "LNURL response is missing a callback".to_string())?;
+    let min_sendable = params.min_sendable.unwrap_or(1);
+    let max_sendable = params.max_sendable.unwrap_or(u64::MAX);
+    if amount_msat < min_sendable || amount_msat > max_sendable {
+        return Err(format!(
+            "Amount must be between {} and {} msat for this recipient",
+            min_sendable, max_sendable
+        ));
+    }
+    let description = params.metadata.as_deref().and_then(metadata_description);
+
+    let response: CallbackResponse = http_get_json(&callback_url(&callback, amount_msat)).await?;
+    if response.status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("ERROR")) {
+        return Err(format!(
+            "LNURL service error: {}",
+            response.reason.unwrap_or_else(|| "unknown reason".to_string())
+        ));
+    }
+    let invoice = response.pr.ok_or_else(|| "LNURL callback returned no invoice".to_string())?;
+
+    // LUD-06 requires the wallet to verify the invoice amount matches what was requested.
+    if bolt11_amount_msat(&invoice)? != Some(amount_msat) {
+        return Err("Invoice amount returned by the LNURL service does not match".to_string());
+    }
+
+    Ok(LnurlInvoice { target, invoice, amount_msat, description })
+}
+
+async fn http_get_json<T: serde::de::DeserializeOwned>(url: &str) -> Result<T, String> {
+    let response = reqwest::get(url).await.map_err(|e| format!("LNURL request failed: {}", e))?;
+    let status = response.status();
+    let body =
+        response.text().await.map_err(|e| format!("Failed to read LNURL response: {}", e))?;
+
+    // LNURL services report errors as JSON, sometimes with a non-2xx status.
+    match serde_json::from_str(&body) {
+        Ok(value) => Ok(value),
+        Err(_) if !status.is_success() => Err(format!("LNURL service returned {}", status)),
+        Err(e) => Err(format!("Invalid LNURL response: {}", e)),
+    }
+}
+
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut chk: u32 = 1;
+    for v in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ u32::from(*v);
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+/// Decode a bech32 string into its human-readable part and 8-bit payload.
+///
+/// LNURLs routinely exceed the 90 character limit of BIP173, so no length limit is enforced.
+fn bech32_decode(s: &str) -> Result<(String, Vec<u8>), String> {
+    if s.chars().any(|c| c.is_ascii_lowercase()) && s.chars().any(|c| c.is_ascii_uppercase()) {
+        return Err("Invalid bech32: mixed case".to_string());
+    }
+    let s = s.to_ascii_lowercase();
+    let sep = s.rfind('1').ok_or_else(|| "Invalid bech32: missing separator".to_string())?;
+    let (hrp, data) = (&s[..sep], &s[sep + 1..]);
+    if hrp.is_empty() || data.len() < 6 {
+        return Err("Invalid bech32: too short".to_string());
+    }
+
+    let values = data
+        .chars()
+        .map(|c| BECH32_CHARSET.find(c).map(|i| i as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| "Invalid bech32: bad character".to_string())?;
+
+    let mut checked: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    checked.push(0);
+    checked.extend(hrp.bytes().map(|b| b & 31));
+    checked.extend(&values);
+    if bech32_polymod(&checked) != 1 {
+        return Err("Invalid bech32: bad checksum".to_string());
+    }
+
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut out = Vec::new();
+    for v in &values[..values.len() - 6] {
+        acc = ((acc << 5) | u32::from(*v)) & 0xfff;
+        bits += 5;
+        while bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    if bits >= 5 || acc & ((1 << bits) - 1) != 0 {
+        return Err("Invalid bech32: bad padding".to_string());
+    }
+
+    Ok((hrp.to_string(), out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_lightning_address() {
+        assert_eq!(
+            resolve_url("alice@example.com").unwrap(),
+            "https://example.com/.well-known/lnurlp/alice"
+        );
+        assert_eq!(
+            resolve_url("lightning:bob@abcdef.onion").unwrap(),
+            "http://abcdef.onion/.well-known/lnurlp/bob"
+        );
+        assert!(resolve_url("Alice@example.com").is_err());
+        assert!(resolve_url("alice@localhost").is_err());
+        assert!(resolve_url("lnbc10u1p3xyz").is_err());
+    }
+
+    #[test]
+    fn test_resolve_bech32_lnurl() {
+        let lnurl = "LNURL1DP68GURN8GHJ7UM9WFMXJCM99E3K7MF0V9CXJ0M385EKVCENXC6R2C35XVUKXEFCV5MKVV34X5EKZD3EV56NYD3HXQURZEPEXEJXXEPNXSCRVWFNV9NXZCN9XQ6XYEFHVGCXXCMYXYMNSERXFQ5FNS";
+        assert_eq!(
+            resolve_url(lnurl).unwrap(),
+            "https://service.com/api?q=3fc3645b439ce8e7f2553a69e5267081d96dcd340693afabe04be7b0ccd178df"
+        );
+        assert!(resolve_url(&lnurl.replace("5FNS", "5FNQ")).is_err());
+    }
+
+    #[test]
+    fn test_callback_url() {
+        assert_eq!(callback_url("https://a.com/cb", 1000), "https://a.com/cb?amount=1000");
+        assert_eq!(callback_url("https://a.com/cb?id=1", 1000), "https://a.com/cb?id=1&amount=1000");
+    }
+
+    #[test]
+    fn test_metadata_description() {
+        let metadata = r#"[["text/identifier","alice@example.com"],["text/plain","Pay Alice"]]"#;
+        assert_eq!(metadata_description(metadata).as_deref(), Some("Pay Alice"));
+        assert_eq!(metadata_description("not json"), None);
+    }
+
+    #[test]
+    fn test_bolt11_amount_msat() {
+        assert_eq!(bolt11_amount_msat("lnbc2500u1pvjluez").unwrap(), Some(250_000_000));
+        assert_eq!(bolt11_amount_msat("lnbc20m1pvjluez").unwrap(), Some(2_000_000_000));
+        assert_eq!(bolt11_amount_msat("lnbcrt10n1pvjluez").unwrap(), Some(1_000));
+        assert_eq!(bolt11_amount_msat("lntb1pvjluez").unwrap(), None);
+        assert_eq!(bolt11_amount_msat("LNBC1PVJLUEZ").unwrap(), None);
+        assert!(bolt11_amount_msat("lnbc15p1pvjluez").is_err());
+    }
+}
@@ -0,0 +1,254 @@
+//! In-app log of the GUI's `tracing` events, and the subscriber feeding it.
+//!
+//! Events must never carry the API key, and invoices or offers in error texts are shortened to a
+//! prefix with [`redact`] before they are logged.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::ui::unix_timestamp_millis;
+
+/// Number of entries kept in the in-app log; older ones are dropped.
+pub const LOG_ENTRIES_KEPT: usize = 1000;
+
+/// Characters of an invoice or offer kept by [`redact`].
+const REDACTED_PREFIX_LEN: usize = 12;
+
+/// Human-readable parts of lightning invoices, offers, refunds and LNURLs, lowercase.
+const LIGHTNING_PREFIXES: [&str; 6] = ["lnbc", "lntb", "lnsb", "lno1", "lnr1", "lnurl"];
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// Unix time in milliseconds
+    pub at_ms: u64,
+    pub level: Level,
+    pub target: String,
+    /// Fields of the enclosing spans followed by the event's message and fields
+    pub message: String,
+}
+
+/// The most recent log entries, shared between the subscriber and the Logs view.
+#[derive(Clone, Default)]
+pub struct LogBuffer(Arc<Mutex<VecDeque<LogEntry>>>);
+
+impl LogBuffer {
+    pub fn push(&self, entry: LogEntry) {
+        let mut entries = self.0.lock().unwrap();
+        if entries.len() == LOG_ENTRIES_KEPT {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Entries at `level` or more severe, oldest first.
+    pub fn entries(&self, level: Level) -> Vec<LogEntry> {
+        let entries = self.0.lock().unwrap();
+        entries.iter().filter(|entry| entry.level <= level).cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+/// Install the global subscriber: the in-app log plus stderr on native, or the browser console
+/// on WASM. Debug events are only recorded for the GUI itself, dependencies from warnings up.
+pub fn init() -> LogBuffer {
+    let buffer = LogBuffer::default();
+    let filter = Targets::new()
+        .with_target(env!("CARGO_CRATE_NAME"), Level::DEBUG)
+        .with_default(Level::WARN);
+
+    let subscriber = tracing_subscriber::registry()
+        .with(BufferLayer(buffer.clone()).with_filter(filter.clone()));
+    #[cfg(not(target_arch = "wasm32"))]
+    let subscriber = subscriber.with(
+        tracing_subscriber::fmt::layer().with_writer(std::io::stderr).with_filter(filter),
+    );
+    #[cfg(target_arch = "wasm32")]
+    let subscriber = subscriber.with(ConsoleLayer.with_filter(filter));
+    // Fails only if a subscriber is already installed, which then keeps receiving the events
+    subscriber.try_init().ok();
+
+    buffer
+}
+
+/// Shorten every lightning invoice, offer or LNURL in `text` to its first characters.
+pub fn redact(text: &str) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_alphanumeric()) {
+        redacted.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(rest.len());
+        let word = &rest[..end];
+        let lowercase = word.to_ascii_lowercase();
+        let is_lightning = LIGHTNING_PREFIXES.iter().any(|prefix| lowercase.starts_with(prefix));
+        if is_lightning && word.len() > REDACTED_PREFIX_LEN {
+            redacted.push_str(&word[..REDACTED_PREFIX_LEN]);
+            redacted.push('…');
+        } else {
+            redacted.push_str(word);
+        }
+        rest = &rest[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+/// Formats the message and fields of an event or span as `message key=value ...`.
+#[derive(Default)]
+struct FieldFormatter {
+    message: String,
+    fields: Vec<String>,
+}
+
+impl FieldFormatter {
+    fn finish(self) -> String {
+        let mut parts = self.fields;
+        if !self.message.is_empty() {
+            parts.insert(0, self.message);
+        }
+        parts.join(" ")
+    }
+}
+
+impl Visit for FieldFormatter {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push(format!("{}={}", field.name(), value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+}
+
+/// Formatted fields of a span, kept in its extensions.
+struct SpanFields(Vec<String>);
+
+/// Records every event into a [`LogBuffer`].
+struct BufferLayer(LogBuffer);
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for BufferLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut formatter = FieldFormatter::default();
+        attrs.record(&mut formatter);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanFields(formatter.fields));
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let mut formatter = FieldFormatter::default();
+        values.record(&mut formatter);
+        if let Some(span) = ctx.span(id) {
+            if let Some(fields) = span.extensions_mut().get_mut::<SpanFields>() {
+                fields.0.extend(formatter.fields);
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut context = Vec::new();
+        for span in ctx.event_scope(event).into_iter().flat_map(|scope| scope.from_root()) {
+            let fields = span.extensions().get::<SpanFields>().map(|f| f.0.join(" "));
+            match fields.filter(|f| !f.is_empty()) {
+                Some(fields) => context.push(format!("{}{{{}}}", span.name(), fields)),
+                None => context.push(span.name().to_string()),
+            }
+        }
+        let mut formatter = FieldFormatter::default();
+        event.record(&mut formatter);
+        context.push(formatter.finish());
+
+        let metadata = event.metadata();
+        self.0.push(LogEntry {
+            at_ms: unix_timestamp_millis(),
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: context.join(": "),
+        });
+    }
+}
+
+/// Forwards events to the `log` logger, which eframe's `WebLogger` prints to the console.
+#[cfg(target_arch = "wasm32")]
+struct ConsoleLayer;
+
+#[cfg(target_arch = "wasm32")]
+impl<S: Subscriber> Layer<S> for ConsoleLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let level = match *metadata.level() {
+            Level::ERROR => log::Level::Error,
+            Level::WARN => log::Level::Warn,
+            Level::INFO => log::Level::Info,
+            Level::DEBUG => log::Level::Debug,
+            _ => log::Level::Trace,
+        };
+        let mut formatter = FieldFormatter::default();
+        event.record(&mut formatter);
+        log::log!(target: metadata.target(), level, "{}", formatter.finish());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVOICE: &str = "lnbcrt10u1pjq7x9dpp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypq";
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(redact("no invoice here"), "no invoice here");
+        assert_eq!(
+            redact(&format!("Failed to pay '{}': route not found", INVOICE)),
+            "Failed to pay 'lnbcrt10u1pj…': route not found"
+        );
+        assert_eq!(redact(&INVOICE.to_ascii_uppercase()), "LNBCRT10U1PJ…");
+        let offer = "offer lno1qgsqvgnwgcg35z6ee2h3yczraddm72xrfua9";
+        assert_eq!(redact(offer), "offer lno1qgsqvgnw…");
+        let refund = "refund lnr1qqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfq";
+        assert_eq!(redact(refund), "refund lnr1qqsyqcyq…");
+        // Short words with a lightning prefix are kept whole
+        assert_eq!(redact("lnbc lno1"), "lnbc lno1");
+    }
+
+    #[test]
+    fn test_log_buffer() {
+        let buffer = LogBuffer::default();
+        for i in 0..LOG_ENTRIES_KEPT + 2 {
+            let level = if i % 2 == 0 { Level::DEBUG } else { Level::WARN };
+            let message = i.to_string();
+            buffer.push(LogEntry { at_ms: 0, level, target: String::new(), message });
+        }
+
+        let all = buffer.entries(Level::TRACE);
+        assert_eq!(all.len(), LOG_ENTRIES_KEPT);
+        assert_eq!(all[0].message, "2");
+        let warnings = buffer.entries(Level::WARN);
+        assert_eq!(warnings.len(), LOG_ENTRIES_KEPT / 2);
+        assert!(warnings.iter().all(|entry| entry.level == Level::WARN));
+        assert!(buffer.entries(Level::ERROR).is_empty());
+
+        buffer.clear();
+        assert!(buffer.entries(Level::TRACE).is_empty());
+    }
+}
@@ -1,6 +1,35 @@
+mod address;
+mod amount;
 mod app;
+mod balance_changes;
+mod bip21;
+#[cfg(not(target_arch = "wasm32"))]
+mod chain_check;
+#[cfg(target_arch = "wasm32")]
+mod clipboard;
+mod cli;
 mod config;
+#[cfg(any(target_arch = "wasm32", test))]
+mod cors;
+#[cfg(not(target_arch = "wasm32"))]
+mod diff;
+mod errors;
+mod esplora;
+mod explorer;
+mod export;
+mod fees;
+mod fiat;
+mod health;
+mod liquidity;
+mod lnurl;
+mod logs;
+mod network;
+#[cfg(not(target_arch = "wasm32"))]
+mod secrets;
+mod spendable;
 mod state;
+mod storage;
+mod sweeps;
 mod task;
 mod ui;
 
@@ -9,6 +38,20 @@ mod ui;
 fn main() -> eframe::Result<()> {
     use eframe::egui;
 
+    let startup_options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(cli::ParsedArgs::Run(options)) => options,
+        Ok(cli::ParsedArgs::Help) => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let logs = logs::init();
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1200.0, 800.0])
@@ -19,7 +62,7 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "LDK Server GUI",
         options,
-        Box::new(|cc| Ok(Box::new(app::LdkServerApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(app::LdkServerApp::new(cc, startup_options, logs)))),
     )
 }
 
@@ -30,10 +73,11 @@ fn main() {
 
     // Redirect tracing to console.log
     eframe::WebLogger::init(log::LevelFilter::Debug).ok();
+    let logs = logs::init();
 
     let web_options = eframe::WebOptions::default();
 
-    wasm_bindgen_futures::spawn_local(async {
+    wasm_bindgen_futures::spawn_local(async move {
         // Get the canvas element
         let document = web_sys::window()
             .expect("No window")
@@ -50,7 +94,10 @@ fn main() {
             .start(
                 canvas,
                 web_options,
-                Box::new(|cc| Ok(Box::new(app::LdkServerApp::new(cc)))),
+                Box::new(|cc| {
+                    let options = cli::StartupOptions::default();
+                    Ok(Box::new(app::LdkServerApp::new(cc, options, logs)))
+                }),
             )
             .await;
 
@@ -0,0 +1,127 @@
+//! Bitcoin networks ldk-server can run on.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Leading zero hex digits every block hash of a public network has, even at its minimum
+/// difficulty: testnet blocks have at least 8 and signet blocks at least 5.
+const MIN_PUBLIC_BLOCK_HASH_ZEROS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Network {
+    #[default]
+    Bitcoin,
+    Testnet,
+    Testnet4,
+    Signet,
+    Regtest,
+}
+
+impl Network {
+    pub const ALL: [Network; 5] =
+        [Network::Bitcoin, Network::Testnet, Network::Testnet4, Network::Signet, Network::Regtest];
+
+    /// Name used for the network in the server config.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Network::Bitcoin => "bitcoin",
+            Network::Testnet => "testnet",
+            Network::Testnet4 => "testnet4",
+            Network::Signet => "signet",
+            Network::Regtest => "regtest",
+        }
+    }
+
+    /// The network exactly as the server config names it, without the aliases `from_str`
+    /// accepts.
+    pub fn from_config_name(name: &str) -> Option<Network> {
+        Self::ALL.into_iter().find(|network| network.as_str() == name)
+    }
+
+    /// Hash of the network's genesis block. Custom signets share the default signet's.
+    pub fn genesis_hash(&self) -> &'static str {
+        match self {
+            Network::Bitcoin => "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f",
+            Network::Testnet => "000000000933ea01ad0ee984209779baaec3ced90fa3f408719526f8d77f4943",
+            Network::Testnet4 => {
+                "00000000da84f2bafbbc53dee25a72ae507ff4914b867c565be350b0da8bf043"
+            }
+            Network::Signet => "00000008819873e925422c1ff0f99f7cc9bbb232af63a077a480a3633bee1ef6",
+            Network::Regtest => "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+        }
+    }
+
+    /// The network whose genesis block has `hash`.
+    pub fn from_genesis_hash(hash: &str) -> Option<Network> {
+        Self::ALL.into_iter().find(|network| network.genesis_hash() == hash)
+    }
+
+    /// The network a block hash must be from, as far as the hash alone tells: a genesis block,
+    /// or regtest for proof of work below any public network's. `None` when it can't be told.
+    pub fn from_block_hash(hash: &str) -> Option<Network> {
+        if let Some(network) = Self::from_genesis_hash(hash) {
+            return Some(network);
+        }
+        let zeros = hash.chars().take_while(|c| *c == '0').count();
+        (hash.len() == 64 && zeros < MIN_PUBLIC_BLOCK_HASH_ZEROS).then_some(Network::Regtest)
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses the config names, plus "mainnet" for bitcoin.
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "mainnet" => Ok(Network::Bitcoin),
+            name => Self::from_config_name(name).ok_or_else(|| format!("Unknown network '{}'", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_names_round_trip() {
+        for network in Network::ALL {
+            assert_eq!(network.to_string().parse::<Network>(), Ok(network));
+            assert_eq!(Network::from_config_name(network.as_str()), Some(network));
+        }
+    }
+
+    #[test]
+    fn test_network_from_str() {
+        assert_eq!("mainnet".parse::<Network>(), Ok(Network::Bitcoin));
+        assert_eq!(" signet ".parse::<Network>(), Ok(Network::Signet));
+        assert!("".parse::<Network>().is_err());
+        assert!("testnet3".parse::<Network>().is_err());
+
+        // The server itself only accepts the config names
+        assert_eq!(Network::from_config_name("mainnet"), None);
+    }
+
+    #[test]
+    fn test_network_from_block_hash() {
+        for network in Network::ALL {
+            assert_eq!(Network::from_genesis_hash(network.genesis_hash()), Some(network));
+            assert_eq!(Network::from_block_hash(network.genesis_hash()), Some(network));
+        }
+
+        let regtest = "3b5f2a8c1d7e9f0a4b6c8d2e1f3a5b7c9d0e2f4a6b8c1d3e5f7a9b0c2d4e6f8a";
+        assert_eq!(Network::from_block_hash(regtest), Some(Network::Regtest));
+        // Proof of work like mainnet's could be from any public network
+        let mainnet = "0000000000000000000320283a032748cef8227873ff4872689bf23f1cda83a5";
+        assert_eq!(Network::from_block_hash(mainnet), None);
+        let signet = "000000f0aa4cfb8c8a3ba9e4e8a94d3e9ac1f7b82c2b3a5d1e6f1e4f0c9b2d7a";
+        assert_eq!(Network::from_block_hash(signet), None);
+        assert_eq!(Network::from_block_hash(""), None);
+    }
+}
@@ -0,0 +1,68 @@
+//! Storage of connection profile API keys in the OS credential store (Keychain, Windows
+//! Credential Manager, Secret Service).
+//!
+//! A profile whose key lives in the keyring keeps only the entry name in `keyring_account` and an
+//! empty `api_key`, so the profile file written by eframe contains no secret.
+
+use crate::state::ConnectionProfile;
+
+/// Service name the entries are filed under.
+const SERVICE: &str = "ldk-server-gui";
+
+/// Keyring entry name for a profile's API key.
+fn profile_account(profile_name: &str) -> String {
+    format!("profile/{}/api_key", profile_name)
+}
+
+fn entry(account: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, account).map_err(|e| format!("OS keyring unavailable: {}", e))
+}
+
+/// Move the profile's API key into the keyring. On failure the key stays in the profile.
+pub fn move_to_keyring(profile: &mut ConnectionProfile) -> Result<(), String> {
+    if profile.keyring_account.is_some() {
+        return Ok(());
+    }
+    let account = profile_account(&profile.name);
+    entry(&account)?
+        .set_password(&profile.api_key)
+        .map_err(|e| format!("Could not write to the OS keyring: {}", e))?;
+    profile.api_key.clear();
+    profile.keyring_account = Some(account);
+    Ok(())
+}
+
+/// Move the API key back into the profile and delete its keyring entry.
+pub fn move_to_profile(profile: &mut ConnectionProfile) -> Result<(), String> {
+    if profile.keyring_account.is_none() {
+        return Ok(());
+    }
+    profile.api_key = api_key(profile)?;
+    forget(profile)?;
+    profile.keyring_account = None;
+    Ok(())
+}
+
+/// The profile's API key, read from the keyring if it is stored there.
+pub fn api_key(profile: &ConnectionProfile) -> Result<String, String> {
+    let Some(account) = &profile.keyring_account else {
+        return Ok(profile.api_key.clone());
+    };
+    entry(account)?.get_password().map_err(|e| match e {
+        keyring::Error::NoEntry => {
+            format!("The API key of profile '{}' is missing from the OS keyring", profile.name)
+        }
+        e => format!("Could not read from the OS keyring: {}", e),
+    })
+}
+
+/// Delete the profile's keyring entry, if it has one. A missing entry is not an error.
+pub fn forget(profile: &ConnectionProfile) -> Result<(), String> {
+    let Some(account) = &profile.keyring_account else {
+        return Ok(());
+    };
+    match entry(account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Could not delete from the OS keyring: {}", e)),
+    }
+}
@@ -0,0 +1,138 @@
+//! Why the spendable on-chain balance is less than the total.
+
+use ldk_server_client::ldk_server_protos::api::GetBalancesResponse;
+use ldk_server_client::ldk_server_protos::types::pending_sweep_balance::BalanceType;
+
+/// Part of the difference between the total and the spendable on-chain balance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortfallKind {
+    AnchorReserve,
+    UnconfirmedSweeps,
+    /// What the API doesn't itemize
+    Unaccounted,
+}
+
+impl ShortfallKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ShortfallKind::AnchorReserve => "Anchor channel reserve",
+            ShortfallKind::UnconfirmedSweeps => "Unconfirmed sweeps",
+            ShortfallKind::Unaccounted => "Unaccounted",
+        }
+    }
+
+    pub fn explanation(&self) -> &'static str {
+        match self {
+            ShortfallKind::AnchorReserve => {
+                "Kept aside to pay the fees of bumping a force-close transaction of an anchor \
+                 channel; it shrinks as channels are closed."
+            }
+            ShortfallKind::UnconfirmedSweeps => {
+                "Funds of closed channels swept back to the wallet by a transaction that hasn't \
+                 confirmed yet."
+            }
+            ShortfallKind::Unaccounted => {
+                "Not itemized by the node, usually incoming transactions from others that haven't \
+                 confirmed yet. They become spendable once confirmed."
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortfallItem {
+    pub kind: ShortfallKind,
+    pub amount_sats: u64,
+}
+
+/// Itemize `total_onchain_balance_sats - spendable_onchain_balance_sats`. The items always add
+/// up to the difference: each known cause is capped at what is left of it, and the rest is
+/// [`ShortfallKind::Unaccounted`]. Items of zero are left out.
+pub fn spendable_shortfall(balances: &GetBalancesResponse) -> Vec<ShortfallItem> {
+    let mut remaining =
+        balances.total_onchain_balance_sats.saturating_sub(balances.spendable_onchain_balance_sats);
+    let unconfirmed_sweeps_sats = balances
+        .pending_balances_from_channel_closures
+        .iter()
+        .filter_map(|sweep| match &sweep.balance_type {
+            Some(BalanceType::BroadcastAwaitingConfirmation(b)) => Some(b.amount_satoshis),
+            _ => None,
+        })
+        .fold(0u64, u64::saturating_add);
+
+    let mut items = Vec::new();
+    for (kind, amount_sats) in [
+        (ShortfallKind::AnchorReserve, balances.total_anchor_channels_reserve_sats),
+        (ShortfallKind::UnconfirmedSweeps, unconfirmed_sweeps_sats),
+        (ShortfallKind::Unaccounted, remaining),
+    ] {
+        let amount_sats = amount_sats.min(remaining);
+        if amount_sats > 0 {
+            remaining -= amount_sats;
+            items.push(ShortfallItem { kind, amount_sats });
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ldk_server_client::ldk_server_protos::types::{
+        BroadcastAwaitingConfirmation, PendingBroadcast, PendingSweepBalance,
+    };
+
+    fn sweep(balance_type: BalanceType) -> PendingSweepBalance {
+        PendingSweepBalance { balance_type: Some(balance_type) }
+    }
+
+    fn balances(total: u64, spendable: u64, reserve: u64, sweep_sats: u64) -> GetBalancesResponse {
+        GetBalancesResponse {
+            total_onchain_balance_sats: total,
+            spendable_onchain_balance_sats: spendable,
+            total_anchor_channels_reserve_sats: reserve,
+            pending_balances_from_channel_closures: vec![
+                sweep(BalanceType::BroadcastAwaitingConfirmation(BroadcastAwaitingConfirmation {
+                    amount_satoshis: sweep_sats,
+                    ..Default::default()
+                })),
+                sweep(BalanceType::PendingBroadcast(PendingBroadcast {
+                    amount_satoshis: 1_000_000,
+                    ..Default::default()
+                })),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn item(kind: ShortfallKind, amount_sats: u64) -> ShortfallItem {
+        ShortfallItem { kind, amount_sats }
+    }
+
+    #[test]
+    fn test_spendable_shortfall_adds_up() {
+        let items = spendable_shortfall(&balances(200_000, 140_000, 25_000, 30_000));
+        assert_eq!(
+            items,
+            vec![
+                item(ShortfallKind::AnchorReserve, 25_000),
+                item(ShortfallKind::UnconfirmedSweeps, 30_000),
+                item(ShortfallKind::Unaccounted, 5_000),
+            ]
+        );
+        assert_eq!(items.iter().map(|i| i.amount_sats).sum::<u64>(), 60_000);
+    }
+
+    #[test]
+    fn test_spendable_shortfall_capped() {
+        // A reserve larger than the wallet only accounts for what the wallet holds
+        let items = spendable_shortfall(&balances(10_000, 0, 25_000, 30_000));
+        assert_eq!(items, vec![item(ShortfallKind::AnchorReserve, 10_000)]);
+
+        // A sweep not yet counted in the total
+        let items = spendable_shortfall(&balances(100_000, 75_000, 25_000, 30_000));
+        assert_eq!(items, vec![item(ShortfallKind::AnchorReserve, 25_000)]);
+
+        assert!(spendable_shortfall(&balances(50_000, 50_000, 0, 0)).is_empty());
+    }
+}
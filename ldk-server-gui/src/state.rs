@@ -1,38 +1,100 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
 
-use crate::config::{ChainSourceConfig, ChainSourceType};
-use crate::task::ChannelTaskHandle;
+use serde::{Deserialize, Serialize};
+
+use crate::amount::AmountUnit;
+use crate::balance_changes::BalanceChanges;
+use crate::bip21::Bip21Uri;
+use crate::config::{
+    ChainSourceConfig, ChainSourceType, Lsps2ServiceSettings, NodeSettings, SectionUpdate,
+    DEFAULT_CONFIG_URL,
+};
+use crate::errors::TaskError;
+use crate::esplora::TxWatch;
+use crate::fees::FeeEstimates;
+use crate::fiat::{FiatRate, FIAT_CURRENCIES};
+use crate::liquidity::{LiquidityAlert, LiquidityThresholds};
+use crate::lnurl::LnurlInvoice;
+use crate::network::Network;
+use crate::task::{
+    CoalescePolicy, Progress, RefreshSchedule, RefreshScheduler, TaskOutput, TaskRegistry,
+    TaskTimings, DEFAULT_MAX_CONCURRENT_REQUESTS, DEFAULT_REQUEST_TIMEOUT_SECS,
+};
+use crate::ui::qr::QrTextures;
 use ldk_server_client::client::LdkServerClient;
 use ldk_server_client::ldk_server_protos::api::{
-    Bolt11ReceiveResponse, Bolt11SendResponse, Bolt12ReceiveResponse, Bolt12SendResponse,
-    CloseChannelResponse, ConnectPeerResponse, ForceCloseChannelResponse, GetBalancesResponse,
-    GetNodeInfoResponse, ListChannelsResponse, ListPaymentsResponse, OnchainReceiveResponse,
-    OnchainSendResponse, OpenChannelResponse, SpliceInResponse, SpliceOutResponse,
-    UpdateChannelConfigResponse,
+    GetBalancesResponse, GetNodeInfoResponse, ListChannelsResponse, ListPaymentsResponse,
 };
 use ldk_server_client::ldk_server_protos::types::PageToken;
 
-
 #[derive(Clone, PartialEq, Default)]
 pub enum ConnectionStatus {
     #[default]
     Disconnected,
+    /// Waiting for a first request to confirm the URL, API key and certificate
+    Connecting,
     Connected,
+    /// The server rejected the API key mid-session; waiting for a new one
+    AuthRequired,
+    /// Requests keep failing; the client is being re-established with backoff
+    Reconnecting { attempt: u32, next_attempt_at: u64 },
     Error(String),
 }
 
-#[derive(Clone, Copy, PartialEq, Default)]
+/// Number of recently used server URLs to remember.
+const MAX_RECENT_SERVER_URLS: usize = 5;
+
+/// Age in minutes past which a wallet sync reported by the node is shown as stale.
+pub const DEFAULT_STALE_SYNC_MINS: u64 = 15;
+
+/// Blocks the node's best block may trail the public chain tip before it is warned about.
+pub const DEFAULT_MAX_BLOCK_LAG: u32 = 6;
+
+/// Connection form fields that are validated before connecting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionField {
+    ServerUrl,
+    ApiKey,
+    /// The certificate path or pasted PEM, whichever source is selected
+    #[allow(dead_code)] // Used only on native
+    TlsCert,
+    #[allow(dead_code)] // Used only on native
+    Proxy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum ActiveTab {
     #[default]
+    Overview,
     NodeInfo,
     Balances,
     Channels,
     Payments,
     Lightning,
     Onchain,
+    Logs,
+}
+
+impl ActiveTab {
+    /// Look up a tab by the name used on the command line, e.g. `payments` or `node-info`.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace('_', "-").as_str() {
+            "overview" => Some(ActiveTab::Overview),
+            "node-info" | "nodeinfo" => Some(ActiveTab::NodeInfo),
+            "balances" => Some(ActiveTab::Balances),
+            "channels" => Some(ActiveTab::Channels),
+            "payments" => Some(ActiveTab::Payments),
+            "lightning" => Some(ActiveTab::Lightning),
+            "onchain" | "on-chain" => Some(ActiveTab::Onchain),
+            "logs" => Some(ActiveTab::Logs),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Default, Clone)]
@@ -47,7 +109,7 @@ pub struct OpenChannelForm {
     pub cltv_expiry_delta: String,
 }
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, PartialEq)]
 pub struct Bolt11ReceiveForm {
     pub amount_msat: String,
     pub description: String,
@@ -60,7 +122,7 @@ pub struct Bolt11SendForm {
     pub amount_msat: String,
 }
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, PartialEq)]
 pub struct Bolt12ReceiveForm {
     pub description: String,
     pub amount_msat: String,
@@ -79,9 +141,139 @@ pub struct Bolt12SendForm {
 #[derive(Default, Clone)]
 pub struct OnchainSendForm {
     pub address: String,
-    pub amount_sats: String,
+    /// Amount in the current on-chain display unit
+    pub amount: String,
     pub send_all: bool,
     pub fee_rate_sat_per_vb: String,
+    /// Details of the BIP21 URI the address was extracted from, if any
+    pub uri: Option<Bip21Uri>,
+}
+
+#[derive(Default, Clone)]
+pub struct OnchainReceiveForm {
+    /// Requested amount in the current on-chain display unit
+    pub amount: String,
+    pub label: String,
+    /// Local label attached to the next generated address
+    pub address_label: String,
+}
+
+/// An on-chain address previously generated by this GUI.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GeneratedAddress {
+    pub address: String,
+    pub created_at: u64,
+    #[serde(default)]
+    pub label: String,
+    /// How many times the address has been displayed in the Receive view
+    #[serde(default)]
+    pub times_shown: u32,
+    #[serde(default)]
+    pub copied: bool,
+}
+
+/// Where the TLS certificate used to connect comes from.
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum TlsCertSource {
+    #[default]
+    File,
+    /// PEM text pasted into the connection settings
+    Pem,
+}
+
+/// Results of the periodic health check while connected.
+#[derive(Default)]
+pub struct ConnectionHealth {
+    pub last_check_at: Option<u64>,
+    pub last_success_at: Option<u64>,
+    /// Round-trip time of the last successful check
+    pub latency_ms: Option<u64>,
+    pub consecutive_failures: u32,
+}
+
+/// A saved set of connection settings.
+///
+/// Profiles are persisted only through eframe storage; the API key is never placed in egui
+/// memory, and is moved to the OS keyring when that is enabled.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ConnectionProfile {
+    pub name: String,
+    pub server_url: String,
+    pub api_key: String,
+    pub tls_cert_path: String,
+    pub network: String,
+    /// SHA-256 fingerprint the certificate must have; a mismatch is confirmed before connecting
+    #[serde(default)]
+    pub pinned_cert_fingerprint: Option<String>,
+    /// SOCKS5 proxy (`host:port`) to connect through; empty for a direct connection
+    #[serde(default)]
+    pub proxy: String,
+    /// OS keyring entry holding the API key, in which case `api_key` is left empty
+    #[serde(default)]
+    pub keyring_account: Option<String>,
+}
+
+/// Connection settings and preferences remembered between runs.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistedSettings {
+    pub server_url: String,
+    pub tls_cert_path: String,
+    pub network: String,
+    pub mempool_api_url: String,
+    pub remember_api_key: bool,
+    /// Only stored when `remember_api_key` is set
+    pub api_key: Option<String>,
+    pub use_keyring: bool,
+    pub tls_cert_source: TlsCertSource,
+    pub insecure_http: bool,
+    pub proxy: String,
+    pub remember_tls_cert_pem: bool,
+    /// Only stored when `remember_tls_cert_pem` is set
+    pub tls_cert_pem: Option<String>,
+    /// Zero when not set, in which case the default is kept
+    pub request_timeout_secs: u64,
+    /// Zero when not set, in which case the default is kept
+    pub max_concurrent_requests: usize,
+    pub recent_server_urls: Vec<String>,
+    /// Background refresh settings changed from their defaults
+    pub refresh_schedules: BTreeMap<TaskKind, RefreshSchedule>,
+    pub refresh_in_background: bool,
+    pub data_age_thresholds: DataAgeThresholds,
+    /// Zero when not set, in which case the default is kept
+    pub stale_sync_mins: u64,
+    /// Zero when not set, in which case the default is kept
+    pub max_block_lag: u32,
+    pub liquidity_thresholds: LiquidityThresholds,
+    /// Currency fiat values are shown in; `None` when fiat values are off
+    pub fiat_currency: Option<String>,
+    pub display_unit: AmountUnit,
+}
+
+/// Periodic balance refresh after an on-chain send, until the new balance shows up.
+#[derive(Clone)]
+pub struct BalanceRefresh {
+    pub started_at: u64,
+    pub last_requested_at: u64,
+    /// Total on-chain balance before the send; refreshing stops once it changes
+    pub baseline_total_sats: Option<u64>,
+}
+
+/// A saved on-chain destination.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AddressBookEntry {
+    pub label: String,
+    pub address: String,
+    /// Network the address belongs to; entries are only offered on the same network
+    pub network: String,
+}
+
+#[derive(Default, Clone)]
+pub struct AddressBookForm {
+    pub label: String,
+    pub address: String,
+    /// Index into the address book of the entry being edited, `None` when adding
+    pub editing: Option<usize>,
 }
 
 #[derive(Default, Clone)]
@@ -99,6 +291,8 @@ pub struct UpdateChannelConfigForm {
     pub forwarding_fee_proportional_millionths: String,
     pub forwarding_fee_base_msat: String,
     pub cltv_expiry_delta: String,
+    /// Apply the config to every channel instead of the one given by the IDs above
+    pub all_channels: bool,
 }
 
 #[derive(Default, Clone)]
@@ -171,6 +365,227 @@ impl ChainSourceForm {
     }
 }
 
+/// Editable `[node]` section of the config file (used on native only). Address lists are
+/// entered comma separated.
+#[allow(dead_code)]
+#[derive(Default, Clone)]
+pub struct NodeSettingsForm {
+    pub network: String,
+    pub alias: String,
+    pub listening_addresses: String,
+    pub announcement_addresses: String,
+    pub rest_service_address: String,
+    /// `dir_path` of the `[storage.disk]` section, edited along with the node settings
+    pub storage_dir: String,
+}
+
+#[allow(dead_code)]
+impl NodeSettingsForm {
+    pub fn from_settings(settings: &NodeSettings, storage_dir: &str) -> Self {
+        Self {
+            network: settings.network.clone(),
+            alias: settings.alias.clone(),
+            listening_addresses: settings.listening_addresses.join(", "),
+            announcement_addresses: settings.announcement_addresses.join(", "),
+            rest_service_address: settings.rest_service_address.clone(),
+            storage_dir: storage_dir.to_string(),
+        }
+    }
+
+    pub fn to_settings(&self) -> NodeSettings {
+        fn split_list(text: &str) -> Vec<String> {
+            text.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect()
+        }
+        NodeSettings {
+            network: self.network.clone(),
+            alias: self.alias.trim().to_string(),
+            listening_addresses: split_list(&self.listening_addresses),
+            announcement_addresses: split_list(&self.announcement_addresses),
+            rest_service_address: self.rest_service_address.trim().to_string(),
+        }
+    }
+
+    /// The config file changes made in this form.
+    pub fn section_updates(&self) -> Vec<SectionUpdate> {
+        vec![
+            SectionUpdate::Node(self.to_settings()),
+            SectionUpdate::StorageDir(self.storage_dir.trim().to_string()),
+        ]
+    }
+}
+
+/// Editable `[liquidity.lsps2_service]` section (used on native only). Numbers are kept as
+/// entered and parsed when saving.
+#[allow(dead_code)]
+#[derive(Default, Clone)]
+pub struct Lsps2ServiceForm {
+    pub enabled: bool,
+    pub advertise_service: bool,
+    pub channel_opening_fee_ppm: String,
+    pub channel_over_provisioning_ppm: String,
+    pub min_channel_opening_fee_msat: String,
+    pub min_channel_lifetime: String,
+    pub max_client_to_self_delay: String,
+    pub min_payment_size_msat: String,
+    pub max_payment_size_msat: String,
+    pub client_trusts_lsp: bool,
+    pub require_token: String,
+}
+
+#[allow(dead_code)]
+impl Lsps2ServiceForm {
+    pub fn from_settings(settings: Option<&Lsps2ServiceSettings>) -> Self {
+        let Some(settings) = settings else {
+            return Self::default();
+        };
+        Self {
+            enabled: true,
+            advertise_service: settings.advertise_service,
+            channel_opening_fee_ppm: settings.channel_opening_fee_ppm.to_string(),
+            channel_over_provisioning_ppm: settings.channel_over_provisioning_ppm.to_string(),
+            min_channel_opening_fee_msat: settings.min_channel_opening_fee_msat.to_string(),
+            min_channel_lifetime: settings.min_channel_lifetime.to_string(),
+            max_client_to_self_delay: settings.max_client_to_self_delay.to_string(),
+            min_payment_size_msat: settings.min_payment_size_msat.to_string(),
+            max_payment_size_msat: settings.max_payment_size_msat.to_string(),
+            client_trusts_lsp: settings.client_trusts_lsp,
+            require_token: settings.require_token.clone().unwrap_or_default(),
+        }
+    }
+
+    /// The section to write, `None` when the service is disabled.
+    pub fn to_settings(&self) -> Result<Option<Lsps2ServiceSettings>, String> {
+        if !self.enabled {
+            return Ok(None);
+        }
+        fn number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+            value.trim().parse().map_err(|_| format!("{} must be a whole number", name))
+        }
+        let token = self.require_token.trim();
+        let settings = Lsps2ServiceSettings {
+            advertise_service: self.advertise_service,
+            channel_opening_fee_ppm: number("Opening fee", &self.channel_opening_fee_ppm)?,
+            channel_over_provisioning_ppm: number(
+                "Over-provisioning",
+                &self.channel_over_provisioning_ppm,
+            )?,
+            min_channel_opening_fee_msat: number(
+                "Minimum opening fee",
+                &self.min_channel_opening_fee_msat,
+            )?,
+            min_channel_lifetime: number("Minimum channel lifetime", &self.min_channel_lifetime)?,
+            max_client_to_self_delay: number(
+                "Maximum client to_self_delay",
+                &self.max_client_to_self_delay,
+            )?,
+            min_payment_size_msat: number("Minimum payment size", &self.min_payment_size_msat)?,
+            max_payment_size_msat: number("Maximum payment size", &self.max_payment_size_msat)?,
+            client_trusts_lsp: self.client_trusts_lsp,
+            require_token: (!token.is_empty()).then(|| token.to_string()),
+        };
+        settings.validate()?;
+        Ok(Some(settings))
+    }
+}
+
+/// Steps of the new-config wizard, in order (used on native only).
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum NewConfigStep {
+    #[default]
+    Network,
+    Storage,
+    RestAddress,
+    ChainSource,
+    Location,
+}
+
+#[allow(dead_code)]
+impl NewConfigStep {
+    pub const ALL: [NewConfigStep; 5] = [
+        NewConfigStep::Network,
+        NewConfigStep::Storage,
+        NewConfigStep::RestAddress,
+        NewConfigStep::ChainSource,
+        NewConfigStep::Location,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            NewConfigStep::Network => "Network",
+            NewConfigStep::Storage => "Storage Directory",
+            NewConfigStep::RestAddress => "REST Service Address",
+            NewConfigStep::ChainSource => "Chain Source",
+            NewConfigStep::Location => "Save Location",
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|step| step == self).unwrap_or_default()
+    }
+
+    pub fn next(&self) -> Option<NewConfigStep> {
+        Self::ALL.get(self.index() + 1).copied()
+    }
+
+    pub fn previous(&self) -> Option<NewConfigStep> {
+        self.index().checked_sub(1).map(|i| Self::ALL[i])
+    }
+}
+
+/// Settings collected by the new-config wizard (used on native only).
+#[allow(dead_code)]
+pub struct NewConfigWizard {
+    pub step: NewConfigStep,
+    pub network: String,
+    pub storage_dir: String,
+    pub rest_service_address: String,
+    pub chain_source: ChainSourceForm,
+    /// Where the config file is written
+    pub path: String,
+    /// Why the current step can't be completed yet
+    pub error: Option<String>,
+}
+
+impl Default for NewConfigWizard {
+    fn default() -> Self {
+        let path = std::env::current_dir()
+            .map(|dir| dir.join("ldk-server-config.toml").display().to_string())
+            .unwrap_or_else(|_| "ldk-server-config.toml".to_string());
+        Self {
+            step: NewConfigStep::default(),
+            network: "regtest".to_string(),
+            storage_dir: "/tmp/ldk-server".to_string(),
+            rest_service_address: "127.0.0.1:3002".to_string(),
+            chain_source: ChainSourceForm::default(),
+            path,
+            error: None,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl NewConfigWizard {
+    pub fn node_settings(&self) -> NodeSettings {
+        NodeSettings {
+            network: self.network.clone(),
+            rest_service_address: self.rest_service_address.trim().to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Config file contents awaiting confirmation in the diff preview (used on native only).
+#[allow(dead_code)]
+pub struct PendingConfigSave {
+    pub path: String,
+    pub contents: String,
+    /// Unified diff between the current file and `contents`
+    pub diff: String,
+    /// Applied to the session once the file has been written
+    pub updates: Vec<SectionUpdate>,
+}
+
 #[derive(Default, Clone)]
 pub struct Forms {
     pub open_channel: OpenChannelForm,
@@ -179,6 +594,8 @@ pub struct Forms {
     pub bolt12_receive: Bolt12ReceiveForm,
     pub bolt12_send: Bolt12SendForm,
     pub onchain_send: OnchainSendForm,
+    pub onchain_receive: OnchainReceiveForm,
+    pub address_book: AddressBookForm,
     pub splice_in: SpliceForm,
     pub splice_out: SpliceForm,
     pub update_channel_config: UpdateChannelConfigForm,
@@ -186,6 +603,10 @@ pub struct Forms {
     pub connect_peer: ConnectPeerForm,
     #[allow(dead_code)]
     pub chain_source: ChainSourceForm,
+    #[allow(dead_code)]
+    pub node_settings: NodeSettingsForm,
+    #[allow(dead_code)]
+    pub lsps2_service: Lsps2ServiceForm,
 }
 
 pub struct StatusMessage {
@@ -223,121 +644,579 @@ impl StatusMessage {
     }
 }
 
-pub struct AsyncTasks {
-    pub node_info: Option<ChannelTaskHandle<GetNodeInfoResponse>>,
-    pub balances: Option<ChannelTaskHandle<GetBalancesResponse>>,
-    pub channels: Option<ChannelTaskHandle<ListChannelsResponse>>,
-    pub payments: Option<ChannelTaskHandle<ListPaymentsResponse>>,
-    pub onchain_receive: Option<ChannelTaskHandle<OnchainReceiveResponse>>,
-    pub onchain_send: Option<ChannelTaskHandle<OnchainSendResponse>>,
-    pub bolt11_receive: Option<ChannelTaskHandle<Bolt11ReceiveResponse>>,
-    pub bolt11_send: Option<ChannelTaskHandle<Bolt11SendResponse>>,
-    pub bolt12_receive: Option<ChannelTaskHandle<Bolt12ReceiveResponse>>,
-    pub bolt12_send: Option<ChannelTaskHandle<Bolt12SendResponse>>,
-    pub open_channel: Option<ChannelTaskHandle<OpenChannelResponse>>,
-    pub close_channel: Option<ChannelTaskHandle<CloseChannelResponse>>,
-    pub force_close_channel: Option<ChannelTaskHandle<ForceCloseChannelResponse>>,
-    pub splice_in: Option<ChannelTaskHandle<SpliceInResponse>>,
-    pub splice_out: Option<ChannelTaskHandle<SpliceOutResponse>>,
-    pub update_channel_config: Option<ChannelTaskHandle<UpdateChannelConfigResponse>>,
-    pub connect_peer: Option<ChannelTaskHandle<ConnectPeerResponse>>,
-}
-
-impl Default for AsyncTasks {
-    fn default() -> Self {
-        Self {
-            node_info: None,
-            balances: None,
-            channels: None,
-            payments: None,
-            onchain_receive: None,
-            onchain_send: None,
-            bolt11_receive: None,
-            bolt11_send: None,
-            bolt12_receive: None,
-            bolt12_send: None,
-            open_channel: None,
-            close_channel: None,
-            force_close_channel: None,
-            splice_in: None,
-            splice_out: None,
-            update_channel_config: None,
-            connect_peer: None,
+/// Operations run as background tasks, at most one of each kind per session. Completed tasks are
+/// handled in this order. The result type of each kind is noted in its doc comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum TaskKind {
+    /// Connection probe; the client is only kept once it succeeds.
+    /// `(Arc<LdkServerClient>, GetNodeInfoResponse)`
+    Connect,
+    /// Network the connected node's best block belongs to. `Network`
+    NetworkCheck,
+    /// Background ping, kept separate from user-initiated node info fetches. Latency in ms, `u64`
+    HealthCheck,
+    /// `GetNodeInfoResponse`
+    NodeInfo,
+    /// `GetBalancesResponse`
+    Balances,
+    /// `ListChannelsResponse`
+    Channels,
+    /// The fetched page and whether it continues the already loaded payments.
+    /// `(ListPaymentsResponse, bool)`
+    Payments,
+    /// Generated address and the label to attach to it. `(OnchainReceiveResponse, String)`
+    OnchainReceive,
+    /// `OnchainSendResponse`
+    OnchainSend,
+    /// Watched txid and its confirmation count. `(String, u32)`
+    TxConfirmations,
+    /// `FeeEstimates`
+    FeeEstimates,
+    /// Height of the public chain tip the node's best block is compared with. `u32`
+    PublicTip,
+    /// BTC price in the fiat currency selected in the settings. `FiatRate`
+    FiatRate,
+    /// Connectivity check of the chain source settings being edited. `String`
+    ChainSourceCheck,
+    /// Config fetched for the wasm Load Config dialog, with the URL it came from.
+    /// `(String, String)`
+    ConfigFetch,
+    /// Config file picked in the wasm Load Config dialog, with its name; `None` if none was.
+    /// `Option<(String, String)>`
+    ConfigUpload,
+    /// Export saved to a file, or downloaded in the browser; where to, `None` if the save
+    /// dialog was cancelled. `Option<String>`
+    SaveFile,
+    /// `(Bolt11ReceiveResponse, Bolt11ReceiveForm)`
+    Bolt11Receive,
+    /// `Bolt11SendResponse`
+    Bolt11Send,
+    /// `LnurlInvoice`
+    LnurlPay,
+    /// `(Bolt12ReceiveResponse, Bolt12ReceiveForm)`
+    Bolt12Receive,
+    /// `Bolt12SendResponse`
+    Bolt12Send,
+    /// `OpenChannelResponse`
+    OpenChannel,
+    /// `CloseChannelResponse`
+    CloseChannel,
+    /// `ForceCloseChannelResponse`
+    ForceCloseChannel,
+    /// `SpliceInResponse`
+    SpliceIn,
+    /// `SpliceOutResponse`
+    SpliceOut,
+    /// `UpdateChannelConfigResponse`
+    UpdateChannelConfig,
+    /// Config update of every channel, one at a time, reporting progress. `u32`, the number of
+    /// channels updated
+    UpdateAllChannelConfigs,
+    /// `ConnectPeerResponse`
+    ConnectPeer,
+    /// Node info, balances, channels and the first payments page fetched at once. `SyncAll`
+    SyncAll,
+}
+
+impl TaskKind {
+    /// Description of a user-initiated operation, `None` for background refreshes.
+    pub fn operation_name(self) -> Option<&'static str> {
+        match self {
+            TaskKind::Payments => Some("Loading payments"),
+            TaskKind::OnchainReceive => Some("Generating an on-chain address"),
+            TaskKind::OnchainSend => Some("Sending on-chain"),
+            TaskKind::Bolt11Receive => Some("Creating a BOLT11 invoice"),
+            TaskKind::Bolt11Send => Some("Paying a BOLT11 invoice"),
+            TaskKind::LnurlPay => Some("Requesting an LNURL invoice"),
+            TaskKind::Bolt12Receive => Some("Creating a BOLT12 offer"),
+            TaskKind::Bolt12Send => Some("Paying a BOLT12 offer"),
+            TaskKind::OpenChannel => Some("Opening a channel"),
+            TaskKind::CloseChannel => Some("Closing a channel"),
+            TaskKind::ForceCloseChannel => Some("Force-closing a channel"),
+            TaskKind::SpliceIn => Some("Splicing in"),
+            TaskKind::SpliceOut => Some("Splicing out"),
+            TaskKind::UpdateChannelConfig => Some("Updating channel config"),
+            TaskKind::UpdateAllChannelConfigs => Some("Updating channel configs"),
+            TaskKind::ConnectPeer => Some("Connecting to a peer"),
+            TaskKind::Connect
+            | TaskKind::NetworkCheck
+            | TaskKind::HealthCheck
+            | TaskKind::NodeInfo
+            | TaskKind::Balances
+            | TaskKind::Channels
+            | TaskKind::TxConfirmations
+            | TaskKind::FeeEstimates
+            | TaskKind::PublicTip
+            | TaskKind::FiatRate
+            | TaskKind::ChainSourceCheck
+            | TaskKind::ConfigFetch
+            | TaskKind::ConfigUpload
+            | TaskKind::SaveFile
+            | TaskKind::SyncAll => None,
+        }
+    }
+
+    /// Whether the operation changes the node in a way the user needs to know the outcome of:
+    /// payments, channel changes and peer connections. An invoice or address lost on exit is
+    /// harmless.
+    pub fn is_mutating(self) -> bool {
+        matches!(
+            self,
+            TaskKind::OnchainSend
+                | TaskKind::Bolt11Send
+                | TaskKind::Bolt12Send
+                | TaskKind::OpenChannel
+                | TaskKind::CloseChannel
+                | TaskKind::ForceCloseChannel
+                | TaskKind::SpliceIn
+                | TaskKind::SpliceOut
+                | TaskKind::UpdateChannelConfig
+                | TaskKind::UpdateAllChannelConfigs
+                | TaskKind::ConnectPeer
+        )
+    }
+
+    /// How a request made while a fetch of this kind with other parameters is in flight is
+    /// handled, for the kinds started through [`TaskRegistry::request`].
+    pub fn coalesce_policy(self) -> CoalescePolicy {
+        match self {
+            // The page being loaded is of no use once the user goes back to the first one
+            TaskKind::Payments => CoalescePolicy::Replace,
+            // Estimates from an API that is no longer configured are of no use
+            TaskKind::FeeEstimates => CoalescePolicy::Replace,
+            // A price in the currency that was just deselected is of no use
+            TaskKind::FiatRate => CoalescePolicy::Replace,
+            // Settings edited during a check are checked once it is done
+            TaskKind::ChainSourceCheck => CoalescePolicy::Queue,
+            _ => CoalescePolicy::Ignore,
         }
     }
 }
 
+pub type AsyncTasks = TaskRegistry<TaskKind>;
+
+/// Result of each fetch of a [`TaskKind::SyncAll`], kept apart so that one failing does not
+/// discard the others.
+pub struct SyncAll {
+    pub node_info: Result<GetNodeInfoResponse, TaskError>,
+    pub balances: Result<GetBalancesResponse, TaskError>,
+    pub channels: Result<ListChannelsResponse, TaskError>,
+    pub payments: Result<ListPaymentsResponse, TaskError>,
+}
+
+impl SyncAll {
+    /// Each part as the result of the task that fetches it alone.
+    pub fn into_parts(self) -> [(TaskKind, Result<TaskOutput, TaskError>); 4] {
+        [
+            (TaskKind::NodeInfo, self.node_info.map(TaskOutput::new)),
+            (TaskKind::Balances, self.balances.map(TaskOutput::new)),
+            (TaskKind::Channels, self.channels.map(TaskOutput::new)),
+            (TaskKind::Payments, self.payments.map(|page| TaskOutput::new((page, false)))),
+        ]
+    }
+}
+
+/// Number of finished tasks whose timing is kept for the Diagnostics panel.
+pub const TASK_TIMINGS_KEPT: usize = 50;
+
+/// Resources refreshed in the background while connected, with their label in the settings and
+/// default schedule. Channels are only refreshed while one of them is not ready yet, and the
+/// public chain tip only where an Esplora API is known for the network.
+pub const REFRESHED_RESOURCES: [(TaskKind, &str, RefreshSchedule); 6] = [
+    (TaskKind::HealthCheck, "Health check", RefreshSchedule { interval_secs: 30, paused: false }),
+    (TaskKind::NodeInfo, "Node info", RefreshSchedule { interval_secs: 60, paused: false }),
+    (TaskKind::Balances, "Balances", RefreshSchedule { interval_secs: 30, paused: false }),
+    (TaskKind::Channels, "Pending channels", RefreshSchedule { interval_secs: 20, paused: false }),
+    (TaskKind::Payments, "Payments", RefreshSchedule { interval_secs: 120, paused: true }),
+    // Paused by default, as it tells the explorer when the GUI is in use
+    (TaskKind::PublicTip, "Public chain tip", RefreshSchedule { interval_secs: 300, paused: true }),
+];
+
+fn default_refresh_schedules() -> BTreeMap<TaskKind, RefreshSchedule> {
+    REFRESHED_RESOURCES.iter().map(|(kind, _, schedule)| (*kind, *schedule)).collect()
+}
+
 impl AsyncTasks {
-    pub fn any_pending(&self) -> bool {
-        self.node_info.is_some()
-            || self.balances.is_some()
-            || self.channels.is_some()
-            || self.payments.is_some()
-            || self.onchain_receive.is_some()
-            || self.onchain_send.is_some()
-            || self.bolt11_receive.is_some()
-            || self.bolt11_send.is_some()
-            || self.bolt12_receive.is_some()
-            || self.bolt12_send.is_some()
-            || self.open_channel.is_some()
-            || self.close_channel.is_some()
-            || self.force_close_channel.is_some()
-            || self.splice_in.is_some()
-            || self.splice_out.is_some()
-            || self.update_channel_config.is_some()
-            || self.connect_peer.is_some()
+    /// User-initiated operations still in flight, excluding background refreshes.
+    pub fn pending_operations(&self) -> Vec<&'static str> {
+        self.pending().filter_map(TaskKind::operation_name).collect()
+    }
+
+    /// Operations in flight that change the node, see [`TaskKind::is_mutating`].
+    #[allow(dead_code)] // Used only on native
+    pub fn pending_mutations(&self) -> Vec<&'static str> {
+        let mutating = self.pending().filter(|kind| kind.is_mutating());
+        mutating.filter_map(TaskKind::operation_name).collect()
     }
 }
 
-pub struct AppState {
+/// Progress of closing the window while operations that change the node are running.
+#[allow(dead_code)] // Used only on native
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ShutdownState {
+    #[default]
+    Running,
+    /// Closing was held back to ask the user whether to close anyway
+    Confirming,
+    /// Waiting for the running operations to finish, at most until the deadline in Unix
+    /// milliseconds
+    Draining { deadline_ms: u64 },
+    /// The app closes the window itself; the request is no longer held back
+    Closing,
+}
+
+/// Wallet of the node whose sync time is reported in its node info.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodeWallet {
+    Onchain,
+    Lightning,
+}
+
+impl NodeWallet {
+    pub fn label(self) -> &'static str {
+        match self {
+            NodeWallet::Onchain => "on-chain",
+            NodeWallet::Lightning => "lightning",
+        }
+    }
+}
+
+/// Wallets last synced more than `threshold_secs` before `now`, with their sync time. Wallets
+/// that never synced are left out, as the node may still be starting.
+pub fn stale_wallet_syncs(
+    info: &GetNodeInfoResponse, now: u64, threshold_secs: u64,
+) -> Vec<(NodeWallet, u64)> {
+    [
+        (NodeWallet::Onchain, info.latest_onchain_wallet_sync_timestamp),
+        (NodeWallet::Lightning, info.latest_lightning_wallet_sync_timestamp),
+    ]
+    .into_iter()
+    .filter_map(|(wallet, synced_at)| Some((wallet, synced_at?)))
+    .filter(|(_, synced_at)| now.saturating_sub(*synced_at) > threshold_secs)
+    .collect()
+}
+
+/// A server response and when it was fetched.
+pub struct Cached<T> {
+    pub data: T,
+    /// Unix time in seconds
+    pub fetched_at: u64,
+}
+
+impl<T> Cached<T> {
+    /// Wrap a response fetched just now.
+    pub fn new(data: T) -> Self {
+        Self { data, fetched_at: crate::ui::unix_timestamp_now() }
+    }
+}
+
+/// Ages in seconds past which cached data is shown as aging (amber) and as stale (red).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DataAgeThresholds {
+    pub aging_after_secs: u64,
+    pub stale_after_secs: u64,
+}
+
+impl Default for DataAgeThresholds {
+    fn default() -> Self {
+        Self { aging_after_secs: 60, stale_after_secs: 300 }
+    }
+}
+
+/// Connection, cached responses and in-flight requests of one node.
+pub struct NodeSession {
     // Connection settings
     pub server_url: String,
     pub api_key: String,
     #[allow(dead_code)] // Used only on native
     pub tls_cert_path: String,
+    #[allow(dead_code)] // Used only on native
+    pub tls_cert_source: TlsCertSource,
+    #[allow(dead_code)] // Used only on native
+    pub tls_cert_pem: String,
+    /// Connect over plain HTTP without a certificate; only allowed for loopback and onion hosts
+    #[allow(dead_code)] // Used only on native
+    pub insecure_http: bool,
+    /// SOCKS5 proxy (`host:port`), e.g. a local Tor daemon; empty for a direct connection
+    pub proxy: String,
+    /// Certificate source and the fingerprint (or read error) of the certificate it contained
+    #[allow(dead_code)] // Used only on native
+    pub tls_cert_fingerprint: Option<(String, Result<String, String>)>,
+    /// Fingerprint pinned by the selected profile
+    #[allow(dead_code)] // Used only on native
+    pub pinned_cert_fingerprint: Option<String>,
     pub connection_status: ConnectionStatus,
     pub client: Option<Arc<LdkServerClient>>,
+    pub connection_health: ConnectionHealth,
+    /// Incremented on every disconnect so results of older tasks can be recognized
+    pub connection_generation: u64,
 
     // Config info (from loaded config file)
     #[allow(dead_code)] // Used only on native
     pub config_file_path: Option<String>,
     pub network: String,
+    /// Network the connected node's chain was verified to be, if it could be determined
+    pub confirmed_network: Option<Network>,
     pub chain_source: ChainSourceConfig,
+    pub lsps2_service: Option<Lsps2ServiceSettings>,
+    /// `[node]` section of the loaded config, the only source of the node's addresses
+    pub node_settings: Option<NodeSettings>,
+
+    // Cached API responses
+    pub node_info: Option<Cached<GetNodeInfoResponse>>,
+    pub balances: Option<Cached<GetBalancesResponse>>,
+    /// Changes since the previous refresh; `None` until balances were fetched twice
+    pub balance_changes: Option<BalanceChanges>,
+    pub balances_requested_at: Option<u64>,
+    pub balance_refresh: Option<BalanceRefresh>,
+    pub channels: Option<Cached<ListChannelsResponse>>,
+    /// Loaded payment pages; `fetched_at` is when the first page was fetched
+    pub payments: Option<Cached<ListPaymentsResponse>>,
+    pub payments_page_token: Option<PageToken>,
+
+    // Async tasks
+    pub tasks: AsyncTasks,
+    /// Latest progress of the multi-step tasks in flight
+    pub task_progress: BTreeMap<TaskKind, Progress>,
+    /// Last background refresh of each resource in `AppState::refresh_schedules`
+    pub refresh: RefreshScheduler<TaskKind>,
+    /// Connection generation for which the Overview tab last synced missing data
+    pub overview_synced_generation: Option<u64>,
+    /// Connection generation for which the Balances tab last fetched missing channels
+    pub balances_channels_generation: Option<u64>,
+    /// Wallets whose last sync was stale when node info was last fetched, with the sync time
+    pub stale_wallet_syncs: Vec<(NodeWallet, u64)>,
+    /// Height of the public chain tip, fetched when the check is enabled under Background Refresh
+    pub public_tip_height: Option<u32>,
+    /// Blocks the node's best block trails the public tip by, when more than the allowed lag
+    pub blocks_behind: Option<u32>,
+    /// Channel liquidity below the alert thresholds when channels were last fetched
+    pub liquidity_alerts: Vec<LiquidityAlert>,
+}
+
+impl Default for NodeSession {
+    fn default() -> Self {
+        Self {
+            server_url: "localhost:3002".into(),
+            api_key: String::new(),
+            tls_cert_path: String::new(),
+            tls_cert_source: TlsCertSource::default(),
+            tls_cert_pem: String::new(),
+            insecure_http: false,
+            proxy: String::new(),
+            tls_cert_fingerprint: None,
+            pinned_cert_fingerprint: None,
+            connection_status: ConnectionStatus::Disconnected,
+            client: None,
+            connection_health: ConnectionHealth::default(),
+            connection_generation: 0,
+
+            config_file_path: None,
+            network: String::new(),
+            confirmed_network: None,
+            chain_source: ChainSourceConfig::default(),
+            lsps2_service: None,
+            node_settings: None,
+
+            node_info: None,
+            balances: None,
+            balance_changes: None,
+            balances_requested_at: None,
+            balance_refresh: None,
+            channels: None,
+            payments: None,
+            payments_page_token: None,
+
+            tasks: AsyncTasks::default(),
+            task_progress: BTreeMap::new(),
+            refresh: RefreshScheduler::default(),
+            overview_synced_generation: None,
+            balances_channels_generation: None,
+            stale_wallet_syncs: Vec::new(),
+            public_tip_height: None,
+            blocks_behind: None,
+            liquidity_alerts: Vec::new(),
+        }
+    }
+}
+
+impl NodeSession {
+    /// Name shown in the node switcher: the server URL, followed by the node id prefix once known.
+    pub fn label(&self) -> String {
+        let url = self.server_url.trim();
+        let url = if url.is_empty() { "New node" } else { url };
+        match &self.node_info {
+            Some(info) if info.data.node_id.len() > 8 => {
+                format!("{} ({}…)", url, &info.data.node_id[..8])
+            }
+            _ => url.to_string(),
+        }
+    }
+
+    /// The configured network, or `None` if none is set or the name is not recognized.
+    pub fn network(&self) -> Option<Network> {
+        Network::from_config_name(&self.network)
+    }
+
+    /// Network that network-dependent features use: the verified one, else the configured one.
+    pub fn chain_network(&self) -> Option<Network> {
+        self.confirmed_network.or_else(|| self.network())
+    }
+}
+
+pub struct AppState {
+    /// The node currently shown; taken out of `sessions` while active
+    pub session: NodeSession,
+    /// All connected (or configured) nodes. The slot at `active_session` holds a placeholder
+    /// while its session is checked out into `session`.
+    pub sessions: Vec<NodeSession>,
+    pub active_session: usize,
+
+    // Connection preferences
+    /// Persist the pasted certificate along with the other connection settings
+    #[allow(dead_code)] // Used only on native
+    pub remember_tls_cert_pem: bool,
+    /// Servers that were connected to successfully, most recent first
+    pub recent_server_urls: Vec<String>,
+    /// API key entered in the re-authentication dialog
+    pub reauth_api_key: String,
+    pub reauth_error: Option<String>,
+    /// Whether the pending connection probe was started from the re-authentication dialog
+    pub reauthenticating: bool,
+    /// Problems found in the connection form on the last submit, in form order
+    pub connection_field_errors: Vec<(ConnectionField, String)>,
+    /// Field to focus on the next frame
+    pub focus_connection_field: Option<ConnectionField>,
+    /// Client calls still pending after this long are abandoned
+    pub request_timeout_secs: u64,
+    /// Requests allowed to run at once, across all nodes
+    pub max_concurrent_requests: usize,
+    /// Durations of the last finished tasks of every node
+    pub task_timings: TaskTimings<TaskKind>,
+    /// Background refresh interval of each resource in [`REFRESHED_RESOURCES`]
+    pub refresh_schedules: BTreeMap<TaskKind, RefreshSchedule>,
+    /// Keep refreshing resources while the window is unfocused or the page hidden
+    pub refresh_in_background: bool,
+    /// When the "updated N ago" captions of cached data turn amber and red
+    pub data_age_thresholds: DataAgeThresholds,
+    /// Wallet syncs older than this many minutes are warned about
+    pub stale_sync_mins: u64,
+    /// A node trailing the public chain tip by more blocks than this is warned about
+    pub max_block_lag: u32,
+    /// Channel liquidity below which the Balances tab shows an alert
+    pub liquidity_thresholds: LiquidityThresholds,
+    /// Persist the API key along with the other connection settings
+    pub remember_api_key: bool,
+    /// Connection fields filled from environment variables and not edited since
+    #[allow(dead_code)] // Used only on native
+    pub env_overrides: Vec<ConnectionField>,
+    #[allow(dead_code)] // Used only on native
+    pub connection_profiles: Vec<ConnectionProfile>,
+    /// Keep profile API keys in the OS keyring instead of the profile file
+    #[allow(dead_code)] // Used only on native
+    pub use_keyring: bool,
+    /// Fingerprint of a certificate that did not match the pin, awaiting confirmation
+    #[allow(dead_code)] // Used only on native
+    pub cert_pin_mismatch: Option<String>,
+    /// Name of the profile the connection fields were last filled from
+    #[allow(dead_code)] // Used only on native
+    pub selected_profile: Option<String>,
+    #[allow(dead_code)] // Used only on native
+    pub profile_name: String,
 
     // Navigation
     pub active_tab: ActiveTab,
+    /// Channel id highlighted in the Channels tab after following a link to it
+    pub highlighted_channel: Option<String>,
+    /// Whether the Channels tab still has to scroll to the highlighted channel
+    pub scroll_to_highlighted_channel: bool,
+    /// Least severe level shown in the Logs tab
+    pub log_level: tracing::Level,
 
-    // Cached API responses
-    pub node_info: Option<GetNodeInfoResponse>,
-    pub balances: Option<GetBalancesResponse>,
-    pub channels: Option<ListChannelsResponse>,
-    pub payments: Option<ListPaymentsResponse>,
-    pub payments_page_token: Option<PageToken>,
+    // Settings
+    /// Unit used for amount display and entry in the on-chain forms
+    pub onchain_unit: AmountUnit,
+    /// Unit amounts are displayed in everywhere else, switched from the top panel
+    pub display_unit: AmountUnit,
+    /// Whether node ids are shortened in responses copied as JSON
+    pub redact_json_node_ids: bool,
 
     // Operation results
     pub onchain_address: Option<String>,
+    /// Whether the displayed address had already been shown or copied before
+    pub onchain_address_reused: bool,
+    pub address_history: Vec<GeneratedAddress>,
+    pub address_book: Vec<AddressBookEntry>,
+    /// Destination of the last successful on-chain send
+    pub last_sent_address: Option<String>,
     pub generated_invoice: Option<String>,
+    /// Form values the displayed invoice was generated from, used to detect stale invoices
+    pub generated_invoice_form: Option<Bolt11ReceiveForm>,
     pub generated_offer: Option<String>,
+    /// Form values the displayed offer was generated from, used to detect stale offers
+    pub generated_offer_form: Option<Bolt12ReceiveForm>,
     pub last_payment_id: Option<String>,
+    /// Invoice fetched from a lightning address / LNURL, awaiting confirmation
+    pub pending_lnurl_invoice: Option<LnurlInvoice>,
     pub last_txid: Option<String>,
+    /// Confirmation watcher for `last_txid`
+    pub tx_watch: Option<TxWatch>,
     pub last_channel_id: Option<String>,
 
-    // Async tasks
-    pub tasks: AsyncTasks,
+    // Fee rate estimates (from mempool.space)
+    /// Custom API base URL; empty means the public instance for the configured network
+    pub mempool_api_url: String,
+    pub fee_estimates: Option<FeeEstimates>,
+    pub fee_estimates_error: Option<String>,
+    pub fee_estimates_requested_at: Option<u64>,
+
+    // Fiat values (from CoinGecko)
+    /// Lowercase currency code; `None` when fiat values are off
+    pub fiat_currency: Option<String>,
+    pub fiat_rate: Option<FiatRate>,
+    pub fiat_rate_requested_at: Option<u64>,
 
     // Form state
     pub forms: Forms,
 
     // UI state
     pub status_message: Option<StatusMessage>,
+    pub qr_textures: QrTextures,
     pub show_open_channel_dialog: bool,
     pub show_close_channel_dialog: bool,
     pub show_splice_in_dialog: bool,
     pub show_splice_out_dialog: bool,
     pub show_update_config_dialog: bool,
     pub show_connect_peer_dialog: bool,
+    pub show_onchain_send_confirm_dialog: bool,
+    /// The user confirmed sending an amount below the dust threshold
+    pub dust_acknowledged: bool,
+    pub show_disconnect_dialog: bool,
+    pub show_drain_wallet_dialog: bool,
+    pub show_address_book_dialog: bool,
+    /// Drain wallet choice: keep the anchor reserve instead of sweeping everything
+    pub drain_keep_reserve: bool,
+    #[allow(dead_code)] // Used only on wasm
     pub show_load_config_dialog: bool,
+    /// The last failed request was blocked by the browser, most likely for lack of CORS headers
+    #[allow(dead_code)] // Used only on wasm
+    pub cors_blocked: bool,
+    #[allow(dead_code)] // Used only on wasm
+    pub show_cors_help: bool,
+    /// Config that failed to load and why, one problem per line
+    pub config_load_error: Option<(String, String)>,
+    #[allow(dead_code)] // Used only on native
+    pub pending_config_save: Option<PendingConfigSave>,
+    #[allow(dead_code)] // Used only on native
+    pub new_config_wizard: Option<NewConfigWizard>,
+    #[allow(dead_code)] // Used only on native
+    pub shutdown: ShutdownState,
+    /// Outcome of the last chain source connectivity check
+    #[allow(dead_code)] // Used only on native
+    pub chain_source_check_result: Option<Result<String, String>>,
     pub config_paste_text: String,
+    /// URL the wasm Load Config dialog fetches the config from
+    pub config_url: String,
     pub lightning_tab: LightningTab,
     pub onchain_tab: OnchainTab,
 }
@@ -362,46 +1241,255 @@ pub enum OnchainTab {
 impl Default for AppState {
     fn default() -> Self {
         Self {
-            server_url: "localhost:3002".into(),
-            api_key: String::new(),
-            tls_cert_path: String::new(),
-            connection_status: ConnectionStatus::Disconnected,
-            client: None,
+            session: NodeSession::default(),
+            sessions: vec![NodeSession::default()],
+            active_session: 0,
+            remember_tls_cert_pem: false,
+            recent_server_urls: Vec::new(),
+            reauth_api_key: String::new(),
+            reauth_error: None,
+            reauthenticating: false,
+            connection_field_errors: Vec::new(),
+            env_overrides: Vec::new(),
+            focus_connection_field: None,
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            task_timings: TaskTimings::new(TASK_TIMINGS_KEPT),
+            refresh_schedules: default_refresh_schedules(),
+            refresh_in_background: false,
+            data_age_thresholds: DataAgeThresholds::default(),
+            stale_sync_mins: DEFAULT_STALE_SYNC_MINS,
+            max_block_lag: DEFAULT_MAX_BLOCK_LAG,
+            liquidity_thresholds: LiquidityThresholds::default(),
+            remember_api_key: false,
+            connection_profiles: Vec::new(),
+            use_keyring: false,
+            cert_pin_mismatch: None,
+            selected_profile: None,
+            profile_name: String::new(),
 
-            config_file_path: None,
-            network: String::new(),
-            chain_source: ChainSourceConfig::default(),
+            active_tab: ActiveTab::Overview,
+            highlighted_channel: None,
+            scroll_to_highlighted_channel: false,
+            log_level: tracing::Level::INFO,
 
-            active_tab: ActiveTab::NodeInfo,
-
-            node_info: None,
-            balances: None,
-            channels: None,
-            payments: None,
-            payments_page_token: None,
+            onchain_unit: AmountUnit::default(),
+            display_unit: AmountUnit::default(),
+            redact_json_node_ids: true,
 
             onchain_address: None,
+            onchain_address_reused: false,
+            address_history: Vec::new(),
+            address_book: Vec::new(),
+            last_sent_address: None,
             generated_invoice: None,
+            generated_invoice_form: None,
             generated_offer: None,
+            generated_offer_form: None,
             last_payment_id: None,
+            pending_lnurl_invoice: None,
             last_txid: None,
+            tx_watch: None,
             last_channel_id: None,
 
-            tasks: AsyncTasks::default(),
+            mempool_api_url: String::new(),
+            fee_estimates: None,
+            fee_estimates_error: None,
+            fee_estimates_requested_at: None,
+            fiat_currency: None,
+            fiat_rate: None,
+            fiat_rate_requested_at: None,
 
             forms: Forms::default(),
 
             status_message: None,
+            config_load_error: None,
+            qr_textures: QrTextures::default(),
             show_open_channel_dialog: false,
             show_close_channel_dialog: false,
             show_splice_in_dialog: false,
             show_splice_out_dialog: false,
             show_update_config_dialog: false,
             show_connect_peer_dialog: false,
+            show_onchain_send_confirm_dialog: false,
+            dust_acknowledged: false,
+            show_drain_wallet_dialog: false,
+            show_disconnect_dialog: false,
+            show_address_book_dialog: false,
+            drain_keep_reserve: true,
             show_load_config_dialog: false,
+            cors_blocked: false,
+            show_cors_help: false,
+            pending_config_save: None,
+            new_config_wizard: None,
+            shutdown: ShutdownState::default(),
+            chain_source_check_result: None,
             config_paste_text: String::new(),
+            config_url: DEFAULT_CONFIG_URL.to_string(),
             lightning_tab: LightningTab::default(),
             onchain_tab: OnchainTab::default(),
         }
     }
 }
+
+impl AppState {
+    /// Report a config from `source` that failed to load, listing every problem in a dialog.
+    pub fn set_config_load_error(&mut self, source: String, error: String) {
+        self.status_message = Some(StatusMessage::error(format!(
+            "Failed to load config from {}",
+            source
+        )));
+        self.config_load_error = Some((source, error));
+    }
+
+    /// Report a config loaded from `source`, warning about server files it points at that are
+    /// missing, since the connection would then fail with an empty API key.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_config_loaded(&mut self, source: &str, warnings: &[String]) {
+        self.status_message = Some(if warnings.is_empty() {
+            StatusMessage::success(format!("Config loaded from {}", source))
+        } else {
+            StatusMessage::error(format!(
+                "Config loaded from {}, but found {}",
+                source,
+                warnings.join(" and ")
+            ))
+        });
+    }
+
+    /// Every node session, the active one first.
+    #[allow(dead_code)] // Used only on native
+    pub fn all_sessions(&self) -> impl Iterator<Item = &NodeSession> {
+        let active = self.active_session;
+        let background = self.sessions.iter().enumerate().filter(move |(i, _)| *i != active);
+        std::iter::once(&self.session).chain(background.map(|(_, session)| session))
+    }
+
+    /// Every node session, the active one first.
+    #[allow(dead_code)] // Used only on native
+    pub fn all_sessions_mut(&mut self) -> impl Iterator<Item = &mut NodeSession> {
+        let active = self.active_session;
+        let background = self.sessions.iter_mut().enumerate().filter(move |(i, _)| *i != active);
+        std::iter::once(&mut self.session).chain(background.map(|(_, session)| session))
+    }
+
+    /// Forget results of operations performed on the previously active node.
+    pub fn clear_node_results(&mut self) {
+        self.onchain_address = None;
+        self.onchain_address_reused = false;
+        self.last_sent_address = None;
+        self.generated_invoice = None;
+        self.generated_invoice_form = None;
+        self.generated_offer = None;
+        self.generated_offer_form = None;
+        self.last_payment_id = None;
+        self.pending_lnurl_invoice = None;
+        self.last_txid = None;
+        self.tx_watch = None;
+        self.last_channel_id = None;
+        self.fee_estimates = None;
+        self.fee_estimates_error = None;
+        self.fee_estimates_requested_at = None;
+    }
+
+    pub fn persisted_settings(&self) -> PersistedSettings {
+        let default_schedules = default_refresh_schedules();
+        PersistedSettings {
+            server_url: self.session.server_url.trim().to_string(),
+            tls_cert_path: self.session.tls_cert_path.trim().to_string(),
+            network: self.session.network.clone(),
+            mempool_api_url: self.mempool_api_url.trim().to_string(),
+            remember_api_key: self.remember_api_key,
+            api_key: self.remember_api_key.then(|| self.session.api_key.clone()),
+            use_keyring: self.use_keyring,
+            tls_cert_source: self.session.tls_cert_source,
+            insecure_http: self.session.insecure_http,
+            proxy: self.session.proxy.trim().to_string(),
+            remember_tls_cert_pem: self.remember_tls_cert_pem,
+            tls_cert_pem: self.remember_tls_cert_pem.then(|| self.session.tls_cert_pem.clone()),
+            request_timeout_secs: self.request_timeout_secs,
+            max_concurrent_requests: self.max_concurrent_requests,
+            recent_server_urls: self.recent_server_urls.clone(),
+            refresh_schedules: self
+                .refresh_schedules
+                .iter()
+                .filter(|(kind, schedule)| default_schedules.get(kind) != Some(schedule))
+                .map(|(kind, schedule)| (*kind, *schedule))
+                .collect(),
+            refresh_in_background: self.refresh_in_background,
+            data_age_thresholds: self.data_age_thresholds,
+            stale_sync_mins: self.stale_sync_mins,
+            max_block_lag: self.max_block_lag,
+            liquidity_thresholds: self.liquidity_thresholds,
+            fiat_currency: self.fiat_currency.clone(),
+            display_unit: self.display_unit,
+        }
+    }
+
+    pub fn apply_persisted_settings(&mut self, settings: PersistedSettings) {
+        if !settings.server_url.is_empty() {
+            self.session.server_url = settings.server_url;
+        }
+        self.session.tls_cert_path = settings.tls_cert_path;
+        self.session.network = settings.network;
+        self.mempool_api_url = settings.mempool_api_url;
+        self.remember_api_key = settings.remember_api_key;
+        if let Some(api_key) = settings.api_key.filter(|_| settings.remember_api_key) {
+            self.session.api_key = api_key;
+        }
+        self.use_keyring = settings.use_keyring;
+        self.session.tls_cert_source = settings.tls_cert_source;
+        self.session.insecure_http = settings.insecure_http;
+        self.session.proxy = settings.proxy;
+        self.remember_tls_cert_pem = settings.remember_tls_cert_pem;
+        if let Some(pem) = settings.tls_cert_pem.filter(|_| settings.remember_tls_cert_pem) {
+            self.session.tls_cert_pem = pem;
+        }
+        if settings.request_timeout_secs > 0 {
+            self.request_timeout_secs = settings.request_timeout_secs;
+        }
+        if settings.max_concurrent_requests > 0 {
+            self.max_concurrent_requests = settings.max_concurrent_requests;
+        }
+        self.recent_server_urls = settings.recent_server_urls;
+        for (kind, schedule) in settings.refresh_schedules {
+            if let Some(current) = self.refresh_schedules.get_mut(&kind) {
+                let interval_secs = schedule.interval_secs.max(1);
+                *current = RefreshSchedule { interval_secs, ..schedule };
+            }
+        }
+        self.refresh_in_background = settings.refresh_in_background;
+        let DataAgeThresholds { aging_after_secs, stale_after_secs } = settings.data_age_thresholds;
+        let aging_after_secs = aging_after_secs.max(1);
+        let stale_after_secs = stale_after_secs.max(aging_after_secs);
+        self.data_age_thresholds = DataAgeThresholds { aging_after_secs, stale_after_secs };
+        if settings.stale_sync_mins > 0 {
+            self.stale_sync_mins = settings.stale_sync_mins;
+        }
+        if settings.max_block_lag > 0 {
+            self.max_block_lag = settings.max_block_lag;
+        }
+        self.liquidity_thresholds = settings.liquidity_thresholds;
+        self.fiat_currency =
+            settings.fiat_currency.filter(|currency| FIAT_CURRENCIES.contains(&currency.as_str()));
+        self.display_unit = settings.display_unit;
+    }
+
+    /// The fetched BTC price if fiat values are on and it is recent enough to show.
+    pub fn usable_fiat_rate(&self) -> Option<&FiatRate> {
+        let currency = self.fiat_currency.as_deref()?;
+        let now = crate::ui::unix_timestamp_now();
+        self.fiat_rate.as_ref().filter(|rate| rate.is_usable(currency, now))
+    }
+
+    /// Move `url` to the front of the recently used server URLs.
+    pub fn remember_server_url(&mut self, url: &str) {
+        let url = url.trim();
+        if url.is_empty() {
+            return;
+        }
+        self.recent_server_urls.retain(|u| u != url);
+        self.recent_server_urls.insert(0, url.to_string());
+        self.recent_server_urls.truncate(MAX_RECENT_SERVER_URLS);
+    }
+}
@@ -0,0 +1,79 @@
+//! Persistence of small pieces of GUI state across restarts.
+//!
+//! Native builds use eframe's storage, which is written periodically and on exit via
+//! `App::save`. The wasm build has no eframe persistence, so values are written to the
+//! browser's localStorage as soon as they change, and all of them periodically for changes made
+//! elsewhere. Either way they are written through a [`Store`].
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub const ADDRESS_HISTORY_KEY: &str = "onchain_address_history";
+pub const ADDRESS_BOOK_KEY: &str = "onchain_address_book";
+#[cfg(not(target_arch = "wasm32"))]
+pub const CONNECTION_PROFILES_KEY: &str = "connection_profiles";
+pub const SETTINGS_KEY: &str = "settings";
+pub const AMOUNT_UNIT_KEY: &str = "amount_unit";
+
+/// Every key stored in localStorage, removed by [`clear`].
+#[cfg(target_arch = "wasm32")]
+const KEYS: [&str; 4] = [ADDRESS_HISTORY_KEY, ADDRESS_BOOK_KEY, SETTINGS_KEY, AMOUNT_UNIT_KEY];
+
+/// Somewhere values are saved to.
+pub trait Store {
+    fn set<T: Serialize>(&mut self, key: &str, value: &T);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Store for dyn eframe::Storage + '_ {
+    fn set<T: Serialize>(&mut self, key: &str, value: &T) {
+        eframe::set_value(self, key, value);
+    }
+}
+
+/// The browser's localStorage.
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage;
+
+#[cfg(target_arch = "wasm32")]
+impl Store for LocalStorage {
+    fn set<T: Serialize>(&mut self, key: &str, value: &T) {
+        store(key, value);
+    }
+}
+
+/// Load a previously stored value.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load<T: DeserializeOwned>(cc: &eframe::CreationContext<'_>, key: &str) -> Option<T> {
+    cc.storage.and_then(|storage| eframe::get_value(storage, key))
+}
+
+/// Load a previously stored value.
+#[cfg(target_arch = "wasm32")]
+pub fn load<T: DeserializeOwned>(_cc: &eframe::CreationContext<'_>, key: &str) -> Option<T> {
+    let value = local_storage()?.get_item(key).ok()??;
+    serde_json::from_str(&value).ok()
+}
+
+/// Store a value immediately (wasm only; native values are saved from `App::save`).
+#[cfg(target_arch = "wasm32")]
+pub fn store<T: Serialize>(key: &str, value: &T) {
+    if let (Some(storage), Ok(json)) = (local_storage(), serde_json::to_string(value)) {
+        let _ = storage.set_item(key, &json);
+    }
+}
+
+/// Remove every stored value (wasm only).
+#[cfg(target_arch = "wasm32")]
+pub fn clear() {
+    if let Some(storage) = local_storage() {
+        for key in KEYS {
+            let _ = storage.remove_item(key);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
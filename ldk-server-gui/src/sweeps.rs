@@ -0,0 +1,136 @@
+//! Progress of pending sweeps of closed channel outputs, relative to the node's chain tip.
+
+use ldk_server_client::ldk_server_protos::types::pending_sweep_balance::BalanceType;
+
+/// Confirmations after which LDK considers a sweep irrevocably confirmed (`ANTI_REORG_DELAY`).
+pub const SWEEP_REQUIRED_CONFIRMATIONS: u32 = 6;
+
+/// Average time between blocks, used for time estimates.
+pub const MINUTES_PER_BLOCK: u32 = 10;
+
+/// Where a pending sweep stands at a given tip height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepProgress {
+    /// No spending transaction has been broadcast yet.
+    PendingBroadcast,
+    /// The spending transaction was last broadcast `blocks_ago` blocks before the tip and has not
+    /// confirmed yet.
+    Broadcast { blocks_ago: u32 },
+    /// The spending transaction has `confirmations` of [`SWEEP_REQUIRED_CONFIRMATIONS`].
+    Confirming { confirmations: u32 },
+}
+
+impl SweepProgress {
+    /// Share of the required confirmations reached, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        match self {
+            SweepProgress::PendingBroadcast | SweepProgress::Broadcast { .. } => 0.0,
+            SweepProgress::Confirming { confirmations } => {
+                *confirmations as f32 / SWEEP_REQUIRED_CONFIRMATIONS as f32
+            }
+        }
+    }
+
+    pub fn text(&self) -> String {
+        match *self {
+            SweepProgress::PendingBroadcast => "Waiting to be broadcast".to_string(),
+            SweepProgress::Broadcast { blocks_ago: 0 } => {
+                "Broadcast at the current tip, unconfirmed".to_string()
+            }
+            SweepProgress::Broadcast { blocks_ago } => format!(
+                "Broadcast {} block{} ago (~{} minutes), unconfirmed",
+                blocks_ago,
+                if blocks_ago == 1 { "" } else { "s" },
+                blocks_ago * MINUTES_PER_BLOCK
+            ),
+            SweepProgress::Confirming { confirmations } => {
+                let remaining = SWEEP_REQUIRED_CONFIRMATIONS - confirmations;
+                if remaining == 0 {
+                    format!("{0} of {0} confirmations", SWEEP_REQUIRED_CONFIRMATIONS)
+                } else {
+                    format!(
+                        "{} of {} confirmations (~{} minutes remaining at {} min/block)",
+                        confirmations,
+                        SWEEP_REQUIRED_CONFIRMATIONS,
+                        remaining * MINUTES_PER_BLOCK,
+                        MINUTES_PER_BLOCK
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// Progress of `sweep` with the node's best block at `tip_height`. A tip behind the sweep's
+/// heights, as with node info older than the balances, counts as the sweep's own height.
+pub fn sweep_progress(sweep: &BalanceType, tip_height: u32) -> SweepProgress {
+    match sweep {
+        BalanceType::PendingBroadcast(_) => SweepProgress::PendingBroadcast,
+        BalanceType::BroadcastAwaitingConfirmation(b) => {
+            let blocks_ago = tip_height.saturating_sub(b.latest_broadcast_height);
+            SweepProgress::Broadcast { blocks_ago }
+        }
+        BalanceType::AwaitingThresholdConfirmations(b) => {
+            let confirmations = tip_height.saturating_sub(b.confirmation_height) + 1;
+            SweepProgress::Confirming {
+                confirmations: confirmations.min(SWEEP_REQUIRED_CONFIRMATIONS),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ldk_server_client::ldk_server_protos::types::{
+        AwaitingThresholdConfirmations, BroadcastAwaitingConfirmation, PendingBroadcast,
+    };
+
+    fn confirming(confirmation_height: u32) -> BalanceType {
+        BalanceType::AwaitingThresholdConfirmations(AwaitingThresholdConfirmations {
+            confirmation_height,
+            amount_satoshis: 10_000,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_sweep_progress_confirming() {
+        let progress = sweep_progress(&confirming(100), 102);
+        assert_eq!(progress, SweepProgress::Confirming { confirmations: 3 });
+        let text = "3 of 6 confirmations (~30 minutes remaining at 10 min/block)";
+        assert_eq!(progress.text(), text);
+        assert_eq!(progress.fraction(), 0.5);
+
+        // Confirmed in the tip block itself
+        assert_eq!(
+            sweep_progress(&confirming(100), 100),
+            SweepProgress::Confirming { confirmations: 1 }
+        );
+        // Node info fetched before the confirmation
+        assert_eq!(
+            sweep_progress(&confirming(100), 98),
+            SweepProgress::Confirming { confirmations: 1 }
+        );
+        // Past the threshold but not yet dropped from the balances
+        let progress = sweep_progress(&confirming(100), 110);
+        assert_eq!(progress, SweepProgress::Confirming { confirmations: 6 });
+        assert_eq!(progress.text(), "6 of 6 confirmations");
+        assert_eq!(progress.fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_sweep_progress_broadcast() {
+        let broadcast = BalanceType::BroadcastAwaitingConfirmation(BroadcastAwaitingConfirmation {
+            latest_broadcast_height: 200,
+            ..Default::default()
+        });
+        let progress = sweep_progress(&broadcast, 203);
+        assert_eq!(progress, SweepProgress::Broadcast { blocks_ago: 3 });
+        assert_eq!(progress.text(), "Broadcast 3 blocks ago (~30 minutes), unconfirmed");
+        assert_eq!(sweep_progress(&broadcast, 199), SweepProgress::Broadcast { blocks_ago: 0 });
+
+        let pending = BalanceType::PendingBroadcast(PendingBroadcast::default());
+        assert_eq!(sweep_progress(&pending, 203), SweepProgress::PendingBroadcast);
+    }
+}
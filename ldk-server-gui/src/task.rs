@@ -1,72 +1,1592 @@
 //! Cross-platform async task handling for native and WASM targets.
 
-#[cfg(target_arch = "wasm32")]
-use std::cell::RefCell;
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Debug;
 use std::future::Future;
-#[cfg(target_arch = "wasm32")]
-use std::rc::Rc;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use futures_util::future::{abortable, AbortHandle};
+use serde::{Deserialize, Serialize};
+use tracing::Span;
+
+use crate::errors::TaskError;
+use crate::logs;
+use crate::ui::unix_timestamp_millis;
 
-/// A task handle that can be polled for completion (native implementation using channels)
+/// Default time a request may take before it is abandoned.
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Default number of requests allowed to run at once.
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// Resolve to the future's result, or to [`TaskError::Timeout`] if it takes longer than
+/// `timeout`.
 #[cfg(not(target_arch = "wasm32"))]
-pub struct ChannelTaskHandle<T> {
-    rx: std::sync::mpsc::Receiver<Result<T, String>>,
+pub async fn with_timeout<T, E, F>(future: F, timeout: Duration) -> Result<T, TaskError>
+where
+    E: Into<TaskError>,
+    F: Future<Output = Result<T, E>>,
+{
+    match tokio::time::timeout(timeout, future).await {
+        Ok(res) => res.map_err(Into::into),
+        Err(_) => Err(TaskError::Timeout { after: timeout }),
+    }
+}
+
+/// Resolve to the future's result, or to [`TaskError::Timeout`] if it takes longer than
+/// `timeout`.
+#[cfg(target_arch = "wasm32")]
+pub async fn with_timeout<T, E, F>(future: F, timeout: Duration) -> Result<T, TaskError>
+where
+    E: Into<TaskError>,
+    F: Future<Output = Result<T, E>>,
+{
+    use futures_util::future::{select, Either};
+
+    let future = std::pin::pin!(future);
+    let timer = std::pin::pin!(sleep(timeout));
+    match select(future, timer).await {
+        Either::Left((res, _)) => res.map_err(Into::into),
+        Either::Right(_) => Err(TaskError::Timeout { after: timeout }),
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-impl<T> ChannelTaskHandle<T> {
-    /// Check if the task has completed and return the result if so.
-    pub fn try_take(&mut self) -> Option<Result<T, String>> {
-        match self.rx.try_recv() {
-            Ok(result) => Some(result),
-            Err(_) => None,
+async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+/// Wait on a `setTimeout` timer.
+#[cfg(target_arch = "wasm32")]
+async fn sleep(duration: Duration) {
+    use wasm_bindgen_futures::js_sys::Promise;
+
+    let millis = duration.as_millis().min(i32::MAX as u128) as i32;
+    let promise = Promise::new(&mut |resolve, _| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, millis);
         }
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+/// How a read-only request is retried after a transient failure.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled before each further one
+    pub initial_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Policy of the read-only fetches of node data. Requests that change anything on the node
+    /// are never retried, since a failed attempt may still have gone through.
+    pub const READ_ONLY: RetryPolicy =
+        RetryPolicy { max_attempts: 3, initial_backoff: Duration::from_millis(500) };
+
+    /// Delay before retry number `retry`, counting from zero.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff.saturating_mul(2u32.saturating_pow(retry))
     }
 }
 
-/// Spawn a task on the provided tokio runtime and return a channel-based handle
-#[cfg(not(target_arch = "wasm32"))]
-pub fn spawn_with_runtime<T, F>(rt: &tokio::runtime::Runtime, future: F) -> ChannelTaskHandle<T>
+/// Run the future made by `attempt`, each run bounded by `timeout`, and run it again after a
+/// backoff while it fails with a [transient](TaskError::is_transient) error and attempts remain.
+/// Resolves to the first success or permanent error, or to the last transient one.
+pub async fn with_retry<T, E, F, Fut>(
+    mut attempt: F, timeout: Duration, policy: RetryPolicy,
+) -> Result<T, TaskError>
 where
-    T: Send + 'static,
-    F: Future<Output = Result<T, String>> + Send + 'static,
+    E: Into<TaskError>,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
 {
-    let (tx, rx) = std::sync::mpsc::channel();
+    let mut retry = 0;
+    loop {
+        match with_timeout(attempt(), timeout).await {
+            Err(e) if e.is_transient() && retry + 1 < policy.max_attempts => {
+                sleep(policy.backoff(retry)).await;
+                retry += 1;
+            }
+            result => return result,
+        }
+    }
+}
 
-    rt.spawn(async move {
-        let res = future.await;
-        let _ = tx.send(res);
-    });
+/// Limits how many spawned tasks run at once. Tasks over the limit wait in the order they were
+/// spawned. Clones share the same limit.
+#[derive(Clone)]
+pub struct RequestLimiter {
+    state: Arc<Mutex<LimiterState>>,
+}
 
-    ChannelTaskHandle { rx }
+struct LimiterState {
+    limit: usize,
+    in_flight: usize,
+    /// Waiting tasks in arrival order, with the waker of those polled already
+    queue: VecDeque<(u64, Option<Waker>)>,
+    next_id: u64,
 }
 
-/// WASM implementation using shared state
+impl LimiterState {
+    /// Wake the task at the front of the queue if there is room for it.
+    fn wake_front(&mut self) {
+        if self.in_flight < self.limit {
+            if let Some(waker) = self.queue.front_mut().and_then(|(_, waker)| waker.take()) {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl Default for RequestLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CONCURRENT_REQUESTS)
+    }
+}
+
+impl RequestLimiter {
+    pub fn new(limit: usize) -> Self {
+        let state =
+            LimiterState { limit: limit.max(1), in_flight: 0, queue: VecDeque::new(), next_id: 0 };
+        Self { state: Arc::new(Mutex::new(state)) }
+    }
+
+    /// Change the limit. Tasks already running are not affected; raising it starts waiting ones.
+    pub fn set_limit(&self, limit: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.limit = limit.max(1);
+        state.wake_front();
+    }
+
+    /// Number of tasks running and waiting to run.
+    pub fn counts(&self) -> (usize, usize) {
+        let state = self.state.lock().unwrap();
+        (state.in_flight, state.queue.len())
+    }
+
+    /// Run `future` once fewer than the limit of tasks are running. Its place in the queue is
+    /// taken when this is called, not when the returned future is first polled.
+    pub fn throttle<F: Future>(&self, future: F) -> impl Future<Output = F::Output> {
+        let acquire = self.acquire();
+        async move {
+            let _permit = acquire.await;
+            future.await
+        }
+    }
+
+    fn acquire(&self) -> Acquire {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.queue.push_back((id, None));
+        Acquire { state: self.state.clone(), id: Some(id) }
+    }
+}
+
+/// Waits for a task's turn under a [`RequestLimiter`]. Dropping it gives up its place.
+struct Acquire {
+    state: Arc<Mutex<LimiterState>>,
+    /// Place in the queue, `None` once admitted
+    id: Option<u64>,
+}
+
+impl Future for Acquire {
+    type Output = Permit;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Permit> {
+        let this = self.get_mut();
+        let Some(id) = this.id else {
+            panic!("Acquire polled after completion");
+        };
+        let mut state = this.state.lock().unwrap();
+        let is_front = state.queue.front().map(|(front, _)| *front) == Some(id);
+        if is_front && state.in_flight < state.limit {
+            state.queue.pop_front();
+            state.in_flight += 1;
+            // There may be room for the next one as well
+            state.wake_front();
+            this.id = None;
+            return Poll::Ready(Permit { state: this.state.clone() });
+        }
+        if let Some((_, waker)) = state.queue.iter_mut().find(|(queued, _)| *queued == id) {
+            *waker = Some(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Acquire {
+    fn drop(&mut self) {
+        let Some(id) = self.id else {
+            return;
+        };
+        let mut state = self.state.lock().unwrap();
+        state.queue.retain(|(queued, _)| *queued != id);
+        state.wake_front();
+    }
+}
+
+/// Held by a running task, freeing its slot when dropped.
+struct Permit {
+    state: Arc<Mutex<LimiterState>>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.in_flight -= 1;
+        state.wake_front();
+    }
+}
+
+/// `Send` on native, where tasks run on the tokio runtime's worker threads. Nothing on WASM,
+/// where they run on the browser's only thread and may hold values that cannot leave it.
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSend: Send {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send> MaybeSend for T {}
+
 #[cfg(target_arch = "wasm32")]
-pub struct ChannelTaskHandle<T> {
-    result: Rc<RefCell<Option<Result<T, String>>>>,
+pub trait MaybeSend {}
+#[cfg(target_arch = "wasm32")]
+impl<T> MaybeSend for T {}
+
+/// A spawned task, which delivers its result to its handle itself.
+#[cfg(not(target_arch = "wasm32"))]
+pub type BoxTask = Pin<Box<dyn Future<Output = ()> + Send>>;
+#[cfg(target_arch = "wasm32")]
+pub type BoxTask = Pin<Box<dyn Future<Output = ()>>>;
+
+/// Runs spawned tasks on the platform's executor, so that the app spawns every task the same
+/// way on both targets.
+pub trait TaskSpawner {
+    fn spawn(&self, task: BoxTask);
 }
 
+/// Native tasks run on the app's tokio runtime.
+#[cfg(not(target_arch = "wasm32"))]
+impl TaskSpawner for tokio::runtime::Runtime {
+    fn spawn(&self, task: BoxTask) {
+        tokio::runtime::Runtime::spawn(self, task);
+    }
+}
+
+/// Runs tasks on the browser's event loop.
+#[cfg(target_arch = "wasm32")]
+pub struct LocalSpawner;
+
 #[cfg(target_arch = "wasm32")]
+impl TaskSpawner for LocalSpawner {
+    fn spawn(&self, task: BoxTask) {
+        wasm_bindgen_futures::spawn_local(task);
+    }
+}
+
+/// Moved into a spawned task so that its end, whether it finished, was aborted or panicked,
+/// requests a repaint and the UI handles the task right away.
+struct TaskEndGuard(egui::Context);
+
+impl Drop for TaskEndGuard {
+    fn drop(&mut self) {
+        self.0.request_repaint();
+    }
+}
+
+/// A task handle that can be polled for completion.
+pub struct ChannelTaskHandle<T> {
+    rx: std::sync::mpsc::Receiver<Result<T, TaskError>>,
+    /// Whether the result was taken, after which the channel is expected to be disconnected
+    taken: bool,
+    abort: AbortHandle,
+    generation: u64,
+}
+
 impl<T> ChannelTaskHandle<T> {
-    pub fn try_take(&mut self) -> Option<Result<T, String>> {
-        self.result.borrow_mut().take()
+    /// Check if the task has completed and return the result if so. An aborted task yields
+    /// [`TaskError::Cancelled`], and one that ended without a result, e.g. by panicking,
+    /// [`TaskError::Lost`].
+    pub fn try_take(&mut self) -> Option<Result<T, TaskError>> {
+        if self.abort.is_aborted() {
+            return Some(Err(TaskError::Cancelled));
+        }
+        if self.taken {
+            return None;
+        }
+        let result = match self.rx.try_recv() {
+            Ok(result) => Some(result),
+            Err(std::sync::mpsc::TryRecvError::Empty) => None,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => Some(Err(TaskError::Lost)),
+        };
+        self.taken = result.is_some();
+        result
+    }
+
+    /// Tag the task with the connection generation it was spawned for.
+    pub fn with_generation(mut self, generation: u64) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Cancel the task, which then yields [`TaskError::Cancelled`].
+    pub fn abort(&self) {
+        self.abort.abort();
     }
 }
 
-/// Spawn a task for WASM targets
-#[cfg(target_arch = "wasm32")]
-pub fn spawn_local<T, F>(future: F) -> ChannelTaskHandle<T>
+/// Dropping a handle cancels the task, so clearing a task slot never leaves work running.
+impl<T> Drop for ChannelTaskHandle<T> {
+    fn drop(&mut self) {
+        self.abort.abort();
+    }
+}
+
+/// Spawn a task with `spawner` and return a channel-based handle. `ctx` is asked to repaint
+/// once the task completes or is aborted, so the UI handles it right away.
+pub fn spawn<T, F>(
+    spawner: &dyn TaskSpawner, ctx: &egui::Context, future: F,
+) -> ChannelTaskHandle<T>
 where
-    T: 'static,
-    F: Future<Output = Result<T, String>> + 'static,
+    T: MaybeSend + 'static,
+    F: Future<Output = Result<T, TaskError>> + MaybeSend + 'static,
 {
-    let result: Rc<RefCell<Option<Result<T, String>>>> = Rc::new(RefCell::new(None));
-    let result_clone = result.clone();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let (future, abort) = abortable(future);
+    let end = TaskEndGuard(ctx.clone());
 
-    wasm_bindgen_futures::spawn_local(async move {
-        let res = future.await;
-        *result_clone.borrow_mut() = Some(res);
-    });
+    spawner.spawn(Box::pin(async move {
+        // Bound before the sender so it is dropped after it, even when the task panics
+        let _end = end;
+        let tx = tx;
+        if let Ok(res) = future.await {
+            let _ = tx.send(res);
+        }
+    }));
+
+    ChannelTaskHandle { rx, taken: false, abort, generation: 0 }
+}
+
+/// Spawn a task that fails with a timeout error if it takes longer than `timeout`, so a hung
+/// request never holds its task slot. It waits for its turn under `limiter` before the timeout
+/// starts.
+pub fn spawn_with_timeout<T, E, F>(
+    spawner: &dyn TaskSpawner, ctx: &egui::Context, limiter: &RequestLimiter, future: F,
+    timeout: Duration,
+) -> ChannelTaskHandle<T>
+where
+    T: MaybeSend + 'static,
+    E: Into<TaskError>,
+    F: Future<Output = Result<T, E>> + MaybeSend + 'static,
+{
+    spawn(spawner, ctx, limiter.throttle(with_timeout(future, timeout)))
+}
+
+/// Spawn a task that runs the future made by `attempt` under [`with_retry`], once its turn
+/// under `limiter` comes. Only for requests that are safe to repeat.
+pub fn spawn_with_retry<T, E, F, Fut>(
+    spawner: &dyn TaskSpawner, ctx: &egui::Context, limiter: &RequestLimiter, attempt: F,
+    timeout: Duration, policy: RetryPolicy,
+) -> ChannelTaskHandle<T>
+where
+    T: MaybeSend + 'static,
+    E: Into<TaskError> + MaybeSend + 'static,
+    F: FnMut() -> Fut + MaybeSend + 'static,
+    Fut: Future<Output = Result<T, E>> + MaybeSend + 'static,
+{
+    spawn(spawner, ctx, limiter.throttle(with_retry(attempt, timeout, policy)))
+}
+
+/// Step reached by a multi-step task, e.g. the number of channels updated so far.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    pub done: u32,
+    pub total: u32,
+    pub message: String,
+}
+
+impl Progress {
+    /// Share of the work done, between 0 and 1.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        (self.done as f32 / self.total as f32).min(1.0)
+    }
+}
+
+/// Sent by a task spawned with `spawn_with_progress`: any number of progress updates, then its
+/// result.
+enum TaskEvent<T> {
+    Progress(Progress),
+    Done(Result<T, TaskError>),
+}
+
+/// Lets a task spawned with `spawn_with_progress` report how far it got.
+pub struct ProgressSender<T> {
+    tx: std::sync::mpsc::Sender<TaskEvent<T>>,
+    ctx: egui::Context,
+}
+
+impl<T> ProgressSender<T> {
+    pub fn report(&self, done: u32, total: u32, message: impl Into<String>) {
+        let event = TaskEvent::Progress(Progress { done, total, message: message.into() });
+        let _ = self.tx.send(event);
+        self.ctx.request_repaint();
+    }
+}
+
+/// A task handle like [`ChannelTaskHandle`] for multi-step tasks, which also yields the progress
+/// they report.
+pub struct ProgressTaskHandle<T> {
+    rx: std::sync::mpsc::Receiver<TaskEvent<T>>,
+    /// Result received while taking progress, kept for [`Self::try_take`]
+    result: Option<Result<T, TaskError>>,
+    /// Whether the result was received, after which no more events are expected
+    received: bool,
+    abort: AbortHandle,
+    generation: u64,
+}
+
+impl<T> ProgressTaskHandle<T> {
+    /// The next event sent by the task, or a [`TaskError::Lost`] result if it ended without
+    /// sending one.
+    fn next_event(&mut self) -> Option<TaskEvent<T>> {
+        match self.rx.try_recv() {
+            Ok(event) => Some(event),
+            Err(std::sync::mpsc::TryRecvError::Empty) => None,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                Some(TaskEvent::Done(Err(TaskError::Lost)))
+            }
+        }
+    }
+
+    /// The oldest progress update not taken yet, in the order the task reported them.
+    pub fn try_take_progress(&mut self) -> Option<Progress> {
+        if self.received {
+            return None;
+        }
+        match self.next_event()? {
+            TaskEvent::Progress(progress) => Some(progress),
+            TaskEvent::Done(result) => {
+                self.result = Some(result);
+                self.received = true;
+                None
+            }
+        }
+    }
+
+    /// Check if the task has completed and return the result if so, skipping any progress not
+    /// taken yet. An aborted task yields [`TaskError::Cancelled`].
+    pub fn try_take(&mut self) -> Option<Result<T, TaskError>> {
+        if self.abort.is_aborted() {
+            return Some(Err(TaskError::Cancelled));
+        }
+        while self.try_take_progress().is_some() {}
+        self.result.take()
+    }
+
+    /// Tag the task with the connection generation it was spawned for.
+    pub fn with_generation(mut self, generation: u64) -> Self {
+        self.generation = generation;
+        self
+    }
 
-    ChannelTaskHandle { result }
+    /// Cancel the task, which then yields [`TaskError::Cancelled`].
+    pub fn abort(&self) {
+        self.abort.abort();
+    }
+}
+
+impl<T> Drop for ProgressTaskHandle<T> {
+    fn drop(&mut self) {
+        self.abort.abort();
+    }
+}
+
+/// Spawn a multi-step task with `spawner`. `task` is given a [`ProgressSender`] to report its
+/// progress with, and takes a single slot under `limiter` for all its steps.
+pub fn spawn_with_progress<T, F, Fut>(
+    spawner: &dyn TaskSpawner, ctx: &egui::Context, limiter: &RequestLimiter, task: F,
+) -> ProgressTaskHandle<T>
+where
+    T: MaybeSend + 'static,
+    F: FnOnce(ProgressSender<T>) -> Fut,
+    Fut: Future<Output = Result<T, TaskError>> + MaybeSend + 'static,
+{
+    let (tx, rx) = std::sync::mpsc::channel();
+    let sender = ProgressSender { tx: tx.clone(), ctx: ctx.clone() };
+    let (future, abort) = abortable(limiter.throttle(task(sender)));
+    let end = TaskEndGuard(ctx.clone());
+
+    spawner.spawn(Box::pin(async move {
+        // Bound before the sender so it is dropped after it, even when the task panics
+        let _end = end;
+        let tx = tx;
+        if let Ok(res) = future.await {
+            let _ = tx.send(TaskEvent::Done(res));
+        }
+    }));
+
+    ProgressTaskHandle { rx, result: None, received: false, abort, generation: 0 }
+}
+
+/// A [`ChannelTaskHandle`] with its result type erased, so handles of different types can share
+/// a [`TaskRegistry`].
+trait AnyTask {
+    fn generation(&self) -> u64;
+    fn abort(&self);
+    fn try_take_any(&mut self) -> Option<Result<TaskOutput, TaskError>>;
+
+    fn try_take_progress(&mut self) -> Option<Progress> {
+        None
+    }
+}
+
+impl<T: 'static> AnyTask for ChannelTaskHandle<T> {
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn abort(&self) {
+        ChannelTaskHandle::abort(self)
+    }
+
+    fn try_take_any(&mut self) -> Option<Result<TaskOutput, TaskError>> {
+        Some(self.try_take()?.map(|value| TaskOutput(Box::new(value))))
+    }
+}
+
+impl<T: 'static> AnyTask for ProgressTaskHandle<T> {
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn abort(&self) {
+        ProgressTaskHandle::abort(self)
+    }
+
+    fn try_take_any(&mut self) -> Option<Result<TaskOutput, TaskError>> {
+        Some(self.try_take()?.map(|value| TaskOutput(Box::new(value))))
+    }
+
+    fn try_take_progress(&mut self) -> Option<Progress> {
+        ProgressTaskHandle::try_take_progress(self)
+    }
+}
+
+/// Successful result of a task taken from a [`TaskRegistry`], recovered with [`Self::take`].
+pub struct TaskOutput(Box<dyn Any>);
+
+impl TaskOutput {
+    /// Wrap a value as the result of a task, for results delivered as part of another task's.
+    pub fn new<T: 'static>(value: T) -> Self {
+        TaskOutput(Box::new(value))
+    }
+
+    /// The result as the type the task was inserted with.
+    ///
+    /// Panics if `T` is not that type, which is a bug in the caller.
+    pub fn take<T: 'static>(self) -> T {
+        *self.0.downcast().expect("task output taken as the wrong type")
+    }
+}
+
+/// How a task ended.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskOutcome {
+    Succeeded,
+    Failed,
+    TimedOut,
+    Cancelled,
+}
+
+impl TaskOutcome {
+    fn of<T>(result: &Result<T, TaskError>) -> Self {
+        match result {
+            Ok(_) => TaskOutcome::Succeeded,
+            Err(TaskError::Timeout { .. }) => TaskOutcome::TimedOut,
+            Err(TaskError::Cancelled) => TaskOutcome::Cancelled,
+            Err(_) => TaskOutcome::Failed,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TaskOutcome::Succeeded => "Succeeded",
+            TaskOutcome::Failed => "Failed",
+            TaskOutcome::TimedOut => "Timed out",
+            TaskOutcome::Cancelled => "Cancelled",
+        }
+    }
+}
+
+/// How long a task took, from its insertion into a [`TaskRegistry`] to its result being taken.
+/// This includes any wait for a slot under the [`RequestLimiter`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskTiming<K> {
+    pub kind: K,
+    pub finished_at_ms: u64,
+    pub duration_ms: u64,
+    pub outcome: TaskOutcome,
+}
+
+/// The most recent task timings, dropping the oldest beyond `capacity`.
+pub struct TaskTimings<K> {
+    entries: VecDeque<TaskTiming<K>>,
+    capacity: usize,
+}
+
+impl<K: Copy + Ord> TaskTimings<K> {
+    pub fn new(capacity: usize) -> Self {
+        Self { entries: VecDeque::with_capacity(capacity), capacity }
+    }
+
+    pub fn push(&mut self, timing: TaskTiming<K>) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(timing);
+    }
+
+    /// Recorded timings, newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &TaskTiming<K>> {
+        self.entries.iter().rev()
+    }
+
+    /// Number of recorded tasks and their average duration in milliseconds, for each kind.
+    pub fn averages(&self) -> Vec<(K, usize, u64)> {
+        let mut totals: BTreeMap<K, (usize, u64)> = BTreeMap::new();
+        for timing in &self.entries {
+            let (count, total_ms) = totals.entry(timing.kind).or_default();
+            *count += 1;
+            *total_ms += timing.duration_ms;
+        }
+        totals
+            .into_iter()
+            .map(|(kind, (count, total_ms))| (kind, count, total_ms / count as u64))
+            .collect()
+    }
+}
+
+/// How a request for a resource is handled while a fetch of it with other parameters is in
+/// flight. A request with the same parameters always joins the fetch in flight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoalescePolicy {
+    /// Drop the request and let the fetch in flight finish
+    Ignore,
+    /// Run the request once the fetch in flight completes. A later request replaces an earlier
+    /// queued one.
+    Queue,
+    /// Cancel the fetch in flight and start the request instead
+    Replace,
+}
+
+/// Outcome of [`TaskRegistry::request`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Admission {
+    /// Nothing stands in the way: the caller spawns the task and inserts it
+    Start,
+    /// The fetch in flight has the same parameters and serves the request
+    Joined,
+    /// Kept until the fetch in flight completes, see [`TaskRegistry::take_queued`]
+    Queued,
+    /// Dropped under [`CoalescePolicy::Ignore`]
+    Dropped,
+}
+
+/// In-flight tasks keyed by kind, with at most one task of each kind.
+///
+/// Removing a task, or replacing it with a new one of the same kind, cancels it without a result.
+/// Completed and aborted tasks are yielded in the order of their kinds.
+pub struct TaskRegistry<K> {
+    tasks: BTreeMap<K, Box<dyn AnyTask>>,
+    /// Parameters each task was requested with, only meaningful while the task is registered
+    params: BTreeMap<K, Box<dyn Any>>,
+    /// Requests waiting for the task of their kind to complete
+    queued: BTreeMap<K, Box<dyn Any>>,
+    /// When each task was inserted, in Unix milliseconds
+    started_at: BTreeMap<K, u64>,
+    /// Span of each task, recording its kind and, once it ends, its duration and outcome
+    spans: BTreeMap<K, Span>,
+    /// Timings of the tasks yielded since the last [`Self::take_timings`]
+    finished: Vec<TaskTiming<K>>,
+}
+
+impl<K> Default for TaskRegistry<K> {
+    fn default() -> Self {
+        Self {
+            tasks: BTreeMap::new(),
+            params: BTreeMap::new(),
+            queued: BTreeMap::new(),
+            started_at: BTreeMap::new(),
+            spans: BTreeMap::new(),
+            finished: Vec::new(),
+        }
+    }
+}
+
+impl<K: Copy + Ord + Debug> TaskRegistry<K> {
+    pub fn insert<T: 'static>(&mut self, kind: K, handle: ChannelTaskHandle<T>) {
+        self.tasks.insert(kind, Box::new(handle));
+        self.start(kind);
+    }
+
+    /// Register a multi-step task, whose progress is then yielded by [`Self::take_progress`].
+    pub fn insert_with_progress<T: 'static>(&mut self, kind: K, handle: ProgressTaskHandle<T>) {
+        self.tasks.insert(kind, Box::new(handle));
+        self.start(kind);
+    }
+
+    fn start(&mut self, kind: K) {
+        let span = tracing::info_span!(
+            "task",
+            kind = ?kind,
+            duration_ms = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+        );
+        tracing::debug!(parent: &span, "started");
+        self.spans.insert(kind, span);
+        self.started_at.insert(kind, unix_timestamp_millis());
+    }
+
+    pub fn is_pending(&self, kind: K) -> bool {
+        self.tasks.contains_key(&kind)
+    }
+
+    /// Kinds of the tasks in flight.
+    pub fn pending(&self) -> impl Iterator<Item = K> + '_ {
+        self.tasks.keys().copied()
+    }
+
+    /// Request a fetch of `kind` with `params`, deciding by `policy` what happens when a fetch
+    /// of it with other parameters is in flight. The caller spawns and inserts the task only on
+    /// [`Admission::Start`].
+    pub fn request<P: PartialEq + 'static>(
+        &mut self, kind: K, params: P, policy: CoalescePolicy,
+    ) -> Admission {
+        if self.tasks.contains_key(&kind) {
+            let running = self.params.get(&kind).and_then(|p| p.downcast_ref::<P>());
+            if running == Some(&params) {
+                return Admission::Joined;
+            }
+            match policy {
+                CoalescePolicy::Ignore => return Admission::Dropped,
+                CoalescePolicy::Queue => {
+                    self.queued.insert(kind, Box::new(params));
+                    return Admission::Queued;
+                }
+                CoalescePolicy::Replace => self.remove(kind),
+            }
+        }
+        self.queued.remove(&kind);
+        self.params.insert(kind, Box::new(params));
+        Admission::Start
+    }
+
+    /// Parameters of the request queued behind the task of `kind`, once that task is gone.
+    #[allow(dead_code)] // Used only on native
+    pub fn take_queued<P: 'static>(&mut self, kind: K) -> Option<P> {
+        if self.tasks.contains_key(&kind) {
+            return None;
+        }
+        self.queued.remove(&kind)?.downcast().ok().map(|params| *params)
+    }
+
+    /// Cancel the task of `kind` and any request queued behind it, discarding any result.
+    pub fn remove(&mut self, kind: K) {
+        self.tasks.remove(&kind);
+        self.queued.remove(&kind);
+        self.started_at.remove(&kind);
+        self.spans.remove(&kind);
+    }
+
+    /// Cancel the task of `kind` but keep it registered until the next [`Self::poll`], which
+    /// yields it with [`TaskError::Cancelled`]. A request queued behind it is dropped.
+    pub fn abort(&mut self, kind: K) {
+        if let Some(task) = self.tasks.get(&kind) {
+            task.abort();
+        }
+        self.queued.remove(&kind);
+    }
+
+    /// Take the result of the task of `kind` if it has completed, removing the task.
+    pub fn try_take<T: 'static>(&mut self, kind: K) -> Option<Result<T, TaskError>> {
+        let result = self.tasks.get_mut(&kind)?.try_take_any()?;
+        self.tasks.remove(&kind);
+        self.record_timing(kind, &result);
+        Some(result.map(TaskOutput::take))
+    }
+
+    /// Remove every completed or aborted task, returning their results.
+    pub fn poll(&mut self) -> Vec<(K, Result<TaskOutput, TaskError>)> {
+        let mut completed = Vec::new();
+        self.tasks.retain(|kind, task| match task.try_take_any() {
+            Some(result) => {
+                completed.push((*kind, result));
+                false
+            }
+            None => true,
+        });
+        for (kind, result) in &completed {
+            self.record_timing(*kind, result);
+        }
+        completed
+    }
+
+    fn record_timing<T>(&mut self, kind: K, result: &Result<T, TaskError>) {
+        let finished_at_ms = unix_timestamp_millis();
+        let started_at = self.started_at.remove(&kind).unwrap_or(finished_at_ms);
+        let timing = TaskTiming {
+            kind,
+            finished_at_ms,
+            duration_ms: finished_at_ms.saturating_sub(started_at),
+            outcome: TaskOutcome::of(result),
+        };
+
+        let span = self.spans.remove(&kind).unwrap_or_else(Span::none);
+        span.record("duration_ms", timing.duration_ms);
+        span.record("outcome", timing.outcome.label());
+        match result {
+            Err(e) if matches!(timing.outcome, TaskOutcome::Failed | TaskOutcome::TimedOut) => {
+                tracing::warn!(parent: &span, error = %logs::redact(&e.to_string()), "finished");
+            }
+            _ => tracing::info!(parent: &span, "finished"),
+        }
+        self.finished.push(timing);
+    }
+
+    /// Timings of the tasks yielded by [`Self::poll`] and [`Self::try_take`] since the last call.
+    pub fn take_timings(&mut self) -> Vec<TaskTiming<K>> {
+        std::mem::take(&mut self.finished)
+    }
+
+    /// The latest progress reported by each multi-step task since the last call.
+    pub fn take_progress(&mut self) -> Vec<(K, Progress)> {
+        let mut updates = Vec::new();
+        for (kind, task) in &mut self.tasks {
+            let mut latest = None;
+            while let Some(progress) = task.try_take_progress() {
+                latest = Some(progress);
+            }
+            if let Some(progress) = latest {
+                updates.push((*kind, progress));
+            }
+        }
+        updates
+    }
+
+    /// Drop tasks spawned for an earlier connection, cancelling them and discarding any result.
+    pub fn discard_stale(&mut self, generation: u64) {
+        self.tasks.retain(|_, task| task.generation() == generation);
+        let tasks = &self.tasks;
+        self.queued.retain(|kind, _| tasks.contains_key(kind));
+        self.started_at.retain(|kind, _| tasks.contains_key(kind));
+        self.spans.retain(|kind, _| tasks.contains_key(kind));
+    }
+}
+
+/// Time after a fetch of a resource completes during which it is not fetched again, so
+/// repeated clicks on Refresh do not each send a request.
+pub const REFRESH_COOLDOWN: Duration = Duration::from_secs(1);
+
+/// How often a resource is refreshed in the background.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RefreshSchedule {
+    pub interval_secs: u64,
+    pub paused: bool,
+}
+
+/// Decides which resources are due for a background refresh.
+///
+/// It is evaluated every frame instead of running timers, so it needs no background thread and
+/// works the same on wasm; callers ask egui to repaint after [`Self::next_due_in`].
+pub struct RefreshScheduler<K> {
+    last_run: BTreeMap<K, u64>,
+    /// When the last fetch of each resource completed, in Unix milliseconds
+    completed_at: BTreeMap<K, u64>,
+    /// Resources asked for during their cooldown, fetched once it ends
+    deferred: BTreeSet<K>,
+}
+
+impl<K> Default for RefreshScheduler<K> {
+    fn default() -> Self {
+        Self { last_run: BTreeMap::new(), completed_at: BTreeMap::new(), deferred: BTreeSet::new() }
+    }
+}
+
+impl<K: Copy + Ord> RefreshScheduler<K> {
+    /// Resources whose interval has elapsed at `now` (Unix seconds), skipping paused ones and
+    /// those `busy` reports as already being fetched. The returned resources are recorded as
+    /// refreshed at `now`.
+    pub fn take_due<'a>(
+        &mut self, now: u64, schedules: impl IntoIterator<Item = (&'a K, &'a RefreshSchedule)>,
+        busy: impl Fn(K) -> bool,
+    ) -> Vec<K>
+    where
+        K: 'a,
+    {
+        let mut due = Vec::new();
+        for (&kind, schedule) in schedules {
+            let elapsed = self
+                .last_run
+                .get(&kind)
+                .map_or(true, |last| now.saturating_sub(*last) >= schedule.interval_secs);
+            if !schedule.paused && elapsed && !busy(kind) {
+                self.last_run.insert(kind, now);
+                due.push(kind);
+            }
+        }
+        due
+    }
+
+    /// Seconds until the next unpaused resource is due, `None` if all are paused.
+    pub fn next_due_in<'a>(
+        &self, now: u64, schedules: impl IntoIterator<Item = (&'a K, &'a RefreshSchedule)>,
+    ) -> Option<u64>
+    where
+        K: 'a,
+    {
+        schedules
+            .into_iter()
+            .filter(|(_, schedule)| !schedule.paused)
+            .map(|(kind, schedule)| match self.last_run.get(kind) {
+                Some(last) => (last + schedule.interval_secs).saturating_sub(now),
+                None => 0,
+            })
+            .min()
+    }
+
+    /// Record a refresh done outside the scheduler, e.g. from a Refresh button, so the next
+    /// background one waits a full interval.
+    pub fn mark_run(&mut self, kind: K, now: u64) {
+        self.last_run.insert(kind, now);
+    }
+
+    /// Record that a fetch of `kind` completed at `now_ms` (Unix milliseconds), starting its
+    /// [`REFRESH_COOLDOWN`].
+    pub fn mark_completed(&mut self, kind: K, now_ms: u64) {
+        self.completed_at.insert(kind, now_ms);
+    }
+
+    /// Time left in the cooldown of `kind` at `now_ms`, `None` once it is over.
+    pub fn cooldown_left(&self, kind: K, now_ms: u64) -> Option<Duration> {
+        let ends_at = self.completed_at.get(&kind)? + REFRESH_COOLDOWN.as_millis() as u64;
+        (ends_at > now_ms).then(|| Duration::from_millis(ends_at - now_ms))
+    }
+
+    /// Whether a fetch of `kind` may start at `now_ms`. During its cooldown it may not, and the
+    /// fetch is deferred until [`Self::take_deferred`] yields it.
+    pub fn admit(&mut self, kind: K, now_ms: u64) -> bool {
+        if self.cooldown_left(kind, now_ms).is_some() {
+            self.deferred.insert(kind);
+            return false;
+        }
+        true
+    }
+
+    /// Deferred resources whose cooldown is over at `now_ms`, to be fetched now.
+    pub fn take_deferred(&mut self, now_ms: u64) -> Vec<K> {
+        let ready: Vec<K> = self
+            .deferred
+            .iter()
+            .copied()
+            .filter(|kind| self.cooldown_left(*kind, now_ms).is_none())
+            .collect();
+        for kind in &ready {
+            self.deferred.remove(kind);
+        }
+        ready
+    }
+
+    /// Time until the next deferred resource may be fetched, `None` if none is deferred.
+    pub fn next_deferred_in(&self, now_ms: u64) -> Option<Duration> {
+        let left = |kind: &K| self.cooldown_left(*kind, now_ms).unwrap_or_default();
+        self.deferred.iter().map(left).min()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn block_on<T>(future: impl Future<Output = T>) -> T {
+        tokio::runtime::Runtime::new().unwrap().block_on(future)
+    }
+
+    fn ctx() -> egui::Context {
+        egui::Context::default()
+    }
+
+    #[test]
+    fn test_with_timeout_expires() {
+        let never = futures_util::future::pending::<Result<(), String>>();
+        let err = block_on(with_timeout(never, Duration::from_millis(10))).unwrap_err();
+        assert_eq!(err, TaskError::Timeout { after: Duration::from_millis(10) });
+    }
+
+    #[test]
+    fn test_with_timeout_passes_result_through() {
+        let ok = async { Ok::<_, String>(42) };
+        assert_eq!(block_on(with_timeout(ok, Duration::from_secs(1))), Ok(42));
+
+        let err = async { Err::<(), _>("Esplora request failed".to_string()) };
+        let err = block_on(with_timeout(err, Duration::from_secs(1))).unwrap_err();
+        assert_eq!(err, TaskError::Other("Esplora request failed".to_string()));
+    }
+
+    #[test]
+    fn test_spawned_timeout_frees_handle() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let never = futures_util::future::pending::<Result<(), String>>();
+        let never = with_timeout(never, Duration::from_millis(10));
+        let mut handle = spawn(&rt, &ctx(), never);
+        rt.block_on(tokio::time::sleep(Duration::from_millis(50)));
+        let err = handle.try_take().unwrap().unwrap_err();
+        assert!(matches!(err, TaskError::Timeout { .. }));
+    }
+
+    #[test]
+    fn test_spawn_with_timeout_expires() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let never = futures_util::future::pending::<Result<(), String>>();
+        let limiter = RequestLimiter::default();
+        let timeout = Duration::from_millis(20);
+        let mut handle = spawn_with_timeout(&rt, &ctx(), &limiter, never, timeout);
+        rt.block_on(tokio::time::sleep(Duration::from_millis(60)));
+        let err = handle.try_take().unwrap().unwrap_err();
+        assert!(matches!(err, TaskError::Timeout { .. }));
+    }
+
+    #[test]
+    fn test_spawn_with_timeout_completes_before_deadline() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let slow = async {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            Ok::<_, String>(7)
+        };
+        let limiter = RequestLimiter::default();
+        let timeout = Duration::from_millis(300);
+        let mut handle = spawn_with_timeout(&rt, &ctx(), &limiter, slow, timeout);
+        rt.block_on(tokio::time::sleep(Duration::from_millis(400)));
+        assert_eq!(handle.try_take(), Some(Ok(7)));
+    }
+
+    const FAST_RETRY: RetryPolicy =
+        RetryPolicy { max_attempts: 3, initial_backoff: Duration::from_millis(1) };
+
+    /// Run `with_retry` over attempts failing with `failures` in turn, then succeeding. Returns
+    /// the result and the number of attempts made.
+    fn retry_with_failures(failures: Vec<TaskError>) -> (Result<u32, TaskError>, usize) {
+        let attempts = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = attempts.clone();
+        let attempt = move || {
+            let n = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let result = failures.get(n).cloned().map_or(Ok(7), Err);
+            async move { result }
+        };
+        let result = block_on(with_retry(attempt, Duration::from_secs(1), FAST_RETRY));
+        (result, attempts.load(std::sync::atomic::Ordering::SeqCst))
+    }
+
+    #[test]
+    fn test_retry_backoff_schedule() {
+        let policy = RetryPolicy::READ_ONLY;
+        let delays: Vec<_> = (0..4).map(|retry| policy.backoff(retry).as_millis()).collect();
+        assert_eq!(delays, [500, 1000, 2000, 4000]);
+    }
+
+    #[test]
+    fn test_retry_gating() {
+        let network = TaskError::Network { message: "HTTP request failed: refused".to_string() };
+        let timeout = TaskError::Timeout { after: Duration::from_secs(1) };
+        let auth = TaskError::Auth { message: "Invalid API key".to_string() };
+
+        // Transient failures are retried until an attempt succeeds
+        assert_eq!(retry_with_failures(vec![network.clone(), timeout.clone()]), (Ok(7), 3));
+        // Permanent failures are reported at once
+        assert_eq!(retry_with_failures(vec![auth.clone()]), (Err(auth), 1));
+        assert_eq!(
+            retry_with_failures(vec![TaskError::Other("bad".to_string())]),
+            (Err(TaskError::Other("bad".to_string())), 1)
+        );
+        // The last transient failure is reported once the attempts run out
+        let failures = vec![network.clone(), network.clone(), timeout.clone(), network];
+        assert_eq!(retry_with_failures(failures), (Err(timeout), 3));
+    }
+
+    #[test]
+    fn test_retry_times_out_each_attempt() {
+        let attempts = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = attempts.clone();
+        let attempt = move || {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            futures_util::future::pending::<Result<(), TaskError>>()
+        };
+        let err = block_on(with_retry(attempt, Duration::from_millis(10), FAST_RETRY));
+        assert_eq!(err, Err(TaskError::Timeout { after: Duration::from_millis(10) }));
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    /// Spawn `count` tasks under `limiter` that log their index when they start, then run for
+    /// `millis`.
+    fn spawn_logged(
+        rt: &tokio::runtime::Runtime, limiter: &RequestLimiter, count: usize, millis: u64,
+    ) -> (Vec<ChannelTaskHandle<()>>, std::sync::Arc<std::sync::Mutex<Vec<usize>>>) {
+        let log = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let handles = (0..count)
+            .map(|index| {
+                let log = log.clone();
+                let task = limiter.throttle(async move {
+                    log.lock().unwrap().push(index);
+                    tokio::time::sleep(Duration::from_millis(millis)).await;
+                    Ok(())
+                });
+                spawn(rt, &ctx(), task)
+            })
+            .collect();
+        (handles, log)
+    }
+
+    #[test]
+    fn test_limiter_runs_tasks_in_order_within_limit() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let limiter = RequestLimiter::new(2);
+        let (_handles, log) = spawn_logged(&rt, &limiter, 5, 40);
+
+        rt.block_on(tokio::time::sleep(Duration::from_millis(20)));
+        assert_eq!(*log.lock().unwrap(), [0, 1]);
+        assert_eq!(limiter.counts(), (2, 3));
+
+        rt.block_on(tokio::time::sleep(Duration::from_millis(40)));
+        assert_eq!(*log.lock().unwrap(), [0, 1, 2, 3]);
+        assert_eq!(limiter.counts(), (2, 1));
+
+        rt.block_on(tokio::time::sleep(Duration::from_millis(100)));
+        assert_eq!(*log.lock().unwrap(), [0, 1, 2, 3, 4]);
+        assert_eq!(limiter.counts(), (0, 0));
+    }
+
+    #[test]
+    fn test_limiter_cancel_and_raise_limit() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let limiter = RequestLimiter::new(1);
+        let (mut handles, log) = spawn_logged(&rt, &limiter, 4, 1000);
+        rt.block_on(tokio::time::sleep(Duration::from_millis(20)));
+        assert_eq!(limiter.counts(), (1, 3));
+
+        // A cancelled task gives up its place in the queue
+        drop(handles.remove(1));
+        rt.block_on(tokio::time::sleep(Duration::from_millis(20)));
+        assert_eq!(limiter.counts(), (1, 2));
+
+        // Raising the limit starts the waiting tasks in order
+        limiter.set_limit(3);
+        rt.block_on(tokio::time::sleep(Duration::from_millis(20)));
+        assert_eq!(*log.lock().unwrap(), [0, 2, 3]);
+        assert_eq!(limiter.counts(), (3, 0));
+
+        // Cancelling a running task frees its slot
+        drop(handles);
+        rt.block_on(tokio::time::sleep(Duration::from_millis(20)));
+        assert_eq!(limiter.counts(), (0, 0));
+    }
+
+    /// Runs tasks on its own tokio runtime, counting them.
+    struct CountingSpawner {
+        rt: tokio::runtime::Runtime,
+        spawned: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl TaskSpawner for CountingSpawner {
+        fn spawn(&self, task: BoxTask) {
+            self.spawned.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.rt.spawn(task);
+        }
+    }
+
+    #[test]
+    fn test_boxed_spawner_runs_every_kind_of_task() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let spawned = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let spawner: Box<dyn TaskSpawner> =
+            Box::new(CountingSpawner { rt, spawned: spawned.clone() });
+        let limiter = RequestLimiter::default();
+        let timeout = Duration::from_secs(1);
+        let ready = async { Ok::<_, TaskError>(1) };
+        let mut plain = spawn(&*spawner, &ctx(), ready);
+        let ready = async { Ok::<_, TaskError>(2) };
+        let mut timed = spawn_with_timeout(&*spawner, &ctx(), &limiter, ready, timeout);
+        let attempt = || async { Ok::<_, TaskError>(3) };
+        let mut retried =
+            spawn_with_retry(&*spawner, &ctx(), &limiter, attempt, timeout, FAST_RETRY);
+        let mut stepped = spawn_with_progress(&*spawner, &ctx(), &limiter, |progress| async move {
+            progress.report(1, 1, "Step 1");
+            Ok(4)
+        });
+        std::thread::sleep(Duration::from_millis(50));
+
+        assert_eq!(plain.try_take(), Some(Ok(1)));
+        assert_eq!(timed.try_take(), Some(Ok(2)));
+        assert_eq!(retried.try_take(), Some(Ok(3)));
+        assert_eq!(stepped.try_take(), Some(Ok(4)));
+        assert_eq!(spawned.load(std::sync::atomic::Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_completion_requests_repaint() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let ctx = ctx();
+        let repaints = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = repaints.clone();
+        ctx.set_request_repaint_callback(move |_| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        });
+
+        let slow = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            Ok(1)
+        };
+        let mut handle = spawn(&rt, &ctx, slow);
+        wait(&rt, 50);
+        assert_eq!(repaints.load(std::sync::atomic::Ordering::SeqCst), 0);
+        wait(&rt, 100);
+        assert!(repaints.load(std::sync::atomic::Ordering::SeqCst) > 0);
+        assert_eq!(handle.try_take(), Some(Ok(1)));
+    }
+
+    #[test]
+    fn test_abort_cancels_task() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut handle = spawn(&rt, &ctx(), async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let _ = tx.send(());
+            Ok(())
+        });
+        assert_eq!(handle.try_take(), None);
+        handle.abort();
+        rt.block_on(tokio::time::sleep(Duration::from_millis(50)));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(handle.try_take(), Some(Err(TaskError::Cancelled)));
+    }
+
+    #[test]
+    fn test_dropping_handle_aborts_task() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let handle = spawn(&rt, &ctx(), async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let _ = tx.send(());
+            Ok::<_, TaskError>(())
+        });
+        drop(handle);
+        rt.block_on(tokio::time::sleep(Duration::from_millis(50)));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_progress_delivered_in_order() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let limiter = RequestLimiter::default();
+        let mut handle = spawn_with_progress(&rt, &ctx(), &limiter, |progress| async move {
+            for done in 1..=3 {
+                progress.report(done, 3, format!("Step {}", done));
+            }
+            Ok("finished")
+        });
+        rt.block_on(tokio::time::sleep(Duration::from_millis(20)));
+
+        let reported: Vec<_> = std::iter::from_fn(|| handle.try_take_progress()).collect();
+        let steps: Vec<_> = reported.iter().map(|p| (p.done, p.message.as_str())).collect();
+        assert_eq!(steps, [(1, "Step 1"), (2, "Step 2"), (3, "Step 3")]);
+        assert_eq!(reported[1].fraction(), 2.0 / 3.0);
+        assert_eq!(handle.try_take(), Some(Ok("finished")));
+        assert_eq!(handle.try_take(), None);
+    }
+
+    #[test]
+    fn test_progress_result_skips_untaken_progress() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let limiter = RequestLimiter::default();
+        let mut handle = spawn_with_progress(&rt, &ctx(), &limiter, |progress| async move {
+            progress.report(1, 2, "Step 1");
+            Err::<(), _>(TaskError::Other("boom".to_string()))
+        });
+        rt.block_on(tokio::time::sleep(Duration::from_millis(20)));
+        assert_eq!(handle.try_take(), Some(Err(TaskError::Other("boom".to_string()))));
+        assert_eq!(handle.try_take_progress(), None);
+    }
+
+    async fn panics() -> Result<(), TaskError> {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        panic!("task panicked")
+    }
+
+    #[test]
+    fn test_panicked_task_is_lost() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let limiter = RequestLimiter::default();
+        let mut handle = spawn(&rt, &ctx(), panics());
+        let mut progress_handle = spawn_with_progress(&rt, &ctx(), &limiter, |progress| async move {
+            progress.report(1, 2, "Step 1");
+            panics().await
+        });
+        assert_eq!(handle.try_take(), None);
+        wait(&rt, 50);
+
+        assert_eq!(handle.try_take(), Some(Err(TaskError::Lost)));
+        assert_eq!(handle.try_take(), None);
+        assert_eq!(progress_handle.try_take_progress().map(|p| p.done), Some(1));
+        assert_eq!(progress_handle.try_take(), Some(Err(TaskError::Lost)));
+        // The panicked task's limiter slot is freed too
+        assert_eq!(limiter.counts(), (0, 0));
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    enum Kind {
+        Fast,
+        Slow,
+    }
+
+    fn never(rt: &tokio::runtime::Runtime) -> ChannelTaskHandle<()> {
+        spawn(rt, &ctx(), futures_util::future::pending())
+    }
+
+    fn wait(rt: &tokio::runtime::Runtime, millis: u64) {
+        rt.block_on(tokio::time::sleep(Duration::from_millis(millis)));
+    }
+
+    #[test]
+    fn test_registry_insert_and_poll() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let mut registry = TaskRegistry::default();
+        assert_eq!(registry.pending().count(), 0);
+
+        registry.insert(Kind::Slow, never(&rt));
+        registry.insert(Kind::Fast, spawn(&rt, &ctx(), async { Ok(3u32) }));
+        assert!(registry.is_pending(Kind::Fast));
+        assert_eq!(registry.pending().collect::<Vec<_>>(), [Kind::Fast, Kind::Slow]);
+
+        wait(&rt, 20);
+        let completed = registry.poll();
+        assert_eq!(completed.len(), 1);
+        let (kind, result) = completed.into_iter().next().unwrap();
+        assert_eq!(kind, Kind::Fast);
+        assert_eq!(result.unwrap().take::<u32>(), 3);
+        assert!(!registry.is_pending(Kind::Fast));
+        assert!(registry.is_pending(Kind::Slow));
+        assert!(registry.poll().is_empty());
+    }
+
+    #[test]
+    fn test_registry_try_take() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let mut registry = TaskRegistry::default();
+        let failing = async { Err::<u32, _>(TaskError::Other("boom".to_string())) };
+        registry.insert(Kind::Fast, spawn(&rt, &ctx(), failing));
+        wait(&rt, 20);
+        assert_eq!(registry.try_take::<u32>(Kind::Slow), None);
+        let failed = registry.try_take::<u32>(Kind::Fast);
+        assert_eq!(failed, Some(Err(TaskError::Other("boom".to_string()))));
+        assert_eq!(registry.pending().count(), 0);
+    }
+
+    #[test]
+    fn test_registry_clear() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let mut registry = TaskRegistry::default();
+
+        registry.insert(Kind::Fast, never(&rt));
+        registry.abort(Kind::Fast);
+        assert!(registry.is_pending(Kind::Fast));
+        let completed = registry.poll();
+        assert!(matches!(completed[..], [(Kind::Fast, Err(TaskError::Cancelled))]));
+        assert_eq!(registry.pending().count(), 0);
+
+        registry.insert(Kind::Fast, never(&rt).with_generation(1));
+        registry.insert(Kind::Slow, never(&rt).with_generation(2));
+        registry.discard_stale(2);
+        assert_eq!(registry.pending().collect::<Vec<_>>(), [Kind::Slow]);
+        registry.remove(Kind::Slow);
+        assert_eq!(registry.pending().count(), 0);
+    }
+
+    #[test]
+    fn test_registry_coalescing() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let mut registry = TaskRegistry::default();
+
+        assert_eq!(registry.request(Kind::Fast, 1u32, CoalescePolicy::Ignore), Admission::Start);
+        registry.insert(Kind::Fast, never(&rt));
+        // Identical requests join the fetch in flight whatever the policy
+        for policy in [CoalescePolicy::Ignore, CoalescePolicy::Queue, CoalescePolicy::Replace] {
+            assert_eq!(registry.request(Kind::Fast, 1u32, policy), Admission::Joined);
+        }
+        assert_eq!(registry.request(Kind::Fast, 2u32, CoalescePolicy::Ignore), Admission::Dropped);
+        assert_eq!(registry.take_queued::<u32>(Kind::Fast), None);
+
+        // Replacing cancels the fetch in flight without a result
+        assert_eq!(registry.request(Kind::Fast, 2u32, CoalescePolicy::Replace), Admission::Start);
+        assert!(!registry.is_pending(Kind::Fast));
+        registry.insert(Kind::Fast, never(&rt));
+        assert_eq!(registry.request(Kind::Fast, 2u32, CoalescePolicy::Replace), Admission::Joined);
+
+        // Other parameters wait for the fetch in flight, and are run once it completes
+        let slow = spawn(&rt, &ctx(), async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(())
+        });
+        assert_eq!(registry.request(Kind::Slow, "a", CoalescePolicy::Queue), Admission::Start);
+        registry.insert(Kind::Slow, slow);
+        assert_eq!(registry.request(Kind::Slow, "b", CoalescePolicy::Queue), Admission::Queued);
+        assert_eq!(registry.request(Kind::Slow, "c", CoalescePolicy::Queue), Admission::Queued);
+        assert_eq!(registry.take_queued::<&str>(Kind::Slow), None);
+        wait(&rt, 50);
+        let completed = registry.poll();
+        assert!(matches!(completed[..], [(Kind::Slow, Ok(_))]));
+        assert_eq!(registry.take_queued::<&str>(Kind::Slow), Some("c"));
+        assert_eq!(registry.take_queued::<&str>(Kind::Slow), None);
+
+        // Cancelling drops the queued request too
+        assert_eq!(registry.request(Kind::Fast, 3u32, CoalescePolicy::Queue), Admission::Queued);
+        registry.abort(Kind::Fast);
+        registry.poll();
+        assert_eq!(registry.take_queued::<u32>(Kind::Fast), None);
+    }
+
+    #[test]
+    fn test_registry_records_timings() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let mut registry = TaskRegistry::default();
+        let slow = async {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            Ok(())
+        };
+        registry.insert(Kind::Slow, spawn(&rt, &ctx(), slow));
+        registry.insert(Kind::Fast, never(&rt));
+        registry.abort(Kind::Fast);
+        wait(&rt, 60);
+        assert_eq!(registry.poll().len(), 2);
+
+        let timings = registry.take_timings();
+        assert_eq!(timings.len(), 2);
+        assert_eq!((timings[0].kind, timings[0].outcome), (Kind::Fast, TaskOutcome::Cancelled));
+        assert_eq!((timings[1].kind, timings[1].outcome), (Kind::Slow, TaskOutcome::Succeeded));
+        assert!(timings[1].duration_ms >= 30);
+        assert!(registry.take_timings().is_empty());
+    }
+
+    #[test]
+    fn test_task_timings_bounded() {
+        let mut timings = TaskTimings::new(3);
+        let kinds = [Kind::Fast, Kind::Slow, Kind::Fast, Kind::Fast, Kind::Slow];
+        for (i, kind) in kinds.into_iter().enumerate() {
+            let duration_ms = 10 * (i as u64 + 1);
+            let outcome = TaskOutcome::Succeeded;
+            timings.push(TaskTiming { kind, finished_at_ms: 0, duration_ms, outcome });
+        }
+        let durations: Vec<_> = timings.iter().map(|t| t.duration_ms).collect();
+        assert_eq!(durations, [50, 40, 30]);
+        assert_eq!(timings.averages(), [(Kind::Fast, 2, 35), (Kind::Slow, 1, 50)]);
+    }
+
+    #[test]
+    fn test_registry_progress() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let mut registry = TaskRegistry::default();
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        registry.insert(Kind::Fast, never(&rt));
+        let limiter = RequestLimiter::default();
+        let handle = spawn_with_progress(&rt, &ctx(), &limiter, move |progress| async move {
+            progress.report(1, 2, "First");
+            progress.report(2, 2, "Second");
+            while rx.try_recv().is_err() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+            Ok(2u32)
+        });
+        registry.insert_with_progress(Kind::Slow, handle);
+        wait(&rt, 20);
+
+        // Only the latest update of each task is yielded
+        let updates = registry.take_progress();
+        assert!(matches!(&updates[..], [(Kind::Slow, p)] if p.message == "Second"));
+        assert!(registry.take_progress().is_empty());
+
+        tx.send(()).unwrap();
+        wait(&rt, 30);
+        let completed = registry.poll();
+        assert_eq!(completed.len(), 1);
+        let (kind, result) = completed.into_iter().next().unwrap();
+        assert_eq!(kind, Kind::Slow);
+        assert_eq!(result.unwrap().take::<u32>(), 2);
+    }
+
+    fn schedule(interval_secs: u64, paused: bool) -> RefreshSchedule {
+        RefreshSchedule { interval_secs, paused }
+    }
+
+    #[test]
+    fn test_scheduler_intervals() {
+        let schedules =
+            BTreeMap::from([(Kind::Fast, schedule(10, false)), (Kind::Slow, schedule(60, false))]);
+        let mut scheduler = RefreshScheduler::default();
+
+        // Everything is due on the first evaluation
+        assert_eq!(scheduler.take_due(100, &schedules, |_| false), [Kind::Fast, Kind::Slow]);
+        assert!(scheduler.take_due(105, &schedules, |_| false).is_empty());
+        assert_eq!(scheduler.next_due_in(105, &schedules), Some(5));
+
+        assert_eq!(scheduler.take_due(110, &schedules, |_| false), [Kind::Fast]);
+        assert_eq!(scheduler.take_due(160, &schedules, |_| false), [Kind::Fast, Kind::Slow]);
+
+        scheduler.mark_run(Kind::Fast, 165);
+        assert!(scheduler.take_due(170, &schedules, |_| false).is_empty());
+        assert_eq!(scheduler.take_due(175, &schedules, |_| false), [Kind::Fast]);
+    }
+
+    #[test]
+    fn test_scheduler_skips_paused_and_busy() {
+        let mut schedules =
+            BTreeMap::from([(Kind::Fast, schedule(10, false)), (Kind::Slow, schedule(10, true))]);
+        let mut scheduler = RefreshScheduler::default();
+
+        assert!(scheduler.take_due(100, &schedules, |kind| kind == Kind::Fast).is_empty());
+        // A busy resource is not recorded as run, so it is picked up once the fetch finishes
+        assert_eq!(scheduler.take_due(101, &schedules, |_| false), [Kind::Fast]);
+
+        schedules.insert(Kind::Fast, schedule(10, true));
+        assert!(scheduler.take_due(200, &schedules, |_| false).is_empty());
+        assert_eq!(scheduler.next_due_in(200, &schedules), None);
+    }
+
+    #[test]
+    fn test_scheduler_cooldown() {
+        let mut scheduler = RefreshScheduler::default();
+        assert!(scheduler.admit(Kind::Fast, 10_000));
+
+        scheduler.mark_completed(Kind::Fast, 10_000);
+        assert_eq!(scheduler.cooldown_left(Kind::Fast, 10_400), Some(Duration::from_millis(600)));
+        assert!(!scheduler.admit(Kind::Fast, 10_400));
+        // Other resources have their own cooldown
+        assert!(scheduler.admit(Kind::Slow, 10_400));
+        assert_eq!(scheduler.next_deferred_in(10_400), Some(Duration::from_millis(600)));
+
+        // A fetch asked for during the cooldown is deferred until it ends, once
+        assert!(scheduler.take_deferred(10_900).is_empty());
+        assert_eq!(scheduler.take_deferred(11_000), [Kind::Fast]);
+        assert!(scheduler.take_deferred(11_500).is_empty());
+        assert_eq!(scheduler.cooldown_left(Kind::Fast, 11_000), None);
+        assert!(scheduler.admit(Kind::Fast, 11_000));
+        assert_eq!(scheduler.next_deferred_in(11_000), None);
+    }
 }
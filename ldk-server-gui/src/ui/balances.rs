@@ -1,80 +1,152 @@
 use egui::Ui;
 
 use crate::app::LdkServerApp;
-use crate::state::ConnectionStatus;
-use crate::ui::format_sats;
+use crate::network::Network;
+use crate::liquidity::{self, LiquidityDirection};
+use crate::spendable;
+use crate::state::{ActiveTab, ConnectionStatus, TaskKind};
+use crate::sweeps::{self, SweepProgress};
+use crate::ui::{
+    balance_change, data_age, fiat_value, format_msat, format_sats, json_export_buttons,
+    refresh_button, truncate_id,
+};
+use ldk_server_client::ldk_server_protos::api::GetBalancesResponse;
+use ldk_server_client::ldk_server_protos::types::lightning_balance::BalanceType;
+use ldk_server_client::ldk_server_protos::types::LightningBalance;
 
 pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
     ui.heading("Balances");
     ui.add_space(10.0);
 
-    if !matches!(app.state.connection_status, ConnectionStatus::Connected) {
+    if !matches!(app.state.session.connection_status, ConnectionStatus::Connected) {
         ui.label("Connect to a server to view balances.");
         return;
     }
+    app.refresh_fiat_rate();
+
+    // The channel liquidity table needs channels; fetch them once per connection if missing
+    let session = &mut app.state.session;
+    if session.channels.is_none()
+        && session.balances_channels_generation != Some(session.connection_generation)
+    {
+        session.balances_channels_generation = Some(session.connection_generation);
+        app.fetch_channels();
+    }
 
     ui.horizontal(|ui| {
-        if app.state.tasks.balances.is_some() {
+        if app.state.session.tasks.is_pending(TaskKind::Balances) {
             ui.spinner();
             ui.label("Loading...");
-        } else if ui.button("Refresh").clicked() {
+        } else if refresh_button(ui, &app.state.session.refresh, TaskKind::Balances) {
             app.fetch_balances();
         }
+        data_age(ui, app.state.session.balances.as_ref(), app.state.data_age_thresholds);
+        if let Some(balances) = &app.state.session.balances {
+            let redact = &mut app.state.redact_json_node_ids;
+            if json_export_buttons(ui, &balances.data, redact, &mut app.state.status_message) {
+                let balances = balances.data.clone();
+                app.save_json("balances", balances);
+            }
+        }
     });
 
     ui.add_space(10.0);
 
-    if let Some(balances) = &app.state.balances {
+    render_liquidity_alerts(ui, app);
+
+    if let Some(balances) = app.state.session.balances.as_ref().map(|b| &b.data) {
+        let rate = app.state.usable_fiat_rate();
+        let changes = app.state.session.balance_changes;
         ui.group(|ui| {
-            ui.heading("On-chain Balance");
+            ui.horizontal(|ui| {
+                ui.heading("On-chain Balance");
+                if app.state.session.balance_refresh.is_some() {
+                    ui.label(egui::RichText::new("updating…").small().color(egui::Color32::GRAY));
+                }
+            });
             egui::Grid::new("onchain_balance_grid")
                 .num_columns(2)
                 .spacing([10.0, 5.0])
                 .show(ui, |ui| {
                     ui.label("Total:");
-                    ui.monospace(format!("{} sats", format_sats(balances.total_onchain_balance_sats)));
+                    ui.horizontal(|ui| {
+                        ui.monospace(format_sats(balances.total_onchain_balance_sats));
+                        fiat_value(ui, rate, balances.total_onchain_balance_sats);
+                        balance_change(ui, changes.map(|c| c.total_onchain));
+                    });
                     ui.end_row();
 
                     ui.label("Spendable:");
-                    ui.monospace(format!(
-                        "{} sats",
-                        format_sats(balances.spendable_onchain_balance_sats)
-                    ));
+                    ui.horizontal(|ui| {
+                        ui.monospace(format_sats(balances.spendable_onchain_balance_sats));
+                        fiat_value(ui, rate, balances.spendable_onchain_balance_sats);
+                        balance_change(ui, changes.map(|c| c.spendable_onchain));
+                    });
                     ui.end_row();
 
                     ui.label("Anchor Reserve:");
-                    ui.monospace(format!(
-                        "{} sats",
-                        format_sats(balances.total_anchor_channels_reserve_sats)
-                    ));
+                    ui.horizontal(|ui| {
+                        ui.monospace(format_sats(balances.total_anchor_channels_reserve_sats));
+                        balance_change(ui, changes.map(|c| c.anchor_reserve));
+                    });
                     ui.end_row();
                 });
+            render_spendable_shortfall(ui, balances);
         });
 
         ui.add_space(10.0);
 
         ui.group(|ui| {
             ui.heading("Lightning Balance");
-            ui.monospace(format!(
-                "Total: {} sats",
-                format_sats(balances.total_lightning_balance_sats)
-            ));
+            ui.horizontal(|ui| {
+                ui.monospace(format!(
+                    "Total claimable: {}",
+                    format_sats(balances.total_lightning_balance_sats)
+                ));
+                fiat_value(ui, rate, balances.total_lightning_balance_sats);
+                balance_change(ui, changes.map(|c| c.total_lightning));
+            });
+            ui.label(
+                egui::RichText::new(
+                    "Amounts claimable on-chain if the channels were closed now, before fees.",
+                )
+                .small()
+                .color(egui::Color32::GRAY),
+            );
 
-            if !balances.lightning_balances.is_empty() {
+            for category in lightning_balance_categories(&balances.lightning_balances) {
                 ui.add_space(5.0);
+                let total = category.entries.iter().map(|e| e.amount_sats).sum();
                 egui::CollapsingHeader::new(format!(
-                    "Lightning Balance Details ({} items)",
-                    balances.lightning_balances.len()
+                    "{}: {} ({})",
+                    category.label,
+                    format_sats(total),
+                    category.entries.len()
                 ))
+                .id_salt(category.label)
                 .show(ui, |ui| {
-                    for (i, balance) in balances.lightning_balances.iter().enumerate() {
-                        ui.group(|ui| {
-                            ui.label(format!("Balance #{}", i + 1));
-                            if let Some(balance_type) = &balance.balance_type {
-                                render_lightning_balance(ui, balance_type);
+                    ui.label(egui::RichText::new(category.description).small().italics());
+                    egui::Grid::new(("lightning_balance_grid", category.label))
+                        .striped(true)
+                        .num_columns(4)
+                        .spacing([10.0, 5.0])
+                        .show(ui, |ui| {
+                            ui.strong("Channel");
+                            ui.strong("Counterparty");
+                            ui.strong("Amount");
+                            ui.strong("Details");
+                            ui.end_row();
+
+                            for entry in &category.entries {
+                                ui.monospace(truncate_id(entry.channel_id, 8, 8))
+                                    .on_hover_text(entry.channel_id);
+                                ui.monospace(truncate_id(entry.counterparty_node_id, 8, 8))
+                                    .on_hover_text(entry.counterparty_node_id);
+                                ui.monospace(format_sats(entry.amount_sats));
+                                ui.label(entry.details.as_deref().unwrap_or("-"));
+                                ui.end_row();
                             }
                         });
-                    }
                 });
             }
         });
@@ -89,13 +161,21 @@ pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
                     balances.pending_balances_from_channel_closures.len()
                 ))
                 .show(ui, |ui| {
+                    let network = app.state.session.chain_network().unwrap_or_default();
+                    let tip_height = app
+                        .state
+                        .session
+                        .node_info
+                        .as_ref()
+                        .and_then(|info| info.data.current_best_block.as_ref())
+                        .map(|block| block.height);
                     for (i, sweep) in
                         balances.pending_balances_from_channel_closures.iter().enumerate()
                     {
                         ui.group(|ui| {
                             ui.label(format!("Sweep #{}", i + 1));
                             if let Some(balance_type) = &sweep.balance_type {
-                                render_pending_sweep(ui, balance_type);
+                                render_pending_sweep(ui, balance_type, network, tip_height);
                             }
                         });
                     }
@@ -105,55 +185,243 @@ pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
     } else {
         ui.label("No balance data available. Click Refresh to fetch.");
     }
-}
 
-fn render_lightning_balance(
-    ui: &mut Ui,
-    balance: &ldk_server_client::ldk_server_protos::types::lightning_balance::BalanceType,
-) {
-    use ldk_server_client::ldk_server_protos::types::lightning_balance::BalanceType;
+    ui.add_space(10.0);
+    render_channel_liquidity(ui, app);
+}
 
-    match balance {
-        BalanceType::ClaimableOnChannelClose(b) => {
-            ui.label("Type: Claimable on Channel Close");
-            ui.label(format!("Channel: {}", crate::ui::truncate_id(&b.channel_id, 8, 8)));
-            ui.label(format!("Amount: {} sats", format_sats(b.amount_satoshis)));
-        }
-        BalanceType::ClaimableAwaitingConfirmations(b) => {
-            ui.label("Type: Awaiting Confirmations");
-            ui.label(format!("Channel: {}", crate::ui::truncate_id(&b.channel_id, 8, 8)));
-            ui.label(format!("Amount: {} sats", format_sats(b.amount_satoshis)));
-            ui.label(format!("Confirmation Height: {}", b.confirmation_height));
-        }
-        BalanceType::ContentiousClaimable(b) => {
-            ui.label("Type: Contentious Claimable");
-            ui.label(format!("Channel: {}", crate::ui::truncate_id(&b.channel_id, 8, 8)));
-            ui.label(format!("Amount: {} sats", format_sats(b.amount_satoshis)));
-            ui.label(format!("Timeout Height: {}", b.timeout_height));
-        }
-        BalanceType::MaybeTimeoutClaimableHtlc(b) => {
-            ui.label("Type: Maybe Timeout Claimable HTLC");
-            ui.label(format!("Channel: {}", crate::ui::truncate_id(&b.channel_id, 8, 8)));
-            ui.label(format!("Amount: {} sats", format_sats(b.amount_satoshis)));
-            ui.label(format!("Claimable Height: {}", b.claimable_height));
+/// Outbound, inbound and reserve amounts of each channel with their totals. Channel ids link to
+/// the channel on the Channels tab.
+fn render_channel_liquidity(ui: &mut Ui, app: &mut LdkServerApp) {
+    ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.heading("Channel Liquidity");
+            if app.state.session.tasks.is_pending(TaskKind::Channels) {
+                ui.spinner();
+            }
+        });
+        let Some(channels) = app.state.session.channels.as_ref().map(|c| &c.data.channels) else {
+            ui.label("Channels not loaded yet.");
+            return;
+        };
+        if channels.is_empty() {
+            ui.label("No channels.");
+            return;
         }
-        BalanceType::MaybePreimageClaimableHtlc(b) => {
-            ui.label("Type: Maybe Preimage Claimable HTLC");
-            ui.label(format!("Channel: {}", crate::ui::truncate_id(&b.channel_id, 8, 8)));
-            ui.label(format!("Amount: {} sats", format_sats(b.amount_satoshis)));
-            ui.label(format!("Expiry Height: {}", b.expiry_height));
+
+        let mut linked_channel = None;
+        egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+            egui::Grid::new("channel_liquidity_grid")
+                .striped(true)
+                .num_columns(5)
+                .spacing([12.0, 5.0])
+                .show(ui, |ui| {
+                    ui.strong("Channel");
+                    ui.strong("Counterparty");
+                    ui.strong("Outbound");
+                    ui.strong("Inbound");
+                    ui.strong("Reserve");
+                    ui.end_row();
+
+                    for channel in channels {
+                        let link = ui.link(truncate_id(&channel.channel_id, 8, 8));
+                        if link.on_hover_text("Show on the Channels tab").clicked() {
+                            linked_channel = Some(channel.channel_id.clone());
+                        }
+                        ui.monospace(truncate_id(&channel.counterparty_node_id, 8, 8))
+                            .on_hover_text(&channel.counterparty_node_id);
+                        ui.monospace(format_msat(channel.outbound_capacity_msat));
+                        ui.monospace(format_msat(channel.inbound_capacity_msat));
+                        match channel.unspendable_punishment_reserve {
+                            Some(reserve) => ui.monospace(format_sats(reserve)),
+                            None => ui.label("-"),
+                        };
+                        ui.end_row();
+                    }
+
+                    let totals = liquidity::channel_liquidity_totals(channels);
+                    ui.strong(format!("Total ({})", channels.len()));
+                    ui.label("");
+                    ui.strong(format_msat(totals.outbound_msat));
+                    ui.strong(format_msat(totals.inbound_msat));
+                    ui.strong(format_sats(totals.reserve_sats));
+                    ui.end_row();
+                });
+        });
+        ui.label(
+            egui::RichText::new(
+                "The reserve is the part of our balance the counterparty requires us to keep in \
+                 the channel; it isn't included in the outbound amount.",
+            )
+            .small()
+            .color(egui::Color32::GRAY),
+        );
+
+        if let Some(channel_id) = linked_channel {
+            app.show_channel(channel_id);
         }
-        BalanceType::CounterpartyRevokedOutputClaimable(b) => {
-            ui.label("Type: Counterparty Revoked Output");
-            ui.label(format!("Channel: {}", crate::ui::truncate_id(&b.channel_id, 8, 8)));
-            ui.label(format!("Amount: {} sats", format_sats(b.amount_satoshis)));
+    });
+}
+
+/// Expandable breakdown of why the spendable on-chain balance is less than the total.
+fn render_spendable_shortfall(ui: &mut Ui, balances: &GetBalancesResponse) {
+    let items = spendable::spendable_shortfall(balances);
+    if items.is_empty() {
+        return;
+    }
+    let difference: u64 = items.iter().map(|item| item.amount_sats).sum();
+    egui::CollapsingHeader::new(format!(
+        "Why is spendable {} less than total?",
+        format_sats(difference)
+    ))
+    .id_salt("spendable_shortfall")
+    .show(ui, |ui| {
+        egui::Grid::new("spendable_shortfall_grid")
+            .num_columns(3)
+            .spacing([10.0, 5.0])
+            .show(ui, |ui| {
+                for item in &items {
+                    ui.label(item.kind.label());
+                    ui.monospace(format_sats(item.amount_sats));
+                    ui.label(egui::RichText::new(item.kind.explanation()).small());
+                    ui.end_row();
+                }
+                ui.strong("Difference");
+                ui.monospace(format_sats(difference));
+                ui.end_row();
+            });
+    });
+}
+
+/// A card for each liquidity alert raised when channels were last fetched, with ways to fix it.
+fn render_liquidity_alerts(ui: &mut Ui, app: &mut LdkServerApp) {
+    for alert in app.state.session.liquidity_alerts.clone() {
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            let title = egui::RichText::new(format!("⚠ {}", alert.title())).strong();
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), title);
+            let verb = match alert.direction {
+                LiquidityDirection::Outbound => "send",
+                LiquidityDirection::Inbound => "receive",
+            };
+            ui.label(format!(
+                "Usable channels can {} {}, below the alert threshold of {}. {}",
+                verb,
+                format_sats(alert.available_sats),
+                format_sats(alert.threshold_sats),
+                alert.consequence()
+            ));
+            for suggestion in alert.suggestions() {
+                ui.label(format!("• {}", suggestion));
+            }
+            let is_outbound = alert.direction == LiquidityDirection::Outbound;
+            if is_outbound && ui.button("Open Channel").clicked() {
+                app.state.active_tab = ActiveTab::Channels;
+                app.state.show_open_channel_dialog = true;
+            }
+        });
+        ui.add_space(10.0);
+    }
+}
+
+/// One claimable lightning balance of a [`LightningBalanceCategory`].
+struct LightningBalanceEntry<'a> {
+    channel_id: &'a str,
+    counterparty_node_id: &'a str,
+    amount_sats: u64,
+    /// Heights and fees specific to the category
+    details: Option<String>,
+}
+
+/// Lightning balances of one kind, e.g. all balances claimable on channel close.
+struct LightningBalanceCategory<'a> {
+    label: &'static str,
+    description: &'static str,
+    entries: Vec<LightningBalanceEntry<'a>>,
+}
+
+/// Group `balances` by kind, in the order LDK lists the kinds, leaving out zero amounts and
+/// kinds without any balance.
+fn lightning_balance_categories(
+    balances: &[LightningBalance],
+) -> Vec<LightningBalanceCategory<'_>> {
+    let mut categories = [
+        ("Claimable on channel close", "Our balance in open channels."),
+        (
+            "Awaiting confirmations",
+            "Claimed after a channel close, spendable once the claim confirms.",
+        ),
+        (
+            "Contentious",
+            "HTLCs we can claim with the preimage before the counterparty can claim them back.",
+        ),
+        (
+            "Maybe claimable after timeout",
+            "HTLCs we offered, claimable back once they time out unless the preimage is revealed.",
+        ),
+        (
+            "Maybe claimable with preimage",
+            "HTLCs we received, claimable only if we learn the preimage before they expire.",
+        ),
+        (
+            "Revoked counterparty outputs",
+            "Outputs of a revoked commitment the counterparty broadcast, claimable as a penalty.",
+        ),
+    ]
+    .map(|(label, description)| {
+        LightningBalanceCategory { label, description, entries: Vec::new() }
+    });
+
+    for balance in balances {
+        let (index, channel_id, counterparty_node_id, amount_sats, details) =
+            match balance.balance_type.as_ref() {
+                Some(BalanceType::ClaimableOnChannelClose(b)) => {
+                    let fee = b.transaction_fee_satoshis;
+                    let details = (fee > 0).then(|| format!("closing fee {}", format_sats(fee)));
+                    (0, &b.channel_id, &b.counterparty_node_id, b.amount_satoshis, details)
+                }
+                Some(BalanceType::ClaimableAwaitingConfirmations(b)) => {
+                    let details = format!("spendable at height {}", b.confirmation_height);
+                    (1, &b.channel_id, &b.counterparty_node_id, b.amount_satoshis, Some(details))
+                }
+                Some(BalanceType::ContentiousClaimable(b)) => {
+                    let details = format!("times out at height {}", b.timeout_height);
+                    (2, &b.channel_id, &b.counterparty_node_id, b.amount_satoshis, Some(details))
+                }
+                Some(BalanceType::MaybeTimeoutClaimableHtlc(b)) => {
+                    let kind = if b.outbound_payment { "payment" } else { "forward" };
+                    let details = format!("{}, claimable at height {}", kind, b.claimable_height);
+                    (3, &b.channel_id, &b.counterparty_node_id, b.amount_satoshis, Some(details))
+                }
+                Some(BalanceType::MaybePreimageClaimableHtlc(b)) => {
+                    let details = format!("expires at height {}", b.expiry_height);
+                    (4, &b.channel_id, &b.counterparty_node_id, b.amount_satoshis, Some(details))
+                }
+                Some(BalanceType::CounterpartyRevokedOutputClaimable(b)) => {
+                    (5, &b.channel_id, &b.counterparty_node_id, b.amount_satoshis, None)
+                }
+                None => continue,
+            };
+        if amount_sats == 0 {
+            continue;
         }
+        categories[index].entries.push(LightningBalanceEntry {
+            channel_id,
+            counterparty_node_id,
+            amount_sats,
+            details,
+        });
     }
+
+    categories.into_iter().filter(|category| !category.entries.is_empty()).collect()
 }
 
+/// One pending sweep, with its progress towards being irrevocably confirmed when the node's
+/// tip height is known.
 fn render_pending_sweep(
     ui: &mut Ui,
     balance: &ldk_server_client::ldk_server_protos::types::pending_sweep_balance::BalanceType,
+    network: Network, tip_height: Option<u32>,
 ) {
     use ldk_server_client::ldk_server_protos::types::pending_sweep_balance::BalanceType;
 
@@ -163,23 +431,46 @@ fn render_pending_sweep(
             if let Some(ch) = &b.channel_id {
                 ui.label(format!("Channel: {}", crate::ui::truncate_id(ch, 8, 8)));
             }
-            ui.label(format!("Amount: {} sats", format_sats(b.amount_satoshis)));
+            ui.label(format!("Amount: {}", format_sats(b.amount_satoshis)));
         }
         BalanceType::BroadcastAwaitingConfirmation(b) => {
             ui.label("Type: Broadcast Awaiting Confirmation");
             if let Some(ch) = &b.channel_id {
                 ui.label(format!("Channel: {}", crate::ui::truncate_id(ch, 8, 8)));
             }
-            ui.label(format!("Amount: {} sats", format_sats(b.amount_satoshis)));
-            ui.label(format!("TXID: {}", crate::ui::truncate_id(&b.latest_spending_txid, 8, 8)));
+            ui.label(format!("Amount: {}", format_sats(b.amount_satoshis)));
+            ui.horizontal(|ui| {
+                ui.label("TXID:");
+                crate::ui::txid_link(ui, network, &b.latest_spending_txid, 8, 8);
+            });
+            ui.label(format!("Last broadcast at height: {}", b.latest_broadcast_height));
         }
         BalanceType::AwaitingThresholdConfirmations(b) => {
             ui.label("Type: Awaiting Threshold Confirmations");
             if let Some(ch) = &b.channel_id {
                 ui.label(format!("Channel: {}", crate::ui::truncate_id(ch, 8, 8)));
             }
-            ui.label(format!("Amount: {} sats", format_sats(b.amount_satoshis)));
+            ui.label(format!("Amount: {}", format_sats(b.amount_satoshis)));
+            ui.horizontal(|ui| {
+                ui.label("TXID:");
+                crate::ui::txid_link(ui, network, &b.latest_spending_txid, 8, 8);
+            });
             ui.label(format!("Confirmed at height: {}", b.confirmation_height));
         }
     }
+
+    let Some(tip_height) = tip_height else {
+        return;
+    };
+    let progress = sweeps::sweep_progress(balance, tip_height);
+    match progress {
+        SweepProgress::Confirming { .. } => {
+            let bar = egui::ProgressBar::new(progress.fraction()).text(progress.text());
+            ui.add(bar.desired_width(350.0));
+        }
+        SweepProgress::Broadcast { .. } => {
+            ui.label(progress.text());
+        }
+        SweepProgress::PendingBroadcast => {}
+    }
 }
@@ -1,25 +1,28 @@
 use egui::{Context, ScrollArea, Ui};
 
 use crate::app::LdkServerApp;
-use crate::state::ConnectionStatus;
-use crate::ui::{format_msat, format_sats, truncate_id};
+use crate::state::{ConnectionStatus, TaskKind};
+use crate::ui::{
+    copy_button, data_age, format_msat, format_sats, refresh_button, truncate_id, txid_link,
+};
 
 pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
     ui.heading("Channels");
     ui.add_space(10.0);
 
-    if !matches!(app.state.connection_status, ConnectionStatus::Connected) {
+    if !matches!(app.state.session.connection_status, ConnectionStatus::Connected) {
         ui.label("Connect to a server to view channels.");
         return;
     }
 
     ui.horizontal(|ui| {
-        if app.state.tasks.channels.is_some() {
+        if app.state.session.tasks.is_pending(TaskKind::Channels) {
             ui.spinner();
             ui.label("Loading...");
-        } else if ui.button("Refresh").clicked() {
+        } else if refresh_button(ui, &app.state.session.refresh, TaskKind::Channels) {
             app.fetch_channels();
         }
+        data_age(ui, app.state.session.channels.as_ref(), app.state.data_age_thresholds);
 
         ui.separator();
 
@@ -34,7 +37,8 @@ pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
 
     ui.add_space(10.0);
 
-    if let Some(channels_response) = &app.state.channels {
+    let network = app.state.session.chain_network().unwrap_or_default();
+    if let Some(channels_response) = app.state.session.channels.as_ref().map(|c| &c.data) {
         let channels = &channels_response.channels;
         if channels.is_empty() {
             ui.label("No channels found.");
@@ -60,38 +64,43 @@ pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
                         ui.end_row();
 
                         for ch in channels {
-                            // Channel ID
+                            // Channel ID, highlighted when linked to from another tab
                             ui.horizontal(|ui| {
-                                ui.monospace(truncate_id(&ch.channel_id, 5, 4));
-                                if ui.small_button("Copy").clicked() {
-                                    ui.output_mut(|o| o.copied_text = ch.channel_id.clone());
+                                let id = truncate_id(&ch.channel_id, 5, 4);
+                                let highlighted = app.state.highlighted_channel.as_ref();
+                                if highlighted == Some(&ch.channel_id) {
+                                    let text = egui::RichText::new(id)
+                                        .monospace()
+                                        .background_color(ui.visuals().selection.bg_fill);
+                                    let response = ui.label(text);
+                                    if app.state.scroll_to_highlighted_channel {
+                                        response.scroll_to_me(Some(egui::Align::Center));
+                                        app.state.scroll_to_highlighted_channel = false;
+                                    }
+                                } else {
+                                    ui.monospace(id);
                                 }
+                                copy_button(ui, "Copy", || ch.channel_id.clone());
                             });
 
                             // Counterparty
                             ui.horizontal(|ui| {
                                 ui.monospace(truncate_id(&ch.counterparty_node_id, 5, 4));
-                                if ui.small_button("Copy").clicked() {
-                                    ui.output_mut(|o| {
-                                        o.copied_text = ch.counterparty_node_id.clone()
-                                    });
-                                }
+                                copy_button(ui, "Copy", || ch.counterparty_node_id.clone());
                             });
 
                             // Funding Txid
                             ui.horizontal(|ui| {
                                 if let Some(ref funding_txo) = ch.funding_txo {
-                                    ui.monospace(truncate_id(&funding_txo.txid, 5, 4));
-                                    if ui.small_button("Copy").clicked() {
-                                        ui.output_mut(|o| o.copied_text = funding_txo.txid.clone());
-                                    }
+                                    txid_link(ui, network, &funding_txo.txid, 5, 4);
+                                    copy_button(ui, "Copy", || funding_txo.txid.clone());
                                 } else {
                                     ui.label("-");
                                 }
                             });
 
                             // Capacity
-                            ui.label(format!("{} sats", format_sats(ch.channel_value_sats)));
+                            ui.label(format_sats(ch.channel_value_sats));
 
                             // Outbound capacity
                             ui.label(format_msat(ch.outbound_capacity_msat));
@@ -190,7 +199,7 @@ fn render_connect_peer_dialog(ctx: &Context, app: &mut LdkServerApp) {
             ui.add_space(10.0);
 
             ui.horizontal(|ui| {
-                let is_pending = app.state.tasks.connect_peer.is_some();
+                let is_pending = app.state.session.tasks.is_pending(TaskKind::ConnectPeer);
                 if is_pending {
                     ui.spinner();
                 } else if ui.button("Connect").clicked() {
@@ -262,7 +271,7 @@ fn render_open_channel_dialog(ctx: &Context, app: &mut LdkServerApp) {
             ui.add_space(10.0);
 
             ui.horizontal(|ui| {
-                let is_pending = app.state.tasks.open_channel.is_some();
+                let is_pending = app.state.session.tasks.is_pending(TaskKind::OpenChannel);
                 if is_pending {
                     ui.spinner();
                 } else {
@@ -309,8 +318,9 @@ fn render_close_channel_dialog(ctx: &Context, app: &mut LdkServerApp) {
             ui.add_space(10.0);
 
             ui.horizontal(|ui| {
-                let is_close_pending = app.state.tasks.close_channel.is_some();
-                let is_force_close_pending = app.state.tasks.force_close_channel.is_some();
+                let is_close_pending = app.state.session.tasks.is_pending(TaskKind::CloseChannel);
+                let is_force_close_pending =
+                    app.state.session.tasks.is_pending(TaskKind::ForceCloseChannel);
 
                 if is_close_pending || is_force_close_pending {
                     ui.spinner();
@@ -364,7 +374,7 @@ fn render_splice_in_dialog(ctx: &Context, app: &mut LdkServerApp) {
             ui.add_space(10.0);
 
             ui.horizontal(|ui| {
-                let is_pending = app.state.tasks.splice_in.is_some();
+                let is_pending = app.state.session.tasks.is_pending(TaskKind::SpliceIn);
                 if is_pending {
                     ui.spinner();
                 } else if ui.button("Splice In").clicked() {
@@ -416,7 +426,7 @@ fn render_splice_out_dialog(ctx: &Context, app: &mut LdkServerApp) {
             ui.add_space(10.0);
 
             ui.horizontal(|ui| {
-                let is_pending = app.state.tasks.splice_out.is_some();
+                let is_pending = app.state.session.tasks.is_pending(TaskKind::SpliceOut);
                 if is_pending {
                     ui.spinner();
                 } else if ui.button("Splice Out").clicked() {
@@ -445,12 +455,22 @@ fn render_update_config_dialog(ctx: &Context, app: &mut LdkServerApp) {
                 .num_columns(2)
                 .spacing([10.0, 5.0])
                 .show(ui, |ui| {
+                    ui.label("Channels:");
+                    ui.checkbox(&mut form.all_channels, "Apply to all channels");
+                    ui.end_row();
+
                     ui.label("Channel ID:");
-                    ui.text_edit_singleline(&mut form.user_channel_id);
+                    ui.add_enabled(
+                        !form.all_channels,
+                        egui::TextEdit::singleline(&mut form.user_channel_id),
+                    );
                     ui.end_row();
 
                     ui.label("Counterparty:");
-                    ui.text_edit_singleline(&mut form.counterparty_node_id);
+                    ui.add_enabled(
+                        !form.all_channels,
+                        egui::TextEdit::singleline(&mut form.counterparty_node_id),
+                    );
                     ui.end_row();
 
                     ui.label("Fee Proportional (millionths):");
@@ -469,8 +489,18 @@ fn render_update_config_dialog(ctx: &Context, app: &mut LdkServerApp) {
             ui.add_space(10.0);
 
             ui.horizontal(|ui| {
-                let is_pending = app.state.tasks.update_channel_config.is_some();
-                if is_pending {
+                let tasks = &app.state.session.tasks;
+                let progress =
+                    app.state.session.task_progress.get(&TaskKind::UpdateAllChannelConfigs);
+                if let Some(progress) = progress {
+                    let text =
+                        format!("{} ({}/{})", progress.message, progress.done, progress.total);
+                    ui.add(
+                        egui::ProgressBar::new(progress.fraction()).text(text).desired_width(250.0),
+                    );
+                } else if tasks.is_pending(TaskKind::UpdateChannelConfig)
+                    || tasks.is_pending(TaskKind::UpdateAllChannelConfigs)
+                {
                     ui.spinner();
                 } else if ui.button("Update Config").clicked() {
                     app.update_channel_config();
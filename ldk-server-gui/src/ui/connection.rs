@@ -1,40 +1,589 @@
 use egui::Ui;
 
+use crate::amount::AmountUnit;
 use crate::app::LdkServerApp;
 use crate::config;
 #[cfg(not(target_arch = "wasm32"))]
-use crate::config::ChainSourceType;
-use crate::state::{AppState, ConnectionStatus, StatusMessage};
+use crate::config::{ChainSourceType, SectionUpdate};
+#[cfg(target_arch = "wasm32")]
+use crate::cors;
+#[cfg(target_arch = "wasm32")]
+use crate::errors;
+use crate::fiat::FIAT_CURRENCIES;
+use crate::health::{self, CheckStatus};
+use crate::network::Network;
 #[cfg(not(target_arch = "wasm32"))]
-use crate::state::ChainSourceForm;
+use crate::secrets;
+use crate::state::{
+    AppState, ConnectionField, ConnectionStatus, NodeSession, StatusMessage, TaskKind,
+    REFRESHED_RESOURCES, TASK_TIMINGS_KEPT,
+};
+use crate::task::RequestLimiter;
+use crate::ui::{
+    copy_button, format_age, layout, secret_field, unix_timestamp_millis, unix_timestamp_now,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::state::{
+    ChainSourceForm, ConnectionProfile, Lsps2ServiceForm, NewConfigStep, NewConfigWizard,
+    NodeSettingsForm, ShutdownState, TlsCertSource,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ui::copy_to_clipboard;
+
+/// Dropdown listing every node session, with a status dot per node and entries to add a new
+/// node or close the current one.
+pub fn render_node_switcher(ui: &mut Ui, app: &mut LdkServerApp) {
+    let mut switch_to = None;
+    let mut add = false;
+    let mut remove = false;
+    let active = app.state.active_session;
+    let count = app.state.sessions.len();
+
+    egui::ComboBox::from_id_salt("node_switcher")
+        .selected_text(app.state.session.label())
+        .show_ui(ui, |ui| {
+            for index in 0..count {
+                let session = if index == active {
+                    &app.state.session
+                } else {
+                    &app.state.sessions[index]
+                };
+                ui.horizontal(|ui| {
+                    ui.colored_label(session_status_color(session), "●");
+                    if ui.selectable_label(index == active, session.label()).clicked() {
+                        switch_to = Some(index);
+                    }
+                });
+            }
+            ui.separator();
+            if ui.button("Add node").clicked() {
+                add = true;
+            }
+            if count > 1 && ui.button("Close this node").clicked() {
+                remove = true;
+            }
+        });
+
+    if let Some(index) = switch_to {
+        app.switch_session(index);
+    } else if add {
+        app.add_session();
+    } else if remove {
+        app.remove_active_session();
+    }
+}
+
+fn session_status_color(session: &NodeSession) -> egui::Color32 {
+    match &session.connection_status {
+        ConnectionStatus::Connected if session.connection_health.consecutive_failures == 0 => {
+            egui::Color32::GREEN
+        }
+        ConnectionStatus::Connected => egui::Color32::YELLOW,
+        ConnectionStatus::Connecting
+        | ConnectionStatus::AuthRequired
+        | ConnectionStatus::Reconnecting { .. } => egui::Color32::from_rgb(255, 165, 0),
+        ConnectionStatus::Error(_) => egui::Color32::RED,
+        ConnectionStatus::Disconnected => egui::Color32::GRAY,
+    }
+}
 
 pub fn render_status(ui: &mut Ui, state: &AppState) {
-    match &state.connection_status {
+    match &state.session.connection_status {
         ConnectionStatus::Disconnected => {
             ui.colored_label(egui::Color32::GRAY, "Disconnected");
         }
+        ConnectionStatus::Connecting => {
+            ui.spinner();
+            ui.colored_label(egui::Color32::GRAY, "Connecting…");
+        }
         ConnectionStatus::Connected => {
-            ui.colored_label(egui::Color32::GREEN, "Connected");
+            let health = &state.session.connection_health;
+            let response = if health.consecutive_failures > 0 {
+                ui.colored_label(egui::Color32::YELLOW, "Connected (not responding)")
+            } else if let Some(latency_ms) = health.latency_ms {
+                ui.colored_label(egui::Color32::GREEN, format!("Connected ({} ms)", latency_ms))
+            } else {
+                ui.colored_label(egui::Color32::GREEN, "Connected")
+            };
+            let tooltip = match health.last_success_at {
+                Some(t) => format!(
+                    "Last successful check: {}",
+                    format_age(unix_timestamp_now().saturating_sub(t))
+                ),
+                None => "Waiting for the first health check".to_string(),
+            };
+            response.on_hover_text(tooltip);
+        }
+        ConnectionStatus::AuthRequired => {
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "Authentication required");
+        }
+        ConnectionStatus::Reconnecting { attempt, next_attempt_at } => {
+            let wait = next_attempt_at.saturating_sub(unix_timestamp_now());
+            let text = if wait == 0 {
+                format!("Reconnecting… (attempt {})", attempt)
+            } else {
+                format!("Reconnecting… (attempt {}, retry in {}s)", attempt, wait)
+            };
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), text);
         }
         ConnectionStatus::Error(e) => {
             ui.colored_label(egui::Color32::RED, format!("Error: {}", e));
         }
     }
+
+    let proxy = state.session.proxy.trim();
+    if !proxy.is_empty() && state.session.client.is_some() {
+        ui.label(egui::RichText::new(format!("via proxy {}", proxy)).small())
+            .on_hover_text("Requests are routed through this SOCKS5 proxy");
+    }
+}
+
+/// Spinner with the number of requests running and waiting, while there are any.
+pub fn render_request_activity(ui: &mut Ui, limiter: &RequestLimiter) {
+    let (in_flight, queued) = limiter.counts();
+    if in_flight == 0 && queued == 0 {
+        return;
+    }
+    ui.separator();
+    ui.spinner();
+    let text = if queued == 0 {
+        format!("{} request(s)", in_flight)
+    } else {
+        format!("{} request(s), {} queued", in_flight, queued)
+    };
+    ui.label(egui::RichText::new(text).small()).on_hover_text(format!(
+        "{} running, {} waiting for a free slot",
+        in_flight, queued
+    ));
+}
+
+/// Dropdown of recently used server URLs; picking one only replaces the URL.
+fn render_recent_server_urls(ui: &mut Ui, app: &mut LdkServerApp) {
+    if app.state.recent_server_urls.is_empty() {
+        return;
+    }
+
+    let mut clear = false;
+    egui::ComboBox::from_id_salt("recent_server_urls")
+        .selected_text("Recent")
+        .width(80.0)
+        .show_ui(ui, |ui| {
+            for url in &app.state.recent_server_urls {
+                if ui.selectable_label(app.state.session.server_url.trim() == url, url).clicked() {
+                    app.state.session.server_url = url.clone();
+                    app.state.connection_field_errors.retain(|(f, _)| *f != ConnectionField::ServerUrl);
+                }
+            }
+            ui.separator();
+            if ui.button("Clear history").clicked() {
+                clear = true;
+            }
+        });
+
+    if clear {
+        app.state.recent_server_urls.clear();
+        app.settings_changed();
+    }
+}
+
+/// Returns true if Enter was pressed in the single-line field.
+fn submitted(ui: &Ui, response: &egui::Response) -> bool {
+    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))
+}
+
+/// Apply focus requests and clear stale errors for `field`, then outline it in red with the error
+/// underneath if it failed validation. Returns true if an error is shown.
+fn show_field_error(
+    ui: &mut Ui,
+    state: &mut AppState,
+    field: ConnectionField,
+    response: &egui::Response,
+) -> bool {
+    if state.focus_connection_field == Some(field) {
+        response.request_focus();
+        state.focus_connection_field = None;
+    }
+    if response.changed() {
+        state.connection_field_errors.retain(|(f, _)| *f != field);
+        state.env_overrides.retain(|f| *f != field);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if state.env_overrides.contains(&field) {
+        if let Some(var) = crate::cli::env_var_for(field) {
+            ui.colored_label(egui::Color32::GRAY, format!("Set from {}", var));
+        }
+    }
+
+    let Some((_, error)) = state.connection_field_errors.iter().find(|(f, _)| *f == field) else {
+        return false;
+    };
+    ui.painter().rect_stroke(
+        response.rect.expand(1.0),
+        2.0,
+        egui::Stroke::new(1.5, egui::Color32::RED),
+    );
+    ui.colored_label(egui::Color32::RED, error);
+    true
+}
+
+fn render_disconnect_dialog(ctx: &egui::Context, app: &mut LdkServerApp) {
+    if !app.state.show_disconnect_dialog {
+        return;
+    }
+
+    let mut disconnect = false;
+    let mut close = false;
+
+    egui::Window::new("Disconnect")
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label("These operations are still in progress:");
+            for name in app.state.session.tasks.pending_operations() {
+                ui.label(format!("• {}", name));
+            }
+            ui.add_space(5.0);
+            ui.label(
+                egui::RichText::new(
+                    "Disconnecting stops waiting for them. Requests already received by the \
+                     server may still complete there.",
+                )
+                .small()
+                .color(egui::Color32::GRAY),
+            );
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button("Cancel operations and disconnect").clicked() {
+                    disconnect = true;
+                }
+                if ui.button("Stay connected").clicked() {
+                    close = true;
+                }
+            });
+        });
+
+    if disconnect {
+        app.state.show_disconnect_dialog = false;
+        app.disconnect();
+    } else if close || app.state.session.connection_status != ConnectionStatus::Connected {
+        app.state.show_disconnect_dialog = false;
+    }
+}
+
+/// Toolbar button fetching everything shown about the connected node at once.
+pub fn render_sync_button(ui: &mut Ui, app: &mut LdkServerApp) {
+    if app.state.session.connection_status != ConnectionStatus::Connected {
+        return;
+    }
+    if app.state.session.tasks.is_pending(TaskKind::SyncAll) {
+        ui.spinner();
+        ui.label("Syncing…");
+        return;
+    }
+    let refresh = &app.state.session.refresh;
+    let cooldown = refresh.cooldown_left(TaskKind::SyncAll, unix_timestamp_millis());
+    if let Some(left) = cooldown {
+        ui.ctx().request_repaint_after(left);
+    }
+    if ui
+        .add_enabled(cooldown.is_none(), egui::Button::new("Sync all"))
+        .on_hover_text("Fetch node info, balances, channels and payments")
+        .clicked()
+    {
+        app.sync_all();
+    }
+}
+
+/// Overall health of the connected node from its node info, with the failed checks on hover.
+pub fn render_health_status(ui: &mut Ui, state: &AppState) {
+    if state.session.connection_status != ConnectionStatus::Connected {
+        return;
+    }
+    let Some(info) = &state.session.node_info else {
+        return;
+    };
+    let results = health::health_checks(&info.data, unix_timestamp_now());
+    if health::is_healthy(&results) {
+        ui.colored_label(egui::Color32::GREEN, "● Healthy")
+            .on_hover_text("All node health checks pass; see Node Info");
+    } else {
+        let failed: Vec<&str> = results
+            .iter()
+            .filter(|r| matches!(r.status, CheckStatus::Warning | CheckStatus::Failing))
+            .map(|r| r.check.label())
+            .collect();
+        ui.colored_label(egui::Color32::YELLOW, "● Attention needed")
+            .on_hover_text(format!("Failing checks: {}", failed.join(", ")));
+    }
+}
+
+/// Switch between displaying amounts in sats and BTC. Only changes how amounts are shown.
+pub fn render_display_unit_toggle(ui: &mut Ui, app: &mut LdkServerApp) {
+    let mut unit = app.state.display_unit;
+    // Laid out right to left
+    for option in AmountUnit::ALL.into_iter().rev() {
+        ui.selectable_value(&mut unit, option, option.label());
+    }
+    if unit != app.state.display_unit {
+        app.set_display_unit(unit);
+    }
+}
+
+/// Ask whether to close the window while operations that change a node are running, then list
+/// them until they finish or the grace period runs out.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn render_shutdown_dialog(ctx: &egui::Context, app: &mut LdkServerApp) {
+    let deadline_ms = match app.state.shutdown {
+        ShutdownState::Confirming => None,
+        ShutdownState::Draining { deadline_ms } => Some(deadline_ms),
+        ShutdownState::Running | ShutdownState::Closing => return,
+    };
+
+    let mut wait = false;
+    let mut close_now = false;
+    let mut keep_open = false;
+
+    egui::Window::new("Close")
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(match deadline_ms {
+                None => "These operations are still in progress:",
+                Some(_) => "Waiting for these operations to finish:",
+            });
+            for name in app.running_mutations() {
+                ui.label(format!("• {}", name));
+            }
+            ui.add_space(5.0);
+            match deadline_ms {
+                None => {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Closing waits up to {} s for them, so you know whether they went \
+                             through. Requests already received by the server may still \
+                             complete there.",
+                            crate::app::SHUTDOWN_GRACE_PERIOD_SECS
+                        ))
+                        .small()
+                        .color(egui::Color32::GRAY),
+                    );
+                }
+                Some(deadline_ms) => {
+                    let left_ms = deadline_ms.saturating_sub(unix_timestamp_millis());
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(format!("Closing in at most {} s", left_ms.div_ceil(1000)));
+                    });
+                }
+            }
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if deadline_ms.is_none() {
+                    if ui.button("Wait and close").clicked() {
+                        wait = true;
+                    }
+                } else if ui.button("Close now").clicked() {
+                    close_now = true;
+                }
+                if ui.button("Keep open").clicked() {
+                    keep_open = true;
+                }
+            });
+        });
+
+    if wait {
+        app.begin_shutdown();
+    } else if close_now {
+        app.close_window(ctx);
+    } else if keep_open {
+        app.state.shutdown = ShutdownState::Running;
+    }
+}
+
+/// Ask for a new API key after the server started rejecting the current one.
+pub fn render_reauth_dialog(ctx: &egui::Context, app: &mut LdkServerApp) {
+    if app.state.session.connection_status != ConnectionStatus::AuthRequired
+        && !app.state.reauthenticating
+    {
+        return;
+    }
+
+    let mut submit = false;
+    let mut disconnect = false;
+
+    egui::Window::new("Authentication Required")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label("The server rejected the API key. It is regenerated when the server's storage is wiped.");
+            ui.add_space(5.0);
+
+            egui::Grid::new("reauth_grid").num_columns(2).spacing([10.0, 5.0]).show(ui, |ui| {
+                ui.label("Server URL:");
+                ui.monospace(app.state.session.server_url.trim());
+                ui.end_row();
+
+                ui.label("API Key:");
+                let response = secret_field(ui, "reauth_api_key", &mut app.state.reauth_api_key);
+                submit |= submitted(ui, &response);
+                ui.end_row();
+            });
+
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui.button("Re-read from api_key file").clicked() {
+                match config::reread_api_key(
+                    app.state.session.config_file_path.as_deref(),
+                    &app.state.session.tls_cert_path,
+                    app.state.session.network().unwrap_or_default(),
+                ) {
+                    Ok(api_key) => {
+                        app.state.reauth_api_key = api_key;
+                        app.state.reauth_error = None;
+                    }
+                    Err(e) => app.state.reauth_error = Some(e),
+                }
+            }
+
+            if let Some(e) = &app.state.reauth_error {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if app.state.reauthenticating {
+                    ui.spinner();
+                    ui.label("Checking...");
+                } else if ui.button("Reconnect").clicked() {
+                    submit = true;
+                }
+                if ui.button("Disconnect").clicked() {
+                    disconnect = true;
+                }
+            });
+        });
+
+    if disconnect {
+        app.state.reauthenticating = false;
+        app.disconnect();
+    } else if submit && !app.state.reauthenticating {
+        app.reauthenticate();
+    }
+}
+
+/// Banner shown while the connected node is on a different network than the loaded config.
+pub fn render_network_mismatch(ui: &mut Ui, app: &mut LdkServerApp) {
+    let Some(detected) = app.network_mismatch() else {
+        return;
+    };
+    ui.horizontal(|ui| {
+        ui.colored_label(
+            egui::Color32::from_rgb(255, 165, 0),
+            format!(
+                "⚠ The node is on {} but the configured network is {}. Address checks, fees and \
+                 explorer links follow the node until the config is fixed.",
+                detected,
+                app.state.session.network().unwrap_or_default(),
+            ),
+        );
+        if ui.button(format!("Trust the node, switch to {}", detected)).clicked() {
+            app.trust_node_network();
+        }
+    });
+}
+
+/// Warning that the node's wallets have not synced for a while, usually because its chain source
+/// is unreachable.
+pub fn render_stale_sync_warning(ui: &mut Ui, app: &LdkServerApp) {
+    let now = unix_timestamp_now();
+    let syncs: Vec<String> = app
+        .state
+        .session
+        .stale_wallet_syncs
+        .iter()
+        .map(|(wallet, synced_at)| {
+            let age = format_age(now.saturating_sub(*synced_at));
+            format!("{} wallet last synced {}", wallet.label(), age)
+        })
+        .collect();
+    ui.colored_label(
+        egui::Color32::YELLOW,
+        format!(
+            "⚠ Node wallet sync is stale: {}. The chain source may be down.",
+            syncs.join(", ")
+        ),
+    );
+}
+
+pub fn render_block_lag_warning(ui: &mut Ui, app: &LdkServerApp, blocks: u32) {
+    let public_height = app.state.session.public_tip_height.unwrap_or_default();
+    ui.colored_label(
+        egui::Color32::YELLOW,
+        format!(
+            "⚠ Node is {} blocks behind the public chain tip at height {}. Payments and channel \
+             operations may fail until it catches up.",
+            blocks, public_height
+        ),
+    );
 }
 
 pub fn render_settings(ui: &mut Ui, app: &mut LdkServerApp) {
+    render_disconnect_dialog(&ui.ctx().clone(), app);
+    #[cfg(not(target_arch = "wasm32"))]
+    render_cert_pin_mismatch_dialog(&ui.ctx().clone(), app);
+
     ui.group(|ui| {
         ui.heading("Connection Settings");
         ui.add_space(5.0);
 
-        egui::Grid::new("connection_grid").num_columns(2).spacing([10.0, 5.0]).show(ui, |ui| {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            render_profile_picker(ui, app);
+            ui.add_space(5.0);
+        }
+
+        let mut submit = false;
+        layout::form_grid(ui, "connection_grid", |ui| {
             ui.label("Server URL:");
-            ui.text_edit_singleline(&mut app.state.server_url);
+            ui.vertical(|ui| {
+                let response = ui
+                    .horizontal(|ui| {
+                        let response = ui.text_edit_singleline(&mut app.state.session.server_url);
+                        render_recent_server_urls(ui, app);
+                        response
+                    })
+                    .inner;
+                submit |= submitted(ui, &response);
+                show_field_error(ui, &mut app.state, ConnectionField::ServerUrl, &response);
+            });
             ui.end_row();
 
             ui.label("API Key:");
             ui.vertical(|ui| {
-                ui.text_edit_singleline(&mut app.state.api_key);
+                let response = secret_field(ui, "api_key", &mut app.state.session.api_key);
+                submit |= submitted(ui, &response);
+                show_field_error(ui, &mut app.state, ConnectionField::ApiKey, &response);
+                ui.horizontal(|ui| {
+                    if ui
+                        .checkbox(&mut app.state.remember_api_key, "Remember API key")
+                        .on_hover_text("Store the API key unencrypted in the app's local settings")
+                        .changed()
+                    {
+                        app.settings_changed();
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui
+                        .button("Locate API key…")
+                        .on_hover_text("Read the api_key file from the server's storage directory")
+                        .clicked()
+                    {
+                        if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                            locate_api_key(app, &dir);
+                        }
+                    }
+                });
                 ui.label(
                     egui::RichText::new("Auto-generated at <storage_dir>/<network>/api_key. Get hex: xxd -p <path>/api_key | tr -d '\\n'")
                         .small()
@@ -43,175 +592,1018 @@ pub fn render_settings(ui: &mut Ui, app: &mut LdkServerApp) {
             });
             ui.end_row();
 
-            // TLS cert path is only needed on native (browser handles TLS)
-            #[cfg(not(target_arch = "wasm32"))]
-            {
-                ui.label("TLS Cert Path:");
-                ui.horizontal(|ui| {
-                    ui.text_edit_singleline(&mut app.state.tls_cert_path);
-                    if ui.button("Browse...").clicked() {
-                        if let Some(path) = rfd::FileDialog::new()
-                            .add_filter("PEM files", &["pem"])
-                            .add_filter("All files", &["*"])
-                            .pick_file()
-                        {
-                            app.state.tls_cert_path = path.display().to_string();
-                        }
-                    }
-                });
+            // TLS cert path is only needed on native (browser handles TLS)
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.label("TLS Cert:");
+                ui.vertical(|ui| {
+                    let insecure_label = "Insecure HTTP (localhost / onion only)";
+                    if ui.checkbox(&mut app.state.session.insecure_http, insecure_label).changed() {
+                        app.state.connection_field_errors.clear();
+                        app.settings_changed();
+                    }
+                    if app.state.session.insecure_http {
+                        let url = app.state.session.server_url.trim();
+                        if config::is_onion_url(url) {
+                            ui.label(
+                                egui::RichText::new(
+                                    "TLS disabled: Tor encrypts the connection to the onion service.",
+                                )
+                                .small()
+                                .color(egui::Color32::GRAY),
+                            );
+                        } else {
+                            ui.colored_label(
+                                egui::Color32::RED,
+                                "⚠ TLS disabled: the API key and all requests are sent unencrypted.",
+                            );
+                        }
+                        if !url.is_empty() && !config::insecure_http_allowed(url) {
+                            ui.colored_label(
+                                egui::Color32::RED,
+                                "Only localhost / 127.0.0.1 and onion addresses may be used without TLS.",
+                            );
+                        }
+                        return;
+                    }
+                    ui.horizontal(|ui| {
+                        let source = &mut app.state.session.tls_cert_source;
+                        ui.radio_value(source, TlsCertSource::File, "File path");
+                        ui.radio_value(source, TlsCertSource::Pem, "Paste PEM");
+                    });
+                    match app.state.session.tls_cert_source {
+                        TlsCertSource::File => {
+                            let response = ui.horizontal(|ui| {
+                                let response =
+                                    ui.text_edit_singleline(&mut app.state.session.tls_cert_path);
+                                if ui.button("Browse...").clicked() {
+                                    if let Some(path) = rfd::FileDialog::new()
+                                        .add_filter("PEM files", &["pem"])
+                                        .add_filter("All files", &["*"])
+                                        .pick_file()
+                                    {
+                                        app.state.session.tls_cert_path =
+                                            path.display().to_string();
+                                        app.state
+                                            .connection_field_errors
+                                            .retain(|(f, _)| *f != ConnectionField::TlsCert);
+                                    }
+                                }
+                                response
+                            })
+                            .inner;
+                            submit |= submitted(ui, &response);
+                            show_field_error(ui, &mut app.state, ConnectionField::TlsCert, &response);
+                        }
+                        TlsCertSource::Pem => {
+                            let response = ui.add(
+                                egui::TextEdit::multiline(&mut app.state.session.tls_cert_pem)
+                                    .font(egui::TextStyle::Monospace)
+                                    .desired_rows(6)
+                                    .hint_text("-----BEGIN CERTIFICATE-----"),
+                            );
+                            let has_error =
+                                show_field_error(ui, &mut app.state, ConnectionField::TlsCert, &response);
+                            let pem = app.state.session.tls_cert_pem.trim();
+                            if !pem.is_empty() && !has_error {
+                                if let Err(e) = config::validate_pem_certificate(pem) {
+                                    ui.colored_label(egui::Color32::RED, e);
+                                }
+                            }
+                            ui.checkbox(&mut app.state.remember_tls_cert_pem, "Remember certificate");
+                        }
+                    }
+                    render_cert_fingerprint(ui, app);
+                });
+                ui.end_row();
+
+                ui.label("SOCKS5 Proxy:");
+                ui.vertical(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut app.state.session.proxy)
+                            .hint_text("127.0.0.1:9050"),
+                    );
+                    submit |= submitted(ui, &response);
+                    show_field_error(ui, &mut app.state, ConnectionField::Proxy, &response);
+                    ui.label(
+                        egui::RichText::new(
+                            "Optional. Use a local Tor daemon to reach .onion addresses.",
+                        )
+                        .small()
+                        .color(egui::Color32::GRAY),
+                    );
+                });
+                ui.end_row();
+            }
+
+            ui.label("Network:");
+            let current = app.state.session.network();
+            let mut selected = current;
+            egui::ComboBox::from_id_salt("connection_network")
+                .selected_text(selected.map_or("Not set", |n| n.as_str()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut selected, None, "Not set");
+                    for network in Network::ALL {
+                        ui.selectable_value(&mut selected, Some(network), network.as_str());
+                    }
+                })
+                .response
+                .on_hover_text("Used for explorer links, fee estimates and address checks");
+            if selected != current {
+                app.set_network(selected);
+            }
+            ui.end_row();
+
+            ui.label("Request timeout:");
+            if ui
+                .add(egui::DragValue::new(&mut app.state.request_timeout_secs).range(5..=600).suffix(" s"))
+                .on_hover_text("Requests taking longer than this are abandoned")
+                .changed()
+            {
+                app.settings_changed();
+            }
+            ui.end_row();
+
+            ui.label("Concurrent requests:");
+            if ui
+                .add(egui::DragValue::new(&mut app.state.max_concurrent_requests).range(1..=16))
+                .on_hover_text("Requests beyond this many wait for their turn")
+                .changed()
+            {
+                app.limiter.set_limit(app.state.max_concurrent_requests);
+                app.settings_changed();
+            }
+            ui.end_row();
+
+            ui.label("Sync warning after:");
+            let stale_sync_mins =
+                egui::DragValue::new(&mut app.state.stale_sync_mins).range(1..=1440).suffix(" min");
+            if ui
+                .add(stale_sync_mins)
+                .on_hover_text("Warn when the node's wallets have not synced for this long")
+                .changed()
+            {
+                app.check_wallet_syncs();
+                app.settings_changed();
+            }
+            ui.end_row();
+
+            ui.label("Block lag warning after:");
+            let max_block_lag = egui::DragValue::new(&mut app.state.max_block_lag).range(1..=1000);
+            if ui
+                .add(max_block_lag.suffix(" blocks"))
+                .on_hover_text(
+                    "Warn when the node trails the public chain tip by more blocks; the tip is \
+                     only fetched when Public chain tip is enabled under Background Refresh",
+                )
+                .changed()
+            {
+                app.check_block_lag();
+                app.settings_changed();
+            }
+            ui.end_row();
+
+            let thresholds = &mut app.state.liquidity_thresholds;
+            let liquidity_rows = [
+                ("Outbound alert below:", &mut thresholds.outbound_below_sats, "send"),
+                ("Inbound alert below:", &mut thresholds.inbound_below_sats, "receive"),
+            ];
+            let mut liquidity_changed = false;
+            for (label, threshold, direction) in liquidity_rows {
+                ui.label(label);
+                liquidity_changed |= ui
+                    .add(egui::DragValue::new(threshold).speed(1000).suffix(" sats"))
+                    .on_hover_text(format!(
+                        "Alert on the Balances tab when usable channels can {} less; 0 turns \
+                         the alert off",
+                        direction
+                    ))
+                    .changed();
+                ui.end_row();
+            }
+            if liquidity_changed {
+                app.check_liquidity(false);
+                app.settings_changed();
+            }
+
+            ui.label("Fiat values:");
+            let current = app.state.fiat_currency.clone();
+            let mut selected = current.clone();
+            egui::ComboBox::from_id_salt("fiat_currency")
+                .selected_text(selected.as_deref().map_or("Off".to_string(), str::to_uppercase))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut selected, None, "Off");
+                    for currency in FIAT_CURRENCIES {
+                        let value = Some(currency.to_string());
+                        ui.selectable_value(&mut selected, value, currency.to_uppercase());
+                    }
+                })
+                .response
+                .on_hover_text("Show approximate values of balances and payments from CoinGecko");
+            if selected != current {
+                app.set_fiat_currency(selected);
+            }
+            ui.end_row();
+        });
+
+        egui::CollapsingHeader::new("Background Refresh").default_open(false).show(ui, |ui| {
+            render_refresh_schedules(ui, app);
+        });
+
+        if submit
+            && !matches!(
+                app.state.session.connection_status,
+                ConnectionStatus::Connected | ConnectionStatus::Connecting
+            )
+        {
+            app.connect();
+        }
+
+        ui.add_space(10.0);
+
+        ui.horizontal(|ui| {
+            match app.state.session.connection_status {
+                ConnectionStatus::Connected | ConnectionStatus::AuthRequired => {
+                    if ui.button("Disconnect").clicked() {
+                        app.request_disconnect();
+                    }
+                }
+                ConnectionStatus::Connecting => {
+                    ui.spinner();
+                    if ui.button("Cancel").clicked() {
+                        app.cancel_connect();
+                    }
+                }
+                ConnectionStatus::Reconnecting { .. } => {
+                    if ui.button("Stop retrying").clicked() {
+                        app.stop_reconnecting();
+                    }
+                }
+                _ => {
+                    if ui.button("Connect").clicked() {
+                        app.connect();
+                    }
+                }
+            }
+
+            ui.separator();
+
+            let drop_hint = "Or drop a config file onto the window";
+
+            // Native: file dialog
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui.button("Load Config").on_hover_text(drop_hint).clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("TOML files", &["toml"])
+                    .add_filter("All files", &["*"])
+                    .pick_file()
+                {
+                    app.load_config_file(&path);
+                }
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui
+                .button("Create New Config…")
+                .on_hover_text("Generate a config file for a new node")
+                .clicked()
+            {
+                app.state.new_config_wizard = Some(NewConfigWizard::default());
+            }
+
+            // WASM: show paste dialog
+            #[cfg(target_arch = "wasm32")]
+            if ui.button("Load Config").on_hover_text(drop_hint).clicked() {
+                app.state.show_load_config_dialog = true;
+            }
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = app.state.session.config_file_path.clone() {
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(format!("Config: {}", path))
+                        .small()
+                        .color(egui::Color32::GRAY),
+                );
+                let hint = "The config with passwords and tokens replaced by <redacted>, safe to \
+                    share when asking for help";
+                if ui.small_button("Copy sanitized").on_hover_text(hint).clicked() {
+                    if let Some(sanitized) = sanitized_config(app, &path) {
+                        copy_to_clipboard(ui, sanitized);
+                        app.state.status_message =
+                            Some(StatusMessage::success("Sanitized config copied to clipboard"));
+                    }
+                }
+                if ui.small_button("Export sanitized…").on_hover_text(hint).clicked() {
+                    export_sanitized_config(app, &path);
+                }
+            });
+        }
+    });
+
+    // Chain Source Settings (collapsible) - only on native
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        ui.add_space(10.0);
+
+        egui::CollapsingHeader::new("Chain Source Settings")
+            .default_open(false)
+            .show(ui, |ui| {
+                render_chain_source_editor(ui, &mut app.state.forms.chain_source);
+
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    // Save Config button - saves to current file or prompts if none loaded
+                    let has_config = app.state.session.config_file_path.is_some();
+                    if ui.button("Save").clicked() {
+                        if let Some(path) = app.state.session.config_file_path.clone() {
+                            let chain_source = app.state.forms.chain_source.to_config();
+                            let updates = vec![SectionUpdate::ChainSource(chain_source)];
+                            app.preview_config_save(path, updates);
+                        } else {
+                            app.state.status_message =
+                                Some(StatusMessage::error("No config file loaded. Use 'Save As...'"));
+                        }
+                    }
+
+                    // Save As button - always shows file dialog
+                    if ui.button("Save As...").clicked() {
+                        let mut dialog = rfd::FileDialog::new()
+                            .add_filter("TOML files", &["toml"])
+                            .set_file_name("ldk-server-config.toml");
+
+                        // Set starting directory based on existing config or sensible default
+                        if let Some(existing_path) = &app.state.session.config_file_path {
+                            if let Some(parent) = std::path::Path::new(existing_path).parent() {
+                                dialog = dialog.set_directory(parent);
+                            }
+                        } else if let Ok(cwd) = std::env::current_dir() {
+                            let ldk_server_dir = cwd.join("ldk-server");
+                            if ldk_server_dir.exists() {
+                                dialog = dialog.set_directory(&ldk_server_dir);
+                            } else {
+                                dialog = dialog.set_directory(&cwd);
+                            }
+                        }
+
+                        if let Some(path) = dialog.save_file() {
+                            let chain_source = app.state.forms.chain_source.to_config();
+                            app.preview_config_save(
+                                path.display().to_string(),
+                                vec![SectionUpdate::ChainSource(chain_source)],
+                            );
+                        }
+                    }
+
+                    let checking = app.state.session.tasks.is_pending(TaskKind::ChainSourceCheck);
+                    let can_check =
+                        app.state.forms.chain_source.source_type != ChainSourceType::None;
+                    // Testing again during a check queues a check of the edited settings
+                    if ui
+                        .add_enabled(can_check, egui::Button::new("Test"))
+                        .on_hover_text("Check that the chain source answers with these settings")
+                        .clicked()
+                    {
+                        app.check_chain_source();
+                    }
+                    if checking {
+                        ui.spinner();
+                    }
+
+                    // Show current config file path
+                    if has_config {
+                        if let Some(path) = &app.state.session.config_file_path {
+                            ui.label(
+                                egui::RichText::new(format!("({})", path))
+                                    .small()
+                                    .color(egui::Color32::GRAY),
+                            );
+                        }
+                    }
+                });
+
+                match &app.state.chain_source_check_result {
+                    Some(Ok(message)) => {
+                        ui.colored_label(egui::Color32::GREEN, format!("✔ {}", message));
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(egui::Color32::RED, e);
+                    }
+                    None => {}
+                }
+
+                ui.add_space(5.0);
+                ui.label(
+                    egui::RichText::new("Note: Chain source changes require server restart")
+                        .small()
+                        .italics()
+                        .color(egui::Color32::GRAY),
+                );
+            });
+
+        egui::CollapsingHeader::new("Node Settings").default_open(false).show(ui, |ui| {
+            render_node_settings_editor(ui, &mut app.state.forms.node_settings);
+
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    let updates = app.state.forms.node_settings.section_updates();
+                    match app.state.session.config_file_path.clone() {
+                        Some(path) => app.preview_config_save(path, updates),
+                        None => {
+                            app.state.status_message =
+                                Some(StatusMessage::error("No config file loaded"));
+                        }
+                    }
+                }
+                if let Some(path) = &app.state.session.config_file_path {
+                    ui.label(
+                        egui::RichText::new(format!("({})", path))
+                            .small()
+                            .color(egui::Color32::GRAY),
+                    );
+                }
+            });
+
+            ui.add_space(5.0);
+            ui.label(
+                egui::RichText::new("Note: Node setting changes require server restart")
+                    .small()
+                    .italics()
+                    .color(egui::Color32::GRAY),
+            );
+        });
+
+        egui::CollapsingHeader::new("LSPS2 Service Settings").default_open(false).show(ui, |ui| {
+            render_lsps2_service_editor(ui, &mut app.state.forms.lsps2_service);
+
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    match (
+                        app.state.session.config_file_path.clone(),
+                        app.state.forms.lsps2_service.to_settings(),
+                    ) {
+                        (None, _) => {
+                            app.state.status_message =
+                                Some(StatusMessage::error("No config file loaded"));
+                        }
+                        (Some(_), Err(e)) => {
+                            app.state.status_message =
+                                Some(StatusMessage::error(format!("Failed to save: {}", e)));
+                        }
+                        (Some(path), Ok(settings)) => {
+                            let updates = vec![SectionUpdate::Lsps2Service(settings)];
+                            app.preview_config_save(path, updates)
+                        }
+                    }
+                }
+                if let Some(path) = &app.state.session.config_file_path {
+                    ui.label(
+                        egui::RichText::new(format!("({})", path))
+                            .small()
+                            .color(egui::Color32::GRAY),
+                    );
+                }
+            });
+
+            ui.add_space(5.0);
+            ui.label(
+                egui::RichText::new("Note: LSPS2 service changes require server restart")
+                    .small()
+                    .italics()
+                    .color(egui::Color32::GRAY),
+            );
+        });
+
+        if let Some(path) = app.state.session.config_file_path.clone() {
+            egui::CollapsingHeader::new("Config Backups").default_open(false).show(ui, |ui| {
+                render_config_backups(ui, app, &path);
+            });
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        ui.add_space(10.0);
+        render_clear_stored_data(ui, app);
+    }
+
+    ui.add_space(10.0);
+    egui::CollapsingHeader::new("Diagnostics").default_open(false).show(ui, |ui| {
+        render_diagnostics(ui, &app.state);
+    });
+}
+
+/// Button wiping what the browser stores for the GUI, asking for confirmation first.
+#[cfg(target_arch = "wasm32")]
+fn render_clear_stored_data(ui: &mut Ui, app: &mut LdkServerApp) {
+    let confirm_id = ui.id().with("confirm_clear_stored_data");
+    let mut confirming = ui.data(|d| d.get_temp(confirm_id)).unwrap_or(false);
+    ui.horizontal(|ui| {
+        if !confirming {
+            let hint = "Remove the settings, remembered API key, address history and address \
+                book this browser keeps for the GUI";
+            confirming = ui.button("Clear stored data").on_hover_text(hint).clicked();
+            return;
+        }
+        ui.colored_label(egui::Color32::YELLOW, "Remove everything stored in this browser?");
+        if ui.button("Clear and reload").clicked() {
+            app.clear_stored_data();
+            confirming = false;
+        }
+        if ui.button("Cancel").clicked() {
+            confirming = false;
+        }
+    });
+    ui.data_mut(|d| d.insert_temp(confirm_id, confirming));
+}
+
+/// Average duration of each kind of task, and the timing of each recently finished task.
+fn render_diagnostics(ui: &mut Ui, state: &AppState) {
+    let averages = state.task_timings.averages();
+    if averages.is_empty() {
+        ui.label(egui::RichText::new("No finished tasks yet").color(egui::Color32::GRAY));
+        return;
+    }
+
+    egui::Grid::new("task_averages_grid").num_columns(3).spacing([10.0, 5.0]).show(ui, |ui| {
+        ui.strong("Task");
+        ui.strong("Count");
+        ui.strong("Average");
+        ui.end_row();
+        for (kind, count, avg_ms) in averages {
+            ui.label(format!("{:?}", kind));
+            ui.label(count.to_string());
+            ui.monospace(format!("{} ms", avg_ms));
+            ui.end_row();
+        }
+    });
+
+    ui.add_space(5.0);
+    ui.label(format!("Last {} tasks:", TASK_TIMINGS_KEPT));
+    let now_ms = unix_timestamp_millis();
+    egui::ScrollArea::vertical().id_salt("task_timings_scroll").max_height(200.0).show(ui, |ui| {
+        egui::Grid::new("task_timings_grid").num_columns(4).spacing([10.0, 5.0]).show(ui, |ui| {
+            for timing in state.task_timings.iter() {
+                ui.label(format_age(now_ms.saturating_sub(timing.finished_at_ms) / 1000));
+                ui.label(format!("{:?}", timing.kind));
+                ui.monospace(format!("{} ms", timing.duration_ms));
+                ui.label(timing.outcome.label());
+                ui.end_row();
+            }
+        });
+    });
+    ui.label(
+        egui::RichText::new("Durations include time spent waiting for a free request slot.")
+            .small()
+            .italics()
+            .color(egui::Color32::GRAY),
+    );
+}
+
+/// Interval and pause toggle for each resource the GUI refreshes in the background.
+fn render_refresh_schedules(ui: &mut Ui, app: &mut LdkServerApp) {
+    let mut changed = false;
+    egui::Grid::new("refresh_schedules_grid").num_columns(3).spacing([10.0, 5.0]).show(ui, |ui| {
+        for (kind, label, default) in REFRESHED_RESOURCES {
+            let schedule = app.state.refresh_schedules.entry(kind).or_insert(default);
+            ui.label(format!("{}:", label));
+            let interval = egui::DragValue::new(&mut schedule.interval_secs)
+                .range(5..=3600)
+                .prefix("every ")
+                .suffix(" s");
+            changed |= ui.add_enabled(!schedule.paused, interval).changed();
+            changed |= ui.checkbox(&mut schedule.paused, "Paused").changed();
+            ui.end_row();
+        }
+    });
+    changed |= ui
+        .checkbox(&mut app.state.refresh_in_background, "Also refresh in the background")
+        .on_hover_text("Keep refreshing while the window is unfocused or the page hidden")
+        .changed();
+    ui.label(
+        egui::RichText::new(
+            "Only the health check runs while the window is in the background, unless enabled \
+             above. Pending channels are only refreshed while a channel is opening. The public \
+             chain tip, fetched from the block explorer to check that the node keeps up, is \
+             skipped on regtest unless an Esplora chain source is configured.",
+        )
+        .small()
+        .italics()
+        .color(egui::Color32::GRAY),
+    );
+
+    ui.add_space(5.0);
+    let thresholds = &mut app.state.data_age_thresholds;
+    egui::Grid::new("data_age_thresholds_grid").num_columns(2).spacing([10.0, 5.0]).show(ui, |ui| {
+        ui.label("Data aging after:");
+        let aging = egui::DragValue::new(&mut thresholds.aging_after_secs).range(1..=86400);
+        changed |= ui
+            .add(aging.suffix(" s"))
+            .on_hover_text("The \"updated\" caption of cached data turns amber")
+            .changed();
+        ui.end_row();
+
+        ui.label("Data stale after:");
+        let min = thresholds.aging_after_secs;
+        let stale = egui::DragValue::new(&mut thresholds.stale_after_secs).range(min..=86400);
+        changed |= ui
+            .add(stale.suffix(" s"))
+            .on_hover_text("The \"updated\" caption of cached data turns red")
+            .changed();
+        ui.end_row();
+    });
+    thresholds.stale_after_secs = thresholds.stale_after_secs.max(thresholds.aging_after_secs);
+    if changed {
+        app.settings_changed();
+    }
+}
+
+/// Backups made each time the GUI overwrites the config file, with a button to restore each.
+#[cfg(not(target_arch = "wasm32"))]
+fn render_config_backups(ui: &mut Ui, app: &mut LdkServerApp, path: &str) {
+    let backups = config::list_config_backups(std::path::Path::new(path));
+    if backups.is_empty() {
+        ui.label(egui::RichText::new("No backups yet").color(egui::Color32::GRAY));
+        return;
+    }
+
+    let mut restore = None;
+    egui::Grid::new("config_backups_grid").num_columns(3).spacing([10.0, 5.0]).show(ui, |ui| {
+        for backup in &backups {
+            let name = backup.path.file_name().unwrap_or_default().to_string_lossy();
+            ui.monospace(name);
+            ui.label(format_age(unix_timestamp_now().saturating_sub(backup.created_at)));
+            if ui.small_button("Restore").clicked() {
+                restore = Some(backup.clone());
+            }
+            ui.end_row();
+        }
+    });
+    ui.label(
+        egui::RichText::new(format!(
+            "The last {} versions are kept. Restoring backs up the current file first.",
+            config::CONFIG_BACKUPS_KEPT
+        ))
+        .small()
+        .italics()
+        .color(egui::Color32::GRAY),
+    );
+
+    let Some(backup) = restore else {
+        return;
+    };
+    let restored = config::restore_config_backup(std::path::Path::new(path), &backup)
+        .and_then(|()| config::load_config(path));
+    match restored {
+        Ok(gui_config) => {
+            app.load_gui_config(gui_config, path.to_string());
+            app.state.status_message = Some(StatusMessage::success(format!(
+                "Restored {}",
+                backup.path.display()
+            )));
+        }
+        Err(e) => app.state.status_message = Some(StatusMessage::error(e)),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn render_lsps2_service_editor(ui: &mut Ui, form: &mut Lsps2ServiceForm) {
+    ui.checkbox(&mut form.enabled, "Offer just-in-time channels to clients (LSPS2)");
+    if !form.enabled {
+        return;
+    }
+
+    layout::form_grid(ui, "lsps2_service_grid", |ui| {
+        ui.label("Advertise Service:");
+        ui.checkbox(&mut form.advertise_service, "");
+        ui.end_row();
+
+        let numbers = [
+            ("Opening Fee (ppm):", &mut form.channel_opening_fee_ppm),
+            ("Over-provisioning (ppm):", &mut form.channel_over_provisioning_ppm),
+            ("Min Opening Fee (msat):", &mut form.min_channel_opening_fee_msat),
+            ("Min Channel Lifetime (blocks):", &mut form.min_channel_lifetime),
+            ("Max Client to_self_delay (blocks):", &mut form.max_client_to_self_delay),
+            ("Min Payment Size (msat):", &mut form.min_payment_size_msat),
+            ("Max Payment Size (msat):", &mut form.max_payment_size_msat),
+        ];
+        for (label, value) in numbers {
+            ui.label(label);
+            ui.text_edit_singleline(value);
+            ui.end_row();
+        }
+
+        ui.label("Client Trusts LSP:");
+        ui.checkbox(&mut form.client_trusts_lsp, "");
+        ui.end_row();
+
+        ui.label("Required Token:");
+        secret_field(ui, "lsps2_require_token", &mut form.require_token);
+        ui.end_row();
+    });
+
+    if let Err(e) = form.to_settings() {
+        ui.colored_label(egui::Color32::RED, e);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn render_node_settings_editor(ui: &mut Ui, form: &mut NodeSettingsForm) {
+    layout::form_grid(ui, "node_settings_grid", |ui| {
+        ui.label("Network:");
+        egui::ComboBox::from_id_salt("node_settings_network")
+            .selected_text(form.network.as_str())
+            .show_ui(ui, |ui| {
+                for network in Network::ALL {
+                    ui.selectable_value(&mut form.network, network.to_string(), network.as_str());
+                }
+            });
+        ui.end_row();
+
+        ui.label("Alias:");
+        ui.add(egui::TextEdit::singleline(&mut form.alias).hint_text("Up to 32 bytes"));
+        ui.end_row();
+
+        ui.label("Listening Addresses:");
+        ui.add(
+            egui::TextEdit::singleline(&mut form.listening_addresses)
+                .hint_text("0.0.0.0:9735, [::]:9735"),
+        );
+        ui.end_row();
+
+        ui.label("Announcement Addresses:");
+        ui.add(
+            egui::TextEdit::singleline(&mut form.announcement_addresses)
+                .hint_text("203.0.113.5:9735"),
+        );
+        ui.end_row();
+
+        ui.label("REST Service Address:");
+        ui.add(
+            egui::TextEdit::singleline(&mut form.rest_service_address)
+                .hint_text("127.0.0.1:3002"),
+        );
+        ui.end_row();
+
+        ui.label("Storage Directory:");
+        ui.add(
+            egui::TextEdit::singleline(&mut form.storage_dir).hint_text("/var/lib/ldk-server"),
+        );
+        ui.end_row();
+    });
+
+    // Nothing to validate until a config file has been loaded
+    if form.network.is_empty() {
+        return;
+    }
+    if let Some(Err(e)) = form.section_updates().iter().map(|u| u.validate()).find(|r| r.is_err()) {
+        ui.colored_label(egui::Color32::RED, e);
+    }
+}
+
+/// Fill the API key from the api_key file below the storage directory `dir`.
+#[cfg(not(target_arch = "wasm32"))]
+fn locate_api_key(app: &mut LdkServerApp, dir: &std::path::Path) {
+    match config::locate_api_key(dir, app.state.session.network()) {
+        Ok((path, api_key)) => {
+            app.state.session.api_key = api_key;
+            app.state.connection_field_errors.retain(|(f, _)| *f != ConnectionField::ApiKey);
+            app.state.env_overrides.retain(|f| *f != ConnectionField::ApiKey);
+            app.state.status_message = Some(StatusMessage::success(format!(
+                "API key read from {}",
+                path.display()
+            )));
+        }
+        Err(e) => app.state.status_message = Some(StatusMessage::error(e)),
+    }
+}
+
+/// SHA-256 fingerprint of the entered certificate with copy and pin controls.
+#[cfg(not(target_arch = "wasm32"))]
+fn render_cert_fingerprint(ui: &mut Ui, app: &mut LdkServerApp) {
+    let fingerprint = match app.tls_cert_fingerprint() {
+        None => return,
+        Some(Err(e)) => {
+            ui.label(egui::RichText::new(e.as_str()).small().color(egui::Color32::GRAY));
+            return;
+        }
+        Some(Ok(fingerprint)) => fingerprint.clone(),
+    };
+
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("SHA-256:").small());
+        ui.label(egui::RichText::new(&fingerprint).small().monospace());
+        copy_button(ui, "Copy", || fingerprint.clone());
+    });
+
+    let pinned_fingerprint = app.state.session.pinned_cert_fingerprint.clone();
+    let mut pinned = pinned_fingerprint.as_deref() == Some(fingerprint.as_str());
+    if let Some(expected) = pinned_fingerprint.as_deref().filter(|_| !pinned) {
+        ui.colored_label(
+            egui::Color32::YELLOW,
+            format!("⚠ Does not match the pinned fingerprint {}", expected),
+        );
+    }
+    if ui
+        .checkbox(&mut pinned, "Pin this certificate")
+        .on_hover_text("Saved with the profile; a different certificate asks for confirmation before connecting")
+        .changed()
+    {
+        app.state.session.pinned_cert_fingerprint = pinned.then_some(fingerprint);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn render_cert_pin_mismatch_dialog(ctx: &egui::Context, app: &mut LdkServerApp) {
+    let Some(fingerprint) = app.state.cert_pin_mismatch.clone() else {
+        return;
+    };
+
+    let mut trust = false;
+    let mut cancel = false;
+
+    egui::Window::new("Certificate Changed")
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                "⚠ The TLS certificate does not match the pinned fingerprint.",
+            );
+            ui.label("If the server's certificate was not regenerated on purpose, do not connect.");
+            ui.add_space(5.0);
+
+            egui::Grid::new("cert_pin_mismatch_grid").num_columns(2).spacing([10.0, 5.0]).show(ui, |ui| {
+                ui.label("Pinned:");
+                ui.monospace(
+                    app.state.session.pinned_cert_fingerprint.as_deref().unwrap_or_default(),
+                );
                 ui.end_row();
-            }
-        });
 
-        ui.add_space(10.0);
+                ui.label("Current:");
+                ui.monospace(&fingerprint);
+                ui.end_row();
+            });
 
-        ui.horizontal(|ui| {
-            let is_connected = matches!(app.state.connection_status, ConnectionStatus::Connected);
-            if is_connected {
-                if ui.button("Disconnect").clicked() {
-                    app.disconnect();
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button("Trust new certificate and connect").clicked() {
+                    trust = true;
                 }
-            } else if ui.button("Connect").clicked() {
-                app.connect();
-            }
-
-            ui.separator();
-
-            // Native: file dialog
-            #[cfg(not(target_arch = "wasm32"))]
-            if ui.button("Load Config").clicked() {
-                if let Some(path) = rfd::FileDialog::new()
-                    .add_filter("TOML files", &["toml"])
-                    .add_filter("All files", &["*"])
-                    .pick_file()
-                {
-                    match config::load_config(&path) {
-                        Ok(gui_config) => {
-                            app.state.server_url = gui_config.server_url;
-                            app.state.api_key = gui_config.api_key;
-                            app.state.tls_cert_path = gui_config.tls_cert_path;
-                            app.state.network = gui_config.network;
-                            app.state.config_file_path = Some(path.display().to_string());
-                            app.state.forms.chain_source =
-                                ChainSourceForm::from_config(&gui_config.chain_source);
-                            app.state.chain_source = gui_config.chain_source;
-                            app.state.status_message = Some(StatusMessage::success(format!(
-                                "Config loaded from {}",
-                                path.display()
-                            )));
-                        }
-                        Err(e) => {
-                            app.state.status_message =
-                                Some(StatusMessage::error(format!("Failed to load config: {}", e)));
-                        }
-                    }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
                 }
-            }
-
-            // WASM: show paste dialog
-            #[cfg(target_arch = "wasm32")]
-            if ui.button("Load Config").clicked() {
-                app.state.show_load_config_dialog = true;
-            }
+            });
         });
-    });
 
-    // Chain Source Settings (collapsible) - only on native
-    #[cfg(not(target_arch = "wasm32"))]
-    {
-        ui.add_space(10.0);
+    if trust {
+        app.trust_new_cert();
+    } else if cancel {
+        app.state.cert_pin_mismatch = None;
+    }
+}
 
-        egui::CollapsingHeader::new("Chain Source Settings")
-            .default_open(false)
-            .show(ui, |ui| {
-                render_chain_source_editor(ui, &mut app.state.forms.chain_source);
+/// The loaded config file with its secrets redacted, reporting failures in the status bar.
+#[cfg(not(target_arch = "wasm32"))]
+fn sanitized_config(app: &mut LdkServerApp, path: &str) -> Option<String> {
+    match config::read_config_file(path).and_then(|contents| config::sanitize_config(&contents)) {
+        Ok(sanitized) => Some(sanitized),
+        Err(e) => {
+            app.state.status_message = Some(StatusMessage::error(e));
+            None
+        }
+    }
+}
 
-                ui.add_space(10.0);
+#[cfg(not(target_arch = "wasm32"))]
+fn export_sanitized_config(app: &mut LdkServerApp, path: &str) {
+    let Some(sanitized) = sanitized_config(app, path) else {
+        return;
+    };
+    let name = "ldk-server-config.sanitized.toml".to_string();
+    app.save_text_file(name, move || Ok(sanitized));
+}
 
-                ui.horizontal(|ui| {
-                    // Save Config button - saves to current file or prompts if none loaded
-                    let has_config = app.state.config_file_path.is_some();
-                    if ui.button("Save").clicked() {
-                        if let Some(path) = &app.state.config_file_path {
-                            let chain_source = app.state.forms.chain_source.to_config();
-                            match config::save_chain_source(path, &chain_source) {
-                                Ok(()) => {
-                                    app.state.chain_source = chain_source;
-                                    app.state.status_message = Some(StatusMessage::success(format!(
-                                        "Config saved to {}",
-                                        path
-                                    )));
-                                }
-                                Err(e) => {
-                                    app.state.status_message =
-                                        Some(StatusMessage::error(format!("Failed to save: {}", e)));
-                                }
-                            }
-                        } else {
-                            app.state.status_message =
-                                Some(StatusMessage::error("No config file loaded. Use 'Save As...'"));
-                        }
+#[cfg(not(target_arch = "wasm32"))]
+fn render_profile_picker(ui: &mut Ui, app: &mut LdkServerApp) {
+    let mut selected = None;
+    let mut save = false;
+    let mut delete = false;
+    let mut use_keyring = app.state.use_keyring;
+
+    ui.horizontal(|ui| {
+        ui.label("Profile:");
+        let selected_text = app.state.selected_profile.as_deref().unwrap_or("None");
+        egui::ComboBox::from_id_salt("connection_profile")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                if app.state.connection_profiles.is_empty() {
+                    ui.label("No saved profiles");
+                }
+                for (index, profile) in app.state.connection_profiles.iter().enumerate() {
+                    let is_selected = app.state.selected_profile.as_deref() == Some(&profile.name);
+                    if ui.selectable_label(is_selected, &profile.name).clicked() {
+                        selected = Some(index);
                     }
+                }
+            });
+        if app.state.selected_profile.is_some() && ui.button("Delete").clicked() {
+            delete = true;
+        }
 
-                    // Save As button - always shows file dialog
-                    if ui.button("Save As...").clicked() {
-                        let mut dialog = rfd::FileDialog::new()
-                            .add_filter("TOML files", &["toml"])
-                            .set_file_name("ldk-server-config.toml");
+        ui.separator();
 
-                        // Set starting directory based on existing config or sensible default
-                        if let Some(existing_path) = &app.state.config_file_path {
-                            if let Some(parent) = std::path::Path::new(existing_path).parent() {
-                                dialog = dialog.set_directory(parent);
-                            }
-                        } else if let Ok(cwd) = std::env::current_dir() {
-                            let ldk_server_dir = cwd.join("ldk-server");
-                            if ldk_server_dir.exists() {
-                                dialog = dialog.set_directory(&ldk_server_dir);
-                            } else {
-                                dialog = dialog.set_directory(&cwd);
-                            }
-                        }
+        ui.add(egui::TextEdit::singleline(&mut app.state.profile_name).hint_text("Profile name"));
+        if ui.button("Save as Profile").clicked() {
+            save = true;
+        }
+        ui.checkbox(&mut use_keyring, "Use OS keyring").on_hover_text(
+            "Keep profile API keys in the OS credential store instead of the profile file",
+        );
+    });
 
-                        if let Some(path) = dialog.save_file() {
-                            let chain_source = app.state.forms.chain_source.to_config();
-                            match config::save_chain_source(&path, &chain_source) {
-                                Ok(()) => {
-                                    app.state.config_file_path = Some(path.display().to_string());
-                                    app.state.chain_source = chain_source;
-                                    app.state.status_message = Some(StatusMessage::success(format!(
-                                        "Config saved to {}",
-                                        path.display()
-                                    )));
-                                }
-                                Err(e) => {
-                                    app.state.status_message =
-                                        Some(StatusMessage::error(format!("Failed to save: {}", e)));
-                                }
-                            }
-                        }
-                    }
+    if use_keyring != app.state.use_keyring {
+        app.set_use_keyring(use_keyring);
+    }
 
-                    // Show current config file path
-                    if has_config {
-                        if let Some(path) = &app.state.config_file_path {
-                            ui.label(
-                                egui::RichText::new(format!("({})", path))
-                                    .small()
-                                    .color(egui::Color32::GRAY),
-                            );
-                        }
-                    }
-                });
+    if let Some(index) = selected {
+        let profile = app.state.connection_profiles[index].clone();
+        app.state.session.api_key = match secrets::api_key(&profile) {
+            Ok(api_key) => api_key,
+            Err(e) => {
+                app.state.status_message = Some(StatusMessage::error(e));
+                String::new()
+            }
+        };
+        app.state.session.server_url = profile.server_url;
+        app.state.session.tls_cert_path = profile.tls_cert_path;
+        app.state.session.network = profile.network;
+        app.state.session.pinned_cert_fingerprint = profile.pinned_cert_fingerprint;
+        app.state.session.proxy = profile.proxy;
+        app.state.profile_name = profile.name.clone();
+        app.state.selected_profile = Some(profile.name);
+    }
 
-                ui.add_space(5.0);
-                ui.label(
-                    egui::RichText::new("Note: Chain source changes require server restart")
-                        .small()
-                        .italics()
-                        .color(egui::Color32::GRAY),
-                );
-            });
+    if delete {
+        if let Some(name) = app.state.selected_profile.take() {
+            let removed = app.state.connection_profiles.iter().position(|p| p.name == name);
+            let profile = removed.map(|index| app.state.connection_profiles.remove(index));
+            app.state.status_message = match profile.map(|p| secrets::forget(&p)) {
+                Some(Err(e)) => Some(StatusMessage::error(format!(
+                    "Deleted profile '{}', but its API key is still in the OS keyring: {}",
+                    name, e
+                ))),
+                _ => Some(StatusMessage::success(format!("Deleted profile '{}'", name))),
+            };
+        }
+    }
+
+    if save {
+        let name = app.state.profile_name.trim().to_string();
+        if name.is_empty() {
+            app.state.status_message = Some(StatusMessage::error("Profile name is required"));
+            return;
+        }
+        let mut profile = ConnectionProfile {
+            name: name.clone(),
+            server_url: app.state.session.server_url.trim().to_string(),
+            api_key: app.state.session.api_key.clone(),
+            tls_cert_path: app.state.session.tls_cert_path.trim().to_string(),
+            network: app.state.session.network.clone(),
+            pinned_cert_fingerprint: app.state.session.pinned_cert_fingerprint.clone(),
+            proxy: app.state.session.proxy.trim().to_string(),
+            keyring_account: None,
+        };
+        let keyring_error = if app.state.use_keyring {
+            secrets::move_to_keyring(&mut profile).err()
+        } else {
+            None
+        };
+        let existing = app.state.connection_profiles.iter().position(|p| p.name == name);
+        match existing {
+            Some(index) => {
+                // The key is in plaintext now, so an entry left from an earlier save is stale
+                if profile.keyring_account.is_none() {
+                    let _ = secrets::forget(&app.state.connection_profiles[index]);
+                }
+                app.state.connection_profiles[index] = profile;
+            }
+            None => app.state.connection_profiles.push(profile),
+        }
+        app.state.selected_profile = Some(name.clone());
+        app.state.status_message = Some(match keyring_error {
+            Some(e) => StatusMessage::error(format!(
+                "{}. Profile '{}' was saved with the API key in plaintext.",
+                e, name
+            )),
+            None => StatusMessage::success(format!("Saved profile '{}'", name)),
+        });
     }
 }
 
@@ -248,7 +1640,7 @@ fn render_chain_source_editor(ui: &mut Ui, form: &mut ChainSourceForm) {
                     ui.end_row();
 
                     ui.label("RPC Password:");
-                    ui.add(egui::TextEdit::singleline(&mut form.btc_rpc_password).password(true));
+                    secret_field(ui, "btc_rpc_password", &mut form.btc_rpc_password);
                     ui.end_row();
                 });
         }
@@ -285,18 +1677,321 @@ fn render_chain_source_editor(ui: &mut Ui, form: &mut ChainSourceForm) {
     }
 }
 
-/// Render the Load Config dialog (for WASM - paste config content)
+/// Preview of a pending config file change, written only once confirmed.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn render_config_save_dialog(ctx: &egui::Context, app: &mut LdkServerApp) {
+    let Some(pending) = &app.state.pending_config_save else {
+        return;
+    };
+
+    let mut confirm = false;
+    let mut cancel = false;
+    egui::Window::new("Review Config Changes")
+        .collapsible(false)
+        .resizable(true)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(format!("The following changes will be written to {}:", pending.path));
+            ui.add_space(5.0);
+
+            egui::ScrollArea::both().max_height(400.0).show(ui, |ui| {
+                for line in pending.diff.lines() {
+                    let color = if line.starts_with("@@") {
+                        egui::Color32::LIGHT_BLUE
+                    } else if line.starts_with('+') {
+                        egui::Color32::GREEN
+                    } else if line.starts_with('-') {
+                        egui::Color32::RED
+                    } else {
+                        egui::Color32::GRAY
+                    };
+                    ui.label(egui::RichText::new(line).monospace().color(color));
+                }
+            });
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    confirm = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+    if confirm {
+        app.confirm_config_save();
+    } else if cancel {
+        app.state.pending_config_save = None;
+    }
+}
+
+/// Step-by-step creation of a config file for users who don't have one yet.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn render_new_config_wizard(ctx: &egui::Context, app: &mut LdkServerApp) {
+    let Some(wizard) = &mut app.state.new_config_wizard else {
+        return;
+    };
+
+    let mut open = true;
+    let mut advance = false;
+    egui::Window::new("Create New Config")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(450.0)
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(format!(
+                    "Step {} of {}: {}",
+                    wizard.step.index() + 1,
+                    NewConfigStep::ALL.len(),
+                    wizard.step.title()
+                ))
+                .strong(),
+            );
+            ui.add_space(5.0);
+
+            match wizard.step {
+                NewConfigStep::Network => {
+                    ui.label("The Bitcoin network the node runs on.");
+                    egui::ComboBox::from_id_salt("new_config_network")
+                        .selected_text(wizard.network.as_str())
+                        .show_ui(ui, |ui| {
+                            for network in Network::ALL {
+                                ui.selectable_value(
+                                    &mut wizard.network,
+                                    network.to_string(),
+                                    network.as_str(),
+                                );
+                            }
+                        });
+                }
+                NewConfigStep::Storage => {
+                    ui.label("Where the node keeps its data, API key and TLS certificate.");
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(&mut wizard.storage_dir);
+                        if ui.button("Browse...").clicked() {
+                            if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                                wizard.storage_dir = dir.display().to_string();
+                            }
+                        }
+                    });
+                }
+                NewConfigStep::RestAddress => {
+                    ui.label("Address the server's REST API listens on; the GUI connects to it.");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut wizard.rest_service_address)
+                            .hint_text("127.0.0.1:3002"),
+                    );
+                }
+                NewConfigStep::ChainSource => {
+                    ui.label("Where the node gets blockchain data from.");
+                    render_chain_source_editor(ui, &mut wizard.chain_source);
+                }
+                NewConfigStep::Location => {
+                    ui.label("Where to write the config file.");
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(&mut wizard.path);
+                        if ui.button("Browse...").clicked() {
+                            if let Some(path) = rfd::FileDialog::new()
+                                .add_filter("TOML files", &["toml"])
+                                .set_file_name("ldk-server-config.toml")
+                                .save_file()
+                            {
+                                wizard.path = path.display().to_string();
+                            }
+                        }
+                    });
+                    if std::path::Path::new(wizard.path.trim()).exists() {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            "⚠ A file already exists at this path and will be overwritten.",
+                        );
+                    }
+                }
+            }
+
+            if let Some(error) = &wizard.error {
+                ui.add_space(5.0);
+                ui.colored_label(egui::Color32::RED, error);
+            }
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if let Some(previous) = wizard.step.previous() {
+                    if ui.button("Back").clicked() {
+                        wizard.step = previous;
+                        wizard.error = None;
+                    }
+                }
+                let label = match wizard.step.next() {
+                    Some(_) => "Next",
+                    None if std::path::Path::new(wizard.path.trim()).exists() => "Overwrite",
+                    None => "Create",
+                };
+                if ui.button(label).clicked() {
+                    advance = true;
+                }
+            });
+        });
+
+    if advance {
+        app.advance_new_config_wizard();
+    }
+    if !open {
+        app.state.new_config_wizard = None;
+    }
+}
+
+/// Every problem found in a config that failed to load.
+pub fn render_config_error_dialog(ctx: &egui::Context, app: &mut LdkServerApp) {
+    let Some((source, error)) = &app.state.config_load_error else {
+        return;
+    };
+
+    let mut close = false;
+    egui::Window::new("Config Problems")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(format!("The config from {} could not be loaded:", source));
+            ui.add_space(5.0);
+            for problem in error.lines() {
+                ui.colored_label(egui::Color32::RED, format!("• {}", problem));
+            }
+            ui.add_space(10.0);
+            if ui.button("OK").clicked() {
+                close = true;
+            }
+        });
+
+    if close {
+        app.state.config_load_error = None;
+    }
+}
+
+/// Dim the window while files are dragged over it, to show that configs and certificates can
+/// be dropped onto it.
+pub fn render_drop_overlay(ctx: &egui::Context) {
+    if ctx.input(|i| i.raw.hovered_files.is_empty()) {
+        return;
+    }
+    let text = if cfg!(target_arch = "wasm32") {
+        "Drop a config (.toml) to load it"
+    } else {
+        "Drop a config (.toml) to load it\nor a TLS certificate (.pem, .crt) to connect with it"
+    };
+    let layer = egui::LayerId::new(egui::Order::Foreground, egui::Id::new("drop_overlay"));
+    let painter = ctx.layer_painter(layer);
+    let screen = ctx.screen_rect();
+    painter.rect_filled(screen, 0.0, egui::Color32::from_black_alpha(192));
+    painter.text(
+        screen.center(),
+        egui::Align2::CENTER_CENTER,
+        text,
+        egui::TextStyle::Heading.resolve(&ctx.style()),
+        egui::Color32::WHITE,
+    );
+}
+
+/// Explain how to let the web build reach a server from this page's origin, with reverse proxy
+/// configs to copy.
+#[cfg(target_arch = "wasm32")]
+pub fn render_cors_help_dialog(ctx: &egui::Context, app: &mut LdkServerApp) {
+    if !app.state.show_cors_help {
+        return;
+    }
+
+    let origin = errors::page_origin().unwrap_or_default();
+    let mut open = true;
+    egui::Window::new("Allowing This Page's Origin")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_width(560.0)
+        .show(ctx, |ui| {
+            ui.label(
+                "Browsers only let this page call a server on another origin if the server \
+                 allows it with CORS headers. ldk-server only allows http://localhost:8080, the \
+                 development server, so a GUI served from anywhere else needs a reverse proxy in \
+                 front of the server that allows this origin:",
+            );
+            ui.horizontal(|ui| {
+                ui.monospace(&origin);
+                copy_button(ui, "Copy", || origin.clone());
+            });
+            ui.label(
+                egui::RichText::new(
+                    "Then use the proxy's address as the server URL. If the server already \
+                     allows this origin, the request failed for another reason: the server may \
+                     not be running, or the browser may not trust its certificate.",
+                )
+                .small()
+                .color(egui::Color32::GRAY),
+            );
+
+            let snippets =
+                [("nginx", cors::nginx_snippet(&origin)), ("Caddy", cors::caddy_snippet(&origin))];
+            for (name, snippet) in snippets {
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    ui.strong(name);
+                    copy_button(ui, "Copy", || snippet.clone());
+                });
+                egui::ScrollArea::horizontal().id_salt(name).show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut snippet.as_str())
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+            }
+        });
+
+    if !open {
+        app.state.show_cors_help = false;
+    }
+}
+
+/// Render the Load Config dialog (for WASM - fetch or paste config content)
+#[cfg(target_arch = "wasm32")]
 pub fn render_load_config_dialog(ctx: &egui::Context, app: &mut LdkServerApp) {
     if !app.state.show_load_config_dialog {
         return;
     }
 
+    let mut fetch = false;
+    let mut upload = false;
     egui::Window::new("Load Config")
         .collapsible(false)
         .resizable(true)
         .default_width(500.0)
         .show(ctx, |ui| {
-            ui.label("Paste your ldk-server-config.toml content below:");
+            ui.label("Fetch the config from a URL, relative to this page:");
+            ui.horizontal(|ui| {
+                let response = ui.text_edit_singleline(&mut app.state.config_url);
+                if app.state.session.tasks.is_pending(TaskKind::ConfigFetch) {
+                    ui.spinner();
+                } else if ui.button("Load from URL").clicked() || submitted(ui, &response) {
+                    fetch = true;
+                }
+            });
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                ui.label("Or upload it from this computer:");
+                if app.state.session.tasks.is_pending(TaskKind::ConfigUpload) {
+                    ui.spinner();
+                } else if ui.button("Upload file…").clicked() {
+                    upload = true;
+                }
+            });
+
+            ui.add_space(10.0);
+            ui.label("Or paste your ldk-server-config.toml content below:");
             ui.add_space(5.0);
 
             egui::ScrollArea::vertical()
@@ -314,21 +2009,8 @@ pub fn render_load_config_dialog(ctx: &egui::Context, app: &mut LdkServerApp) {
 
             ui.horizontal(|ui| {
                 if ui.button("Load").clicked() {
-                    match config::parse_config_from_str(&app.state.config_paste_text) {
-                        Ok(gui_config) => {
-                            app.state.server_url = gui_config.server_url;
-                            app.state.api_key = gui_config.api_key;
-                            app.state.network = gui_config.network;
-                            app.state.status_message =
-                                Some(StatusMessage::success("Config loaded successfully"));
-                            app.state.show_load_config_dialog = false;
-                            app.state.config_paste_text.clear();
-                        }
-                        Err(e) => {
-                            app.state.status_message =
-                                Some(StatusMessage::error(format!("Failed to parse config: {}", e)));
-                        }
-                    }
+                    let contents = app.state.config_paste_text.clone();
+                    app.load_config_text("pasted text".to_string(), &contents);
                 }
 
                 if ui.button("Cancel").clicked() {
@@ -337,4 +2019,11 @@ pub fn render_load_config_dialog(ctx: &egui::Context, app: &mut LdkServerApp) {
                 }
             });
         });
+
+    if fetch {
+        app.fetch_config_from_url();
+    }
+    if upload {
+        app.upload_config_file();
+    }
 }
@@ -0,0 +1,48 @@
+//! Layout for narrow windows such as a phone's browser. Below [`COMPACT_WIDTH`] the app is in
+//! compact mode: navigation moves from the side panel into a menu, forms and tables are
+//! stacked, and widgets are larger to be easy to tap.
+
+use egui::Ui;
+
+/// Window width in points below which the app is in compact mode.
+pub const COMPACT_WIDTH: f32 = 700.0;
+
+/// Height of buttons and other interactive widgets in compact mode.
+const COMPACT_INTERACT_HEIGHT: f32 = 32.0;
+
+/// Whether the window is narrow enough for the compact layout.
+pub fn is_compact(ctx: &egui::Context) -> bool {
+    ctx.screen_rect().width() < COMPACT_WIDTH
+}
+
+/// Enlarge buttons and other widgets in compact mode, and restore the default sizes otherwise.
+/// Called at the start of each frame.
+pub fn apply_spacing(ctx: &egui::Context) {
+    let mut spacing = egui::style::Spacing::default();
+    if is_compact(ctx) {
+        spacing.interact_size.y = COMPACT_INTERACT_HEIGHT;
+        spacing.button_padding = egui::vec2(10.0, 6.0);
+        spacing.item_spacing = egui::vec2(8.0, 6.0);
+    }
+    let style = ctx.style();
+    if style.spacing.interact_size != spacing.interact_size
+        || style.spacing.button_padding != spacing.button_padding
+        || style.spacing.item_spacing != spacing.item_spacing
+    {
+        ctx.style_mut(|style| {
+            style.spacing.interact_size = spacing.interact_size;
+            style.spacing.button_padding = spacing.button_padding;
+            style.spacing.item_spacing = spacing.item_spacing;
+        });
+    }
+}
+
+/// A form of label and field rows ended by `ui.end_row()`: a two-column grid, or in compact
+/// mode each label stacked above its field.
+pub fn form_grid<R>(ui: &mut Ui, id_salt: &str, add_contents: impl FnOnce(&mut Ui) -> R) -> R {
+    if is_compact(ui.ctx()) {
+        ui.push_id(id_salt, |ui| ui.vertical(add_contents).inner).inner
+    } else {
+        egui::Grid::new(id_salt).num_columns(2).spacing([10.0, 5.0]).show(ui, add_contents).inner
+    }
+}
@@ -1,13 +1,15 @@
-use egui::Ui;
+use egui::{Context, Ui};
 
 use crate::app::LdkServerApp;
-use crate::state::{ConnectionStatus, LightningTab};
+use crate::lnurl;
+use crate::state::{ConnectionStatus, LightningTab, TaskKind};
+use crate::ui::{copy_button, copy_button_large, format_msat, qr};
 
 pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
     ui.heading("Lightning Payments");
     ui.add_space(10.0);
 
-    if !matches!(app.state.connection_status, ConnectionStatus::Connected) {
+    if !matches!(app.state.session.connection_status, ConnectionStatus::Connected) {
         ui.label("Connect to a server to use lightning payments.");
         return;
     }
@@ -45,16 +47,22 @@ fn render_bolt11_send(ui: &mut Ui, app: &mut LdkServerApp) {
 
         let form = &mut app.state.forms.bolt11_send;
 
-        ui.label("Invoice:");
+        ui.label("Invoice, Lightning Address or LNURL:");
         ui.add(egui::TextEdit::multiline(&mut form.invoice).desired_rows(3).desired_width(f32::INFINITY));
 
+        let is_lnurl = lnurl::is_lnurl_pay_input(&form.invoice);
+
         ui.add_space(5.0);
 
         egui::Grid::new("bolt11_send_grid")
             .num_columns(2)
             .spacing([10.0, 5.0])
             .show(ui, |ui| {
-                ui.label("Amount (msat, for zero-amount invoices):");
+                if is_lnurl {
+                    ui.label("Amount (msat, required):");
+                } else {
+                    ui.label("Amount (msat, for zero-amount invoices):");
+                }
                 ui.text_edit_singleline(&mut form.amount_msat);
                 ui.end_row();
             });
@@ -62,10 +70,21 @@ fn render_bolt11_send(ui: &mut Ui, app: &mut LdkServerApp) {
         ui.add_space(10.0);
 
         ui.horizontal(|ui| {
-            let is_pending = app.state.tasks.bolt11_send.is_some();
-            if is_pending {
+            if app.state.session.tasks.is_pending(TaskKind::LnurlPay) {
+                ui.spinner();
+                ui.label("Fetching invoice...");
+            } else if app.state.session.tasks.is_pending(TaskKind::Bolt11Send) {
                 ui.spinner();
                 ui.label("Sending...");
+            } else if is_lnurl {
+                if ui.button("Fetch Invoice").clicked() {
+                    app.resolve_lnurl_pay();
+                }
+                ui.label(
+                    egui::RichText::new("The invoice will be shown for confirmation before paying")
+                        .small()
+                        .color(egui::Color32::GRAY),
+                );
             } else if ui.button("Pay Invoice").clicked() {
                 app.send_bolt11();
             }
@@ -76,9 +95,7 @@ fn render_bolt11_send(ui: &mut Ui, app: &mut LdkServerApp) {
             ui.horizontal(|ui| {
                 ui.label("Last Payment ID:");
                 ui.monospace(crate::ui::truncate_id(payment_id, 8, 8));
-                if ui.small_button("Copy").clicked() {
-                    ui.output_mut(|o| o.copied_text = payment_id.clone());
-                }
+                copy_button(ui, "Copy", || payment_id.clone());
             });
         }
     });
@@ -111,7 +128,7 @@ fn render_bolt11_receive(ui: &mut Ui, app: &mut LdkServerApp) {
         ui.add_space(10.0);
 
         ui.horizontal(|ui| {
-            let is_pending = app.state.tasks.bolt11_receive.is_some();
+            let is_pending = app.state.session.tasks.is_pending(TaskKind::Bolt11Receive);
             if is_pending {
                 ui.spinner();
                 ui.label("Generating...");
@@ -120,17 +137,39 @@ fn render_bolt11_receive(ui: &mut Ui, app: &mut LdkServerApp) {
             }
         });
 
+        let mut regenerate = false;
         if let Some(invoice) = &app.state.generated_invoice {
+            let is_stale = app
+                .state
+                .generated_invoice_form
+                .as_ref()
+                .is_some_and(|f| f != &app.state.forms.bolt11_receive);
+
             ui.add_space(10.0);
             ui.separator();
-            ui.label("Generated Invoice:");
+            ui.horizontal(|ui| {
+                ui.label("Generated Invoice:");
+                if is_stale {
+                    ui.colored_label(egui::Color32::YELLOW, "stale (form changed)");
+                }
+            });
             ui.add(egui::TextEdit::multiline(&mut invoice.as_str())
                 .desired_rows(4)
                 .desired_width(f32::INFINITY)
                 .interactive(false));
-            if ui.button("Copy Invoice").clicked() {
-                ui.output_mut(|o| o.copied_text = invoice.clone());
-            }
+            qr::show_qr(ui, &mut app.state.qr_textures, "bolt11_invoice", invoice, 240.0);
+            ui.horizontal(|ui| {
+                copy_button_large(ui, "Copy Invoice", || invoice.clone());
+                if is_stale
+                    && !app.state.session.tasks.is_pending(TaskKind::Bolt11Receive)
+                    && ui.button("Regenerate").clicked()
+                {
+                    regenerate = true;
+                }
+            });
+        }
+        if regenerate {
+            app.generate_bolt11_invoice();
         }
     });
 }
@@ -167,7 +206,7 @@ fn render_bolt12_send(ui: &mut Ui, app: &mut LdkServerApp) {
         ui.add_space(10.0);
 
         ui.horizontal(|ui| {
-            let is_pending = app.state.tasks.bolt12_send.is_some();
+            let is_pending = app.state.session.tasks.is_pending(TaskKind::Bolt12Send);
             if is_pending {
                 ui.spinner();
                 ui.label("Sending...");
@@ -181,9 +220,7 @@ fn render_bolt12_send(ui: &mut Ui, app: &mut LdkServerApp) {
             ui.horizontal(|ui| {
                 ui.label("Last Payment ID:");
                 ui.monospace(crate::ui::truncate_id(payment_id, 8, 8));
-                if ui.small_button("Copy").clicked() {
-                    ui.output_mut(|o| o.copied_text = payment_id.clone());
-                }
+                copy_button(ui, "Copy", || payment_id.clone());
             });
         }
     });
@@ -220,7 +257,7 @@ fn render_bolt12_receive(ui: &mut Ui, app: &mut LdkServerApp) {
         ui.add_space(10.0);
 
         ui.horizontal(|ui| {
-            let is_pending = app.state.tasks.bolt12_receive.is_some();
+            let is_pending = app.state.session.tasks.is_pending(TaskKind::Bolt12Receive);
             if is_pending {
                 ui.spinner();
                 ui.label("Generating...");
@@ -229,17 +266,97 @@ fn render_bolt12_receive(ui: &mut Ui, app: &mut LdkServerApp) {
             }
         });
 
+        let mut regenerate = false;
         if let Some(offer) = &app.state.generated_offer {
+            let is_stale = app
+                .state
+                .generated_offer_form
+                .as_ref()
+                .is_some_and(|f| f != &app.state.forms.bolt12_receive);
+
             ui.add_space(10.0);
             ui.separator();
-            ui.label("Generated Offer:");
+            ui.horizontal(|ui| {
+                ui.label("Generated Offer:");
+                if is_stale {
+                    ui.colored_label(egui::Color32::YELLOW, "stale (form changed)");
+                }
+            });
             ui.add(egui::TextEdit::multiline(&mut offer.as_str())
                 .desired_rows(4)
                 .desired_width(f32::INFINITY)
                 .interactive(false));
-            if ui.button("Copy Offer").clicked() {
-                ui.output_mut(|o| o.copied_text = offer.clone());
-            }
+            qr::show_qr(ui, &mut app.state.qr_textures, "bolt12_offer", offer, 240.0);
+            ui.horizontal(|ui| {
+                copy_button_large(ui, "Copy Offer", || offer.clone());
+                if is_stale
+                    && !app.state.session.tasks.is_pending(TaskKind::Bolt12Receive)
+                    && ui.button("Regenerate").clicked()
+                {
+                    regenerate = true;
+                }
+            });
+        }
+        if regenerate {
+            app.generate_bolt12_offer();
         }
     });
 }
+
+pub fn render_dialogs(ctx: &Context, app: &mut LdkServerApp) {
+    render_lnurl_confirm_dialog(ctx, app);
+}
+
+fn render_lnurl_confirm_dialog(ctx: &Context, app: &mut LdkServerApp) {
+    let Some(pending) = &app.state.pending_lnurl_invoice else {
+        return;
+    };
+
+    let mut confirm = false;
+    let mut cancel = false;
+
+    egui::Window::new("Confirm Payment")
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("lnurl_confirm_grid")
+                .num_columns(2)
+                .spacing([10.0, 5.0])
+                .show(ui, |ui| {
+                    ui.label("Recipient:");
+                    ui.monospace(&pending.target);
+                    ui.end_row();
+
+                    ui.label("Amount:");
+                    ui.strong(format_msat(pending.amount_msat));
+                    ui.end_row();
+
+                    if let Some(description) = &pending.description {
+                        ui.label("Description:");
+                        ui.label(description);
+                        ui.end_row();
+                    }
+
+                    ui.label("Invoice:");
+                    ui.monospace(crate::ui::truncate_id(&pending.invoice, 12, 12));
+                    ui.end_row();
+                });
+
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                if ui.button("Pay").clicked() {
+                    confirm = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+    if confirm {
+        app.confirm_lnurl_pay();
+    } else if cancel {
+        app.state.pending_lnurl_invoice = None;
+    }
+}
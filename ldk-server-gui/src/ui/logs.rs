@@ -0,0 +1,84 @@
+use egui::{ScrollArea, Ui};
+use tracing::Level;
+
+use crate::app::LdkServerApp;
+use crate::logs::LogEntry;
+use crate::ui::copy_to_clipboard;
+
+const LEVELS: [Level; 5] = [Level::ERROR, Level::WARN, Level::INFO, Level::DEBUG, Level::TRACE];
+
+pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
+    ui.heading("Logs");
+    ui.add_space(10.0);
+
+    let entries = app.logs.entries(app.state.log_level);
+
+    ui.horizontal(|ui| {
+        ui.label("Level:");
+        egui::ComboBox::from_id_salt("log_level")
+            .selected_text(app.state.log_level.as_str())
+            .show_ui(ui, |ui| {
+                for level in LEVELS {
+                    ui.selectable_value(&mut app.state.log_level, level, level.as_str());
+                }
+            });
+        if ui.add_enabled(!entries.is_empty(), egui::Button::new("Copy all")).clicked() {
+            let text: Vec<String> = entries.iter().map(format_entry).collect();
+            copy_to_clipboard(ui, text.join("\n"));
+        }
+        if ui.button("Clear").clicked() {
+            app.logs.clear();
+        }
+    });
+    ui.label(
+        egui::RichText::new("Each request and task, with how long it took and how it ended.")
+            .small()
+            .color(egui::Color32::GRAY),
+    );
+
+    ui.add_space(10.0);
+
+    if entries.is_empty() {
+        ui.label("Nothing logged at this level yet.");
+        return;
+    }
+
+    ScrollArea::vertical().auto_shrink([false, false]).stick_to_bottom(true).show(ui, |ui| {
+        for entry in &entries {
+            ui.horizontal(|ui| {
+                ui.monospace(format_time(entry.at_ms));
+                let level = egui::RichText::new(entry.level.as_str()).monospace();
+                ui.label(level.color(level_color(entry.level)));
+                ui.monospace(&entry.message);
+            });
+        }
+    });
+    // New entries arrive from the subscriber without an event of their own
+    ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+}
+
+fn level_color(level: Level) -> egui::Color32 {
+    match level {
+        Level::ERROR => egui::Color32::RED,
+        Level::WARN => egui::Color32::from_rgb(255, 165, 0),
+        Level::INFO => egui::Color32::GREEN,
+        _ => egui::Color32::GRAY,
+    }
+}
+
+/// One line of the copied log: time, level, target and message.
+fn format_entry(entry: &LogEntry) -> String {
+    format!(
+        "{} {:5} {}: {}",
+        format_time(entry.at_ms),
+        entry.level.as_str(),
+        entry.target,
+        entry.message
+    )
+}
+
+/// Time of day in UTC with milliseconds, e.g. `14:03:27.512`.
+fn format_time(at_ms: u64) -> String {
+    let secs = at_ms / 1000 % 86400;
+    format!("{:02}:{:02}:{:02}.{:03}", secs / 3600, secs / 60 % 60, secs % 60, at_ms % 1000)
+}
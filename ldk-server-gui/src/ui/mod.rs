@@ -1,10 +1,23 @@
 pub mod balances;
 pub mod channels;
 pub mod connection;
+pub mod layout;
 pub mod lightning;
+pub mod logs;
 pub mod node_info;
 pub mod onchain;
+pub mod overview;
 pub mod payments;
+pub mod qr;
+
+use std::time::Duration;
+
+use crate::amount;
+use crate::export;
+use crate::fiat::{self, FiatRate};
+use crate::network::Network;
+use crate::state::{Cached, DataAgeThresholds, StatusMessage, TaskKind};
+use crate::task::RefreshScheduler;
 
 pub fn truncate_id(s: &str, start: usize, end: usize) -> String {
     if s.len() <= start + end + 2 {
@@ -14,24 +27,246 @@ pub fn truncate_id(s: &str, start: usize, end: usize) -> String {
     }
 }
 
-pub fn format_sats(sats: u64) -> String {
-    let s = sats.to_string();
-    let mut result = String::new();
-    for (i, c) in s.chars().rev().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            result.insert(0, ',');
+/// Show a truncated txid, linked to the block explorer when the network has one.
+pub fn txid_link(ui: &mut egui::Ui, network: Network, txid: &str, start: usize, end: usize) {
+    let text = egui::RichText::new(truncate_id(txid, start, end)).monospace();
+    match crate::explorer::tx_url(network, txid) {
+        Some(url) => {
+            ui.hyperlink_to(text, url).on_hover_text("View in block explorer");
+        }
+        None => {
+            ui.label(text);
         }
-        result.insert(0, c);
     }
-    result
+}
+
+/// Refresh button of a cached resource, disabled with a countdown during the resource's refresh
+/// cooldown. Returns whether it was clicked.
+pub fn refresh_button(
+    ui: &mut egui::Ui, refresh: &RefreshScheduler<TaskKind>, kind: TaskKind,
+) -> bool {
+    let Some(left) = refresh.cooldown_left(kind, unix_timestamp_millis()) else {
+        return ui.button("Refresh").clicked();
+    };
+    ui.add_enabled(false, egui::Button::new("Refresh"));
+    let countdown = format!("{:.1}s", left.as_secs_f32());
+    ui.label(egui::RichText::new(countdown).small().color(egui::Color32::GRAY));
+    ui.ctx().request_repaint_after(Duration::from_millis(100));
+    false
+}
+
+/// "updated 12s ago" caption of cached data, amber once it is older than the aging threshold and
+/// red once it is stale. Shows nothing before the data is first fetched.
+pub fn data_age<T>(ui: &mut egui::Ui, cached: Option<&Cached<T>>, thresholds: DataAgeThresholds) {
+    let Some(cached) = cached else {
+        return;
+    };
+    let age = unix_timestamp_now().saturating_sub(cached.fetched_at);
+    let color = if age >= thresholds.stale_after_secs {
+        egui::Color32::RED
+    } else if age >= thresholds.aging_after_secs {
+        egui::Color32::from_rgb(255, 165, 0)
+    } else {
+        egui::Color32::GRAY
+    };
+    let caption = egui::RichText::new(format!("updated {}", format_age(age))).small().color(color);
+    ui.label(caption).on_hover_text(format!(
+        "Amber after {}s, red after {}s; thresholds are set under Background Refresh",
+        thresholds.aging_after_secs, thresholds.stale_after_secs
+    ));
+    ui.ctx().request_repaint_after(Duration::from_secs(1));
+}
+
+/// Approximate fiat value of `sats` as a gray caption, with the rate and its age on hover.
+/// Shows nothing when fiat values are off or no recent rate could be fetched.
+pub fn fiat_value(ui: &mut egui::Ui, rate: Option<&FiatRate>, sats: u64) {
+    let Some(rate) = rate else {
+        return;
+    };
+    let caption = egui::RichText::new(rate.format_sats(sats)).small().color(egui::Color32::GRAY);
+    ui.label(caption).on_hover_text(format!(
+        "Approximate, at the current rate of 1 BTC = {} from CoinGecko, updated {}",
+        fiat::format_fiat(rate.btc_price, &rate.currency),
+        format_age(unix_timestamp_now().saturating_sub(rate.updated_at))
+    ));
+}
+
+/// Change of a balance since the previous refresh as a small green or red caption, e.g.
+/// `▲ +12,400 sats since last refresh`. Shows nothing when the balance didn't change.
+pub fn balance_change(ui: &mut egui::Ui, change: Option<i64>) {
+    let Some(change) = change.filter(|change| *change != 0) else {
+        return;
+    };
+    let (arrow, sign, color) = if change > 0 {
+        ("▲", "+", egui::Color32::GREEN)
+    } else {
+        ("▼", "-", egui::Color32::RED)
+    };
+    let amount = format_sats(change.unsigned_abs());
+    let text = format!("{} {}{} since last refresh", arrow, sign, amount);
+    ui.label(egui::RichText::new(text).small().color(color));
+}
+
+/// How long a copy button reads "Copied!" after being clicked.
+const COPIED_FEEDBACK: Duration = Duration::from_millis(1500);
+
+/// Copy `text` to the system clipboard: through egui natively, through the browser's Clipboard
+/// API on the web, where failures end up in the status bar.
+pub fn copy_to_clipboard(ui: &egui::Ui, text: String) {
+    copy_then(ui.ctx(), text, |_| {});
+}
+
+/// [`copy_to_clipboard`], calling `on_copied` once the text is on the clipboard. On the web
+/// that is only after the browser accepted it, and never if it refused.
+fn copy_then(ctx: &egui::Context, text: String, on_copied: impl FnOnce(&egui::Context) + 'static) {
+    #[cfg(target_arch = "wasm32")]
+    crate::clipboard::write_text(ctx, text, on_copied);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        ctx.output_mut(|o| o.copied_text = text);
+        on_copied(ctx);
+    }
+}
+
+/// Small button that copies the text `text` returns, reading "Copied!" for a moment once it is
+/// on the clipboard.
+pub fn copy_button(
+    ui: &mut egui::Ui, label: &str, text: impl FnOnce() -> String,
+) -> egui::Response {
+    copy_button_sized(ui, label, text, true)
+}
+
+/// [`copy_button`] at the regular button size.
+pub fn copy_button_large(
+    ui: &mut egui::Ui, label: &str, text: impl FnOnce() -> String,
+) -> egui::Response {
+    copy_button_sized(ui, label, text, false)
+}
+
+fn copy_button_sized(
+    ui: &mut egui::Ui, label: &str, text: impl FnOnce() -> String, small: bool,
+) -> egui::Response {
+    let copied_id = ui.next_auto_id().with("copied_at");
+    let now = ui.input(|i| i.time);
+    let copied_at: Option<f64> = ui.data(|d| d.get_temp(copied_id));
+    let feedback_left = copied_at
+        .map(|t| COPIED_FEEDBACK.as_secs_f64() - (now - t))
+        .filter(|left| *left > 0.0);
+    let label = if feedback_left.is_some() { "Copied!" } else { label };
+    let response = ui.add(egui::Button::new(label).small(small));
+    if response.clicked() {
+        copy_then(ui.ctx(), text(), move |ctx| {
+            let now = ctx.input(|i| i.time);
+            ctx.data_mut(|d| d.insert_temp(copied_id, now));
+            ctx.request_repaint_after(COPIED_FEEDBACK);
+        });
+    } else if let Some(left) = feedback_left {
+        ui.ctx().request_repaint_after(Duration::from_secs_f64(left));
+    }
+    response
+}
+
+/// "Copy as JSON" and "Save as JSON…" buttons for a cached response, with a toggle to shorten
+/// the node ids in it. Serialization failures are reported in the status bar. Returns whether
+/// saving was asked for, which is left to the caller through [`LdkServerApp::save_json`].
+///
+/// [`LdkServerApp::save_json`]: crate::app::LdkServerApp::save_json
+pub fn json_export_buttons<T: serde::Serialize>(
+    ui: &mut egui::Ui, response: &T, redact_node_ids: &mut bool,
+    status_message: &mut Option<StatusMessage>,
+) -> bool {
+    if ui.small_button("Copy as JSON").clicked() {
+        match export::to_json(response, *redact_node_ids) {
+            Ok(json) => copy_to_clipboard(ui, json),
+            Err(e) => *status_message = Some(StatusMessage::error(e)),
+        }
+    }
+    let save = ui.small_button("Save as JSON…").clicked();
+    ui.checkbox(redact_node_ids, "Redact node ids")
+        .on_hover_text("Keep only the first and last characters of node ids in the exported JSON");
+    save
+}
+
+/// A single-line input for secrets, masked by default with a toggle to reveal it and a button
+/// that copies the real value.
+pub fn secret_field(ui: &mut egui::Ui, id_salt: &str, value: &mut String) -> egui::Response {
+    ui.horizontal(|ui| {
+        let id = ui.id().with(id_salt).with("revealed");
+        let mut revealed = ui.data(|d| d.get_temp::<bool>(id)).unwrap_or(false);
+
+        let response = ui.add(egui::TextEdit::singleline(value).password(!revealed));
+        let toggle = ui
+            .selectable_label(revealed, "👁")
+            .on_hover_text(if revealed { "Hide" } else { "Show" });
+        if toggle.clicked() {
+            revealed = !revealed;
+            ui.data_mut(|d| d.insert_temp(id, revealed));
+        }
+        copy_button(ui, "Copy", || value.clone());
+        response
+    })
+    .inner
+}
+
+/// Format a sat amount in the display unit chosen in the top panel, e.g. `1,500 sats`.
+pub fn format_sats(sats: u64) -> String {
+    amount::format_amount(sats, amount::display_unit())
+}
+
+/// Format a sat amount as a decimal BTC string without trailing zeros, e.g. `0.0015`.
+pub fn sats_to_btc_string(sats: u64) -> String {
+    let whole = sats / 100_000_000;
+    let frac = sats % 100_000_000;
+    if frac == 0 {
+        whole.to_string()
+    } else {
+        let frac = format!("{:08}", frac);
+        format!("{}.{}", whole, frac.trim_end_matches('0'))
+    }
 }
 
 pub fn format_msat(msat: u64) -> String {
-    let sats = msat / 1000;
-    let remainder = msat % 1000;
-    if remainder == 0 {
-        format!("{} sats", format_sats(sats))
+    amount::format_msat_amount(msat, amount::display_unit())
+}
+
+/// Current unix time in seconds.
+pub fn unix_timestamp_now() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    let now_secs = (web_sys::js_sys::Date::now() / 1000.0) as u64;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let now_secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    now_secs
+}
+
+/// Current unix time in milliseconds.
+pub fn unix_timestamp_millis() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    let now_millis = web_sys::js_sys::Date::now() as u64;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let now_millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    now_millis
+}
+
+/// Format an elapsed number of seconds as a short "N ago" string.
+pub fn format_age(secs: u64) -> String {
+    if secs < 60 {
+        format!("{}s ago", secs)
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h ago", secs / 3600)
     } else {
-        format!("{}.{:03} sats", format_sats(sats), remainder)
+        format!("{}d ago", secs / 86400)
     }
 }
@@ -1,11 +1,14 @@
 use egui::Ui;
-#[cfg(target_arch = "wasm32")]
-use web_sys::js_sys;
 
 use crate::app::LdkServerApp;
-use crate::config::ChainSourceConfig;
-use crate::state::ConnectionStatus;
-use crate::ui::connection;
+use crate::config::{self, ChainSourceConfig};
+use crate::health::{self, CheckStatus};
+use crate::state::{ConnectionStatus, TaskKind};
+use crate::ui::{
+    connection, copy_button, data_age, format_age, format_msat, json_export_buttons, qr,
+    refresh_button, unix_timestamp_now,
+};
+use ldk_server_client::ldk_server_protos::api::GetNodeInfoResponse;
 
 pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
     ui.heading("Node Information");
@@ -16,9 +19,10 @@ pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
 
     // Show chain source config if loaded
     render_chain_source_info(ui, app);
+    render_lsps2_service_info(ui, app);
     ui.add_space(10.0);
 
-    if !matches!(app.state.connection_status, ConnectionStatus::Connected) {
+    if !matches!(app.state.session.connection_status, ConnectionStatus::Connected) {
         ui.label("Connect to a server to view node information.");
         return;
     }
@@ -26,22 +30,33 @@ pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
     ui.group(|ui| {
         ui.horizontal(|ui| {
             ui.heading("Node Details");
-            if app.state.tasks.node_info.is_some() {
+            if app.state.session.tasks.is_pending(TaskKind::NodeInfo) {
                 ui.spinner();
-            } else if ui.button("Refresh").clicked() {
+            } else if refresh_button(ui, &app.state.session.refresh, TaskKind::NodeInfo) {
                 app.fetch_node_info();
             }
+            data_age(ui, app.state.session.node_info.as_ref(), app.state.data_age_thresholds);
+            if let Some(info) = &app.state.session.node_info {
+                let redact = &mut app.state.redact_json_node_ids;
+                if json_export_buttons(ui, &info.data, redact, &mut app.state.status_message) {
+                    let info = info.data.clone();
+                    app.save_json("node-info", info);
+                }
+            }
         });
         ui.add_space(5.0);
 
-        if let Some(info) = &app.state.node_info {
+        if let Some(info) = app.state.session.node_info.as_ref().map(|i| &i.data) {
+            let qr_id = ui.id().with("node_id_qr");
+            let mut show_qr = ui.data(|d| d.get_temp(qr_id)).unwrap_or(false);
             egui::Grid::new("node_info_grid").num_columns(2).spacing([10.0, 5.0]).show(ui, |ui| {
                 ui.label("Node ID:");
                 ui.horizontal(|ui| {
                     let node_id = &info.node_id;
                     ui.monospace(crate::ui::truncate_id(node_id, 12, 12));
-                    if ui.small_button("Copy").clicked() {
-                        ui.output_mut(|o| o.copied_text = node_id.clone());
+                    copy_button(ui, "Copy", || node_id.clone());
+                    if ui.selectable_label(show_qr, "Show QR").clicked() {
+                        show_qr = !show_qr;
                     }
                 });
                 ui.end_row();
@@ -51,75 +66,213 @@ pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
                     ui.monospace(format!("{} (height: {})", crate::ui::truncate_id(&block.block_hash, 8, 8), block.height));
                     ui.end_row();
                 }
+            });
+            ui.data_mut(|d| d.insert_temp(qr_id, show_qr));
+            if show_qr {
+                let settings = app.state.session.node_settings.as_ref();
+                let uri =
+                    settings.and_then(|s| config::node_uris(&info.node_id, s).into_iter().next());
+                render_node_id_qr(ui, &mut app.state.qr_textures, &info.node_id, uri.as_deref());
+            }
+            ui.add_space(5.0);
+            render_health_checks(ui, info);
+        } else {
+            ui.label("No node info available. Click Refresh to fetch.");
+        }
+    });
 
-                if let Some(ts) = info.latest_lightning_wallet_sync_timestamp {
-                    ui.label("Lightning Wallet Sync:");
-                    ui.label(format_timestamp(ts));
-                    ui.end_row();
-                }
+    render_node_uris(ui, app);
+}
 
-                if let Some(ts) = info.latest_onchain_wallet_sync_timestamp {
-                    ui.label("On-chain Wallet Sync:");
-                    ui.label(format_timestamp(ts));
-                    ui.end_row();
-                }
+/// Addresses peers can reach the node at, as `node_id@host:port` URIs to copy or scan. The
+/// server doesn't report its addresses, so they are taken from the loaded config.
+fn render_node_uris(ui: &mut Ui, app: &mut LdkServerApp) {
+    let Some(node_id) = app.state.session.node_info.as_ref().map(|i| i.data.node_id.clone())
+    else {
+        return;
+    };
+    let settings = app.state.session.node_settings.as_ref();
+    let uris = settings.map(|s| config::node_uris(&node_id, s));
+    let listening_only = settings.is_some_and(|s| s.announcement_addresses.is_empty());
 
-                if let Some(ts) = info.latest_fee_rate_cache_update_timestamp {
-                    ui.label("Fee Rate Cache Update:");
-                    ui.label(format_timestamp(ts));
-                    ui.end_row();
-                }
+    ui.add_space(10.0);
+    ui.group(|ui| {
+        ui.heading("Connect to this Node");
+        ui.add_space(5.0);
 
-                if let Some(ts) = info.latest_rgs_snapshot_timestamp {
-                    ui.label("RGS Snapshot:");
-                    ui.label(format_timestamp(ts));
-                    ui.end_row();
-                }
+        let uris = match uris {
+            None => {
+                ui.label(
+                    "The server doesn't report its addresses. Load its config file to show the \
+                     URIs other nodes can connect to.",
+                );
+                return;
+            }
+            Some(uris) if uris.is_empty() => {
+                ui.label(
+                    "The node has no public address, so other nodes can't open connections to \
+                     it. Set announcement_addresses in the [node] section of the config to \
+                     accept inbound peers.",
+                );
+                return;
+            }
+            Some(uris) => uris,
+        };
 
-                if let Some(ts) = info.latest_node_announcement_broadcast_timestamp {
-                    ui.label("Node Announcement:");
-                    ui.label(format_timestamp(ts));
-                    ui.end_row();
+        let qr_id = ui.id().with("node_uri_qr");
+        let mut shown_qr: Option<usize> = ui.data(|d| d.get_temp(qr_id)).flatten();
+        for (i, uri) in uris.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.monospace(uri);
+                copy_button(ui, "Copy node URI", || uri.clone());
+                let selected = shown_qr == Some(i);
+                if ui.selectable_label(selected, "QR").clicked() {
+                    shown_qr = if selected { None } else { Some(i) };
                 }
             });
-        } else {
-            ui.label("No node info available. Click Refresh to fetch.");
+        }
+        ui.data_mut(|d| d.insert_temp(qr_id, shown_qr));
+
+        if let Some(uri) = shown_qr.and_then(|i| uris.get(i)) {
+            ui.add_space(5.0);
+            qr::show_qr(ui, &mut app.state.qr_textures, "node_uri", uri, 200.0);
+        }
+        if listening_only {
+            ui.label(
+                egui::RichText::new(
+                    "No announcement addresses are configured; these are the addresses the \
+                     node listens on.",
+                )
+                .small()
+                .color(egui::Color32::GRAY),
+            );
+        }
+    });
+}
+
+/// QR codes of the node id and, when its addresses are known, of its first node URI, for peers
+/// to scan when opening a channel.
+fn render_node_id_qr(
+    ui: &mut Ui, textures: &mut qr::QrTextures, node_id: &str, node_uri: Option<&str>,
+) {
+    ui.add_space(5.0);
+    ui.horizontal(|ui| {
+        ui.vertical(|ui| {
+            ui.label(egui::RichText::new("Node ID").small());
+            qr::show_qr(ui, textures, "node_id", node_id, 160.0);
+        });
+        if let Some(uri) = node_uri {
+            ui.add_space(10.0);
+            ui.vertical(|ui| {
+                ui.label(egui::RichText::new("Node URI").small());
+                qr::show_qr(ui, textures, "node_id_uri", uri, 160.0);
+            });
         }
     });
 }
 
-fn format_timestamp(ts: u64) -> String {
-    // Get current time - use js_sys on WASM, SystemTime on native
-    #[cfg(target_arch = "wasm32")]
-    let now_secs = (js_sys::Date::now() / 1000.0) as u64;
-
-    #[cfg(not(target_arch = "wasm32"))]
-    let now_secs = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
-
-    // Calculate how long ago
-    if now_secs >= ts {
-        let secs = now_secs - ts;
-        if secs < 60 {
-            format!("{} seconds ago", secs)
-        } else if secs < 3600 {
-            format!("{} minutes ago", secs / 60)
-        } else if secs < 86400 {
-            format!("{} hours ago", secs / 3600)
+/// Checklist of the node's periodic tasks, each with a dot colored by how long ago it last ran.
+fn render_health_checks(ui: &mut Ui, info: &GetNodeInfoResponse) {
+    let results = health::health_checks(info, unix_timestamp_now());
+    ui.horizontal(|ui| {
+        ui.strong("Health:");
+        if health::is_healthy(&results) {
+            ui.colored_label(egui::Color32::GREEN, "Healthy");
         } else {
-            format!("{} days ago", secs / 86400)
+            ui.colored_label(egui::Color32::YELLOW, "Attention needed");
         }
-    } else {
-        // Future timestamp or error - just show relative to epoch
-        format!("timestamp: {}", ts)
+    });
+    egui::Grid::new("node_health_grid").num_columns(2).spacing([6.0, 4.0]).show(ui, |ui| {
+        for result in &results {
+            let tooltip = match result.age_secs {
+                Some(age) => format!("Last done {}", format_age(age)),
+                None => "Not reported by the node, which may not use it".to_string(),
+            };
+            ui.colored_label(check_color(result.status), "●").on_hover_text(&tooltip);
+            ui.label(result.check.label()).on_hover_text(tooltip);
+            ui.end_row();
+        }
+    });
+}
+
+fn check_color(status: CheckStatus) -> egui::Color32 {
+    match status {
+        CheckStatus::Ok => egui::Color32::GREEN,
+        CheckStatus::Warning => egui::Color32::YELLOW,
+        CheckStatus::Failing => egui::Color32::RED,
+        CheckStatus::NotReported => egui::Color32::GRAY,
     }
 }
 
+fn render_lsps2_service_info(ui: &mut Ui, app: &LdkServerApp) {
+    let Some(settings) = &app.state.session.lsps2_service else {
+        return;
+    };
+
+    ui.add_space(10.0);
+    ui.group(|ui| {
+        ui.heading("LSPS2 Service");
+        ui.add_space(5.0);
+
+        egui::Grid::new("lsps2_service_info_grid").num_columns(2).spacing([10.0, 5.0]).show(
+            ui,
+            |ui| {
+                let yes_no = |value: bool| if value { "Yes" } else { "No" };
+                let rows = [
+                    ("Advertised:", yes_no(settings.advertise_service).to_string()),
+                    ("Opening Fee:", format!("{} ppm", settings.channel_opening_fee_ppm)),
+                    ("Min Opening Fee:", format_msat(settings.min_channel_opening_fee_msat)),
+                    (
+                        "Over-provisioning:",
+                        format!("{} ppm", settings.channel_over_provisioning_ppm),
+                    ),
+                    (
+                        "Min Channel Lifetime:",
+                        format!("{} blocks", settings.min_channel_lifetime),
+                    ),
+                    (
+                        "Max Client to_self_delay:",
+                        format!("{} blocks", settings.max_client_to_self_delay),
+                    ),
+                    (
+                        "Payment Size:",
+                        format!(
+                            "{} – {}",
+                            format_msat(settings.min_payment_size_msat),
+                            format_msat(settings.max_payment_size_msat)
+                        ),
+                    ),
+                    ("Client Trusts LSP:", yes_no(settings.client_trusts_lsp).to_string()),
+                ];
+                for (label, value) in rows {
+                    ui.label(label);
+                    ui.monospace(value);
+                    ui.end_row();
+                }
+
+                ui.label("Required Token:");
+                match &settings.require_token {
+                    Some(token) => {
+                        ui.horizontal(|ui| {
+                            ui.monospace("********");
+                            copy_button(ui, "Copy", || token.clone());
+                        });
+                    }
+                    None => {
+                        ui.label("None");
+                    }
+                }
+                ui.end_row();
+            },
+        );
+    });
+}
+
 fn render_chain_source_info(ui: &mut Ui, app: &LdkServerApp) {
     // Only show if we have chain source info from config
-    if matches!(app.state.chain_source, ChainSourceConfig::None) && app.state.network.is_empty() {
+    if matches!(app.state.session.chain_source, ChainSourceConfig::None)
+        && app.state.session.network.is_empty()
+    {
         return;
     }
 
@@ -128,13 +281,13 @@ fn render_chain_source_info(ui: &mut Ui, app: &LdkServerApp) {
         ui.add_space(5.0);
 
         egui::Grid::new("chain_source_grid").num_columns(2).spacing([10.0, 5.0]).show(ui, |ui| {
-            if !app.state.network.is_empty() {
+            if !app.state.session.network.is_empty() {
                 ui.label("Network:");
-                ui.monospace(&app.state.network);
+                ui.monospace(&app.state.session.network);
                 ui.end_row();
             }
 
-            match &app.state.chain_source {
+            match &app.state.session.chain_source {
                 ChainSourceConfig::None => {}
                 ChainSourceConfig::Bitcoind { rpc_address, rpc_user, rpc_password } => {
                     ui.label("Chain Source:");
@@ -144,27 +297,21 @@ fn render_chain_source_info(ui: &mut Ui, app: &LdkServerApp) {
                     ui.label("RPC Address:");
                     ui.horizontal(|ui| {
                         ui.monospace(rpc_address);
-                        if ui.small_button("Copy").clicked() {
-                            ui.output_mut(|o| o.copied_text = rpc_address.clone());
-                        }
+                        copy_button(ui, "Copy", || rpc_address.clone());
                     });
                     ui.end_row();
 
                     ui.label("RPC User:");
                     ui.horizontal(|ui| {
                         ui.monospace(rpc_user);
-                        if ui.small_button("Copy").clicked() {
-                            ui.output_mut(|o| o.copied_text = rpc_user.clone());
-                        }
+                        copy_button(ui, "Copy", || rpc_user.clone());
                     });
                     ui.end_row();
 
                     ui.label("RPC Password:");
                     ui.horizontal(|ui| {
                         ui.monospace("********");
-                        if ui.small_button("Copy").clicked() {
-                            ui.output_mut(|o| o.copied_text = rpc_password.clone());
-                        }
+                        copy_button(ui, "Copy", || rpc_password.clone());
                     });
                     ui.end_row();
                 }
@@ -176,9 +323,7 @@ fn render_chain_source_info(ui: &mut Ui, app: &LdkServerApp) {
                     ui.label("Server URL:");
                     ui.horizontal(|ui| {
                         ui.monospace(server_url);
-                        if ui.small_button("Copy").clicked() {
-                            ui.output_mut(|o| o.copied_text = server_url.clone());
-                        }
+                        copy_button(ui, "Copy", || server_url.clone());
                     });
                     ui.end_row();
                 }
@@ -190,9 +335,7 @@ fn render_chain_source_info(ui: &mut Ui, app: &LdkServerApp) {
                     ui.label("Server URL:");
                     ui.horizontal(|ui| {
                         ui.monospace(server_url);
-                        if ui.small_button("Copy").clicked() {
-                            ui.output_mut(|o| o.copied_text = server_url.clone());
-                        }
+                        copy_button(ui, "Copy", || server_url.clone());
                     });
                     ui.end_row();
                 }
@@ -1,16 +1,38 @@
-use egui::{ScrollArea, Ui};
+use egui::{Context, ScrollArea, Ui};
 #[cfg(target_arch = "wasm32")]
 use web_sys::js_sys;
 
+use crate::address::{self, AddressType};
+use crate::amount::{self, AmountUnit};
 use crate::app::LdkServerApp;
-use crate::state::{ConnectionStatus, OnchainTab};
-use crate::ui::{format_sats, truncate_id};
+use crate::bip21;
+use crate::export;
+use crate::fees::{self, FeeRateWarning, FeeWarning, ESTIMATED_TX_VBYTES, FEE_ESTIMATES_TTL_SECS};
+use crate::network::Network;
+use crate::state::{
+    ActiveTab, AddressBookEntry, AddressBookForm, ConnectionStatus, GeneratedAddress, LightningTab,
+    OnchainTab, StatusMessage, TaskKind,
+};
+use crate::ui::{
+    copy_button, copy_button_large, data_age, format_age, format_sats, qr, refresh_button,
+    truncate_id, txid_link, unix_timestamp_now,
+};
 
 pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
-    ui.heading("On-chain Transactions");
+    ui.horizontal(|ui| {
+        ui.heading("On-chain Transactions");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            let mut unit = app.state.onchain_unit;
+            for option in AmountUnit::ALL.iter().rev() {
+                ui.selectable_value(&mut unit, *option, option.label());
+            }
+            ui.label("Unit:");
+            app.set_onchain_unit(unit);
+        });
+    });
     ui.add_space(10.0);
 
-    if !matches!(app.state.connection_status, ConnectionStatus::Connected) {
+    if !matches!(app.state.session.connection_status, ConnectionStatus::Connected) {
         ui.label("Connect to a server to use on-chain transactions.");
         return;
     }
@@ -25,7 +47,7 @@ pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
         if ui.selectable_label(app.state.onchain_tab == OnchainTab::History, "History").clicked() {
             app.state.onchain_tab = OnchainTab::History;
             // Fetch payments if not already loaded
-            if app.state.payments.is_none() {
+            if app.state.session.payments.is_none() {
                 app.fetch_payments();
             }
         }
@@ -41,27 +63,116 @@ pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
     }
 }
 
+/// Balances older than this are refetched while the Send tab is open.
+const BALANCES_TTL_SECS: u64 = 60;
+
 fn render_send(ui: &mut Ui, app: &mut LdkServerApp) {
+    let now = unix_timestamp_now();
+    let estimates_stale = app
+        .state
+        .fee_estimates_requested_at
+        .map_or(true, |t| now.saturating_sub(t) >= FEE_ESTIMATES_TTL_SECS);
+    if estimates_stale {
+        app.fetch_fee_estimates();
+    }
+    let balances_stale =
+        app.state.session.balances_requested_at.map_or(true, |t| {
+            now.saturating_sub(t) >= BALANCES_TTL_SECS
+        });
+    if balances_stale {
+        app.fetch_balances();
+    }
+
     ui.group(|ui| {
         ui.heading("Send On-chain");
         ui.add_space(5.0);
 
+        let unit = app.state.onchain_unit;
+        let amount_error = match app.validate_onchain_send_amount() {
+            Err(e) if !app.state.forms.onchain_send.amount.trim().is_empty() => Some(e),
+            _ => None,
+        };
+        let max_amount = max_send_amount(app);
+        let dust_warning = if amount_error.is_none() { dust_warning(app) } else { None };
+        let network = app.state.session.network.clone();
+        let chain_network = app.state.session.chain_network();
+        let address_book = &app.state.address_book;
         let form = &mut app.state.forms.onchain_send;
+        let mut open_drain_dialog = false;
+        let mut open_address_book = false;
+        let mut send_all_enabled = false;
 
         egui::Grid::new("onchain_send_grid")
             .num_columns(2)
             .spacing([10.0, 5.0])
             .show(ui, |ui| {
                 ui.label("Address:");
-                ui.text_edit_singleline(&mut form.address);
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut form.address);
+                    let entries: Vec<_> =
+                        address_book.iter().filter(|e| e.network == network).collect();
+                    egui::ComboBox::from_id_salt("address_book_picker")
+                        .selected_text("Address book")
+                        .show_ui(ui, |ui| {
+                            if entries.is_empty() {
+                                ui.label("No saved addresses");
+                            }
+                            for entry in entries {
+                                let text = format!("{} ({})", entry.label, truncate_id(&entry.address, 6, 6));
+                                if ui.selectable_label(form.address == entry.address, text).clicked() {
+                                    form.address = entry.address.clone();
+                                }
+                            }
+                        });
+                    if ui.small_button("Manage...").clicked() {
+                        open_address_book = true;
+                    }
+                });
                 ui.end_row();
 
-                ui.label("Amount (sats):");
-                ui.add_enabled(!form.send_all, egui::TextEdit::singleline(&mut form.amount_sats));
+                if let Some(network) = chain_network {
+                    if !address::is_for_network(&form.address, network) {
+                        ui.label("");
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!("Address is not a {} address", network),
+                        );
+                        ui.end_row();
+                    }
+                }
+
+                ui.label(format!("Amount ({}):", unit.label()));
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.add_enabled(!form.send_all, egui::TextEdit::singleline(&mut form.amount));
+                        let max_button = ui
+                            .add_enabled(!form.send_all && max_amount.is_some(), egui::Button::new("Max"))
+                            .on_hover_text("Spendable balance minus the estimated fee")
+                            .on_disabled_hover_text("Needs balances and a fee rate");
+                        if max_button.clicked() {
+                            if let Some((sats, rate)) = max_amount {
+                                form.amount = amount::format_amount_input(sats, unit);
+                                form.fee_rate_sat_per_vb = rate.to_string();
+                            }
+                        }
+                    });
+                    if let Some(e) = &amount_error {
+                        ui.colored_label(egui::Color32::RED, e);
+                    }
+                    if let Some(warning) = &dust_warning {
+                        ui.colored_label(egui::Color32::YELLOW, warning);
+                    }
+                });
                 ui.end_row();
 
                 ui.label("Send All:");
-                ui.checkbox(&mut form.send_all, "Send entire balance");
+                ui.horizontal(|ui| {
+                    let response = ui.checkbox(&mut form.send_all, "Send entire balance");
+                    send_all_enabled = response.changed() && form.send_all;
+                    if ui.small_button("Drain wallet...").clicked() {
+                        open_drain_dialog = true;
+                    }
+                });
                 ui.end_row();
 
                 ui.label("Fee Rate (sat/vB, optional):");
@@ -69,15 +180,46 @@ fn render_send(ui: &mut Ui, app: &mut LdkServerApp) {
                 ui.end_row();
             });
 
+        if open_drain_dialog {
+            open_drain_wallet_dialog(app);
+        }
+        if open_address_book {
+            app.state.forms.address_book = Default::default();
+            app.state.show_address_book_dialog = true;
+        }
+        if send_all_enabled && app.state.session.balances.is_none() {
+            app.fetch_balances();
+        }
+
+        apply_pasted_uri(app);
+        render_uri_context(ui, app);
+        render_send_all_estimate(ui, app);
+        render_fee_estimate(ui, app);
+
+        render_fee_presets(ui, app, now);
+
         ui.add_space(10.0);
 
         ui.horizontal(|ui| {
-            let is_pending = app.state.tasks.onchain_send.is_some();
-            if is_pending {
+            if app.state.session.tasks.is_pending(TaskKind::OnchainSend) {
                 ui.spinner();
                 ui.label("Sending...");
+                let hint = "Stop waiting for the server. The transaction may still be sent.";
+                if ui.small_button("Cancel").on_hover_text(hint).clicked() {
+                    app.state.session.tasks.abort(TaskKind::OnchainSend);
+                    app.state.status_message = Some(StatusMessage::success(
+                        "Stopped waiting for the send; check the on-chain balance before retrying",
+                    ));
+                }
             } else if ui.button("Send").clicked() {
-                app.send_onchain();
+                if app.state.forms.onchain_send.address.trim().is_empty() {
+                    app.state.status_message = Some(StatusMessage::error("Address is required"));
+                } else if let Err(e) = app.validate_onchain_send_amount() {
+                    app.state.status_message = Some(StatusMessage::error(e));
+                } else {
+                    app.state.dust_acknowledged = false;
+                    app.state.show_onchain_send_confirm_dialog = true;
+                }
             }
         });
 
@@ -86,13 +228,666 @@ fn render_send(ui: &mut Ui, app: &mut LdkServerApp) {
             ui.separator();
             ui.horizontal(|ui| {
                 ui.label("Last TXID:");
-                ui.monospace(crate::ui::truncate_id(txid, 12, 12));
-                if ui.small_button("Copy").clicked() {
-                    ui.output_mut(|o| o.copied_text = txid.clone());
+                txid_link(ui, app.state.session.chain_network().unwrap_or_default(), txid, 12, 12);
+                copy_button(ui, "Copy", || txid.clone());
+            });
+            render_tx_watch(ui, app);
+            render_save_to_address_book(ui, app);
+        }
+    });
+}
+
+/// Offer to save the destination of the last send if it isn't in the address book yet.
+fn render_save_to_address_book(ui: &mut Ui, app: &mut LdkServerApp) {
+    let Some(address) = &app.state.last_sent_address else {
+        return;
+    };
+    let network = &app.state.session.network;
+    let saved =
+        app.state.address_book.iter().any(|e| &e.address == address && &e.network == network);
+    if saved || address.is_empty() {
+        return;
+    }
+
+    if ui.small_button("Save to address book").clicked() {
+        app.state.forms.address_book =
+            AddressBookForm { address: address.clone(), ..Default::default() };
+        app.state.show_address_book_dialog = true;
+    }
+}
+
+/// Confirmation status of the last sent transaction, with controls for the watcher.
+fn render_tx_watch(ui: &mut Ui, app: &mut LdkServerApp) {
+    let mut start = false;
+    let mut stop = false;
+
+    ui.horizontal(|ui| match &app.state.tx_watch {
+        Some(watch) => {
+            ui.label(format!("Status: {}", watch.status_text()));
+            if watch.is_done() {
+                return;
+            }
+            if app.state.session.tasks.is_pending(TaskKind::TxConfirmations) {
+                ui.spinner();
+            }
+            if ui.small_button("Stop watching").clicked() {
+                stop = true;
+            }
+            if let Some(error) = &watch.error {
+                ui.label(
+                    egui::RichText::new(format!("Status unavailable: {}", error))
+                        .small()
+                        .color(egui::Color32::GRAY),
+                );
+            }
+        }
+        None if app.tx_watch_base_url().is_some() => {
+            if ui.small_button("Watch confirmations").clicked() {
+                start = true;
+            }
+        }
+        None => {
+            ui.label(
+                egui::RichText::new("Confirmation status needs an Esplora chain source")
+                    .small()
+                    .color(egui::Color32::GRAY),
+            );
+        }
+    });
+
+    if start {
+        app.watch_last_txid();
+    }
+    if stop {
+        app.stop_tx_watch();
+    }
+}
+
+/// Warning for amounts below the dust threshold of the destination address type.
+fn dust_warning(app: &LdkServerApp) -> Option<String> {
+    let form = &app.state.forms.onchain_send;
+    if form.send_all {
+        return None;
+    }
+    let sats = amount::parse_amount(&form.amount, app.state.onchain_unit).ok()?;
+    if !address::is_dust(&form.address, sats) {
+        return None;
+    }
+    Some(format!(
+        "Warning: amount is below the dust threshold of {} sats for this address type. \
+         The transaction may be rejected or create an uneconomical output.",
+        address::dust_threshold_sats(&form.address)
+    ))
+}
+
+/// The largest amount that leaves room for the estimated fee, along with the fee rate used.
+/// Uses the entered fee rate, falling back to the half hour estimate.
+fn max_send_amount(app: &LdkServerApp) -> Option<(u64, u64)> {
+    let spendable = app.state.session.balances.as_ref()?.data.spendable_onchain_balance_sats;
+    let rate = match app.state.forms.onchain_send.fee_rate_sat_per_vb.trim().parse::<u64>() {
+        Ok(rate) => rate,
+        Err(_) => app.state.fee_estimates.as_ref()?.fees.half_hour_fee,
+    };
+    let sats = spendable.checked_sub(fees::estimate_fee_sats(rate)).filter(|s| *s > 0)?;
+    Some((sats, rate))
+}
+
+fn render_send_all_estimate(ui: &mut Ui, app: &LdkServerApp) {
+    let form = &app.state.forms.onchain_send;
+    if !form.send_all {
+        return;
+    }
+
+    let Some(balances) = app.state.session.balances.as_ref().map(|b| &b.data) else {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.label("Fetching balances to estimate the amount...");
+        });
+        return;
+    };
+    let unit = app.state.onchain_unit;
+    let spendable = balances.spendable_onchain_balance_sats;
+
+    match form.fee_rate_sat_per_vb.trim().parse::<u64>() {
+        Ok(rate) => {
+            let fee_sats = fees::estimate_fee_sats(rate);
+            ui.label(format!(
+                "Will send ≈ {} − {} fee = {}",
+                amount::format_amount(spendable, unit),
+                amount::format_amount(fee_sats, unit),
+                amount::format_amount(spendable.saturating_sub(fee_sats), unit)
+            ));
+        }
+        Err(_) => {
+            ui.label(format!(
+                "Will send ≈ {} minus the transaction fee (enter a fee rate for an estimate)",
+                amount::format_amount(spendable, unit)
+            ));
+        }
+    }
+}
+
+fn render_fee_estimate(ui: &mut Ui, app: &LdkServerApp) {
+    let form = &app.state.forms.onchain_send;
+    let Ok(fee_rate) = form.fee_rate_sat_per_vb.trim().parse::<u64>() else {
+        return;
+    };
+
+    let fee_sats = fees::estimate_fee_sats(fee_rate);
+    ui.label(
+        egui::RichText::new(format!(
+            "Estimated fee ≈ {} (estimate, assumes ~{} vB)",
+            format_sats(fee_sats),
+            ESTIMATED_TX_VBYTES
+        ))
+        .small()
+        .color(egui::Color32::GRAY),
+    );
+
+    let amount_sats = if form.send_all {
+        None
+    } else {
+        amount::parse_amount(&form.amount, app.state.onchain_unit).ok()
+    };
+    let spendable_sats =
+        app.state.session.balances.as_ref().map(|b| b.data.spendable_onchain_balance_sats);
+    for warning in fees::fee_warnings(amount_sats, fee_sats, spendable_sats) {
+        let text = match warning {
+            FeeWarning::HighRelativeFee { percent } => {
+                format!("Warning: estimated fee is {}% of the amount sent", percent)
+            }
+            FeeWarning::ExceedsSpendable { shortfall_sats } => format!(
+                "Warning: amount plus estimated fee exceeds spendable balance by {}",
+                format_sats(shortfall_sats)
+            ),
+        };
+        ui.colored_label(egui::Color32::YELLOW, text);
+    }
+
+    if let Some(estimates) = &app.state.fee_estimates {
+        let text = match fees::fee_rate_warning(fee_rate, &estimates.fees) {
+            Some(FeeRateWarning::TooLow { economy_fee }) => format!(
+                "Warning: {} sat/vB is far below the current economy estimate of {} sat/vB. \
+                 The transaction may take days to confirm.",
+                fee_rate, economy_fee
+            ),
+            Some(FeeRateWarning::TooHigh { fastest_fee }) => format!(
+                "Warning: {} sat/vB is far above the fastest estimate of {} sat/vB. \
+                 You may be overpaying.",
+                fee_rate, fastest_fee
+            ),
+            None => return,
+        };
+        ui.colored_label(egui::Color32::YELLOW, text);
+    }
+}
+
+/// Replace a pasted BIP21 URI in the address field with its address, prefilling the amount.
+fn apply_pasted_uri(app: &mut LdkServerApp) {
+    let unit = app.state.onchain_unit;
+    let form = &mut app.state.forms.onchain_send;
+
+    if bip21::is_bip21_uri(&form.address) {
+        match bip21::parse_uri(&form.address) {
+            Ok(uri) => {
+                form.address = uri.address.clone();
+                if let Some(sats) = uri.amount_sats {
+                    form.amount = amount::format_amount_input(sats, unit);
+                    form.send_all = false;
+                }
+                form.uri = Some(uri);
+            }
+            Err(e) => {
+                app.state.status_message =
+                    Some(StatusMessage::error(format!("Invalid bitcoin URI: {}", e)));
+            }
+        }
+    } else if form.uri.as_ref().is_some_and(|uri| uri.address != form.address.trim()) {
+        // The address was edited by hand, so the URI details no longer apply
+        form.uri = None;
+    }
+}
+
+fn render_uri_context(ui: &mut Ui, app: &mut LdkServerApp) {
+    let Some(uri) = &app.state.forms.onchain_send.uri else {
+        return;
+    };
+
+    let mut use_lightning = None;
+    ui.add_space(5.0);
+    egui::Grid::new("onchain_send_uri_grid").num_columns(2).spacing([10.0, 5.0]).show(ui, |ui| {
+        if let Some(label) = &uri.label {
+            ui.label("Label:");
+            ui.label(egui::RichText::new(label).italics());
+            ui.end_row();
+        }
+        if let Some(message) = &uri.message {
+            ui.label("Message:");
+            ui.label(egui::RichText::new(message).italics());
+            ui.end_row();
+        }
+        if let Some(invoice) = &uri.lightning {
+            ui.label("Lightning:");
+            if ui.button("Pay via Lightning instead").clicked() {
+                use_lightning = Some(invoice.clone());
+            }
+            ui.end_row();
+        }
+    });
+
+    if let Some(invoice) = use_lightning {
+        app.state.forms.bolt11_send.invoice = invoice;
+        app.state.lightning_tab = LightningTab::Bolt11Send;
+        app.state.active_tab = ActiveTab::Lightning;
+    }
+}
+
+pub fn render_dialogs(ctx: &Context, app: &mut LdkServerApp) {
+    render_send_confirm_dialog(ctx, app);
+    render_drain_wallet_dialog(ctx, app);
+    render_address_book_dialog(ctx, app);
+}
+
+fn render_address_book_dialog(ctx: &Context, app: &mut LdkServerApp) {
+    if !app.state.show_address_book_dialog {
+        return;
+    }
+
+    let mut edit = None;
+    let mut delete = None;
+    let mut save = false;
+    let mut close = false;
+
+    egui::Window::new("Address Book")
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let network = &app.state.session.network;
+            let entries: Vec<_> = app
+                .state
+                .address_book
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| &e.network == network)
+                .collect();
+
+            if entries.is_empty() {
+                ui.label("No saved addresses for this network.");
+            } else {
+                egui::Grid::new("address_book_grid").striped(true).spacing([10.0, 4.0]).show(ui, |ui| {
+                    ui.strong("Label");
+                    ui.strong("Address");
+                    ui.strong("");
+                    ui.end_row();
+
+                    for (index, entry) in entries {
+                        ui.label(&entry.label);
+                        ui.monospace(truncate_id(&entry.address, 10, 10));
+                        ui.horizontal(|ui| {
+                            if ui.small_button("Edit").clicked() {
+                                edit = Some(index);
+                            }
+                            if ui.small_button("Delete").clicked() {
+                                delete = Some(index);
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            }
+
+            ui.add_space(10.0);
+            ui.separator();
+
+            let form = &mut app.state.forms.address_book;
+            ui.label(if form.editing.is_some() { "Edit Entry" } else { "Add Entry" });
+            egui::Grid::new("address_book_form_grid").num_columns(2).spacing([10.0, 5.0]).show(ui, |ui| {
+                ui.label("Label:");
+                ui.text_edit_singleline(&mut form.label);
+                ui.end_row();
+
+                ui.label("Address:");
+                ui.text_edit_singleline(&mut form.address);
+                ui.end_row();
+            });
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                let label = if form.editing.is_some() { "Save" } else { "Add" };
+                if ui.button(label).clicked() {
+                    save = true;
+                }
+                if form.editing.is_some() && ui.button("Cancel Edit").clicked() {
+                    *form = Default::default();
+                }
+                if ui.button("Close").clicked() {
+                    close = true;
                 }
             });
+        });
+
+    if let Some(index) = edit {
+        let entry = &app.state.address_book[index];
+        app.state.forms.address_book = AddressBookForm {
+            label: entry.label.clone(),
+            address: entry.address.clone(),
+            editing: Some(index),
+        };
+    }
+    if let Some(index) = delete {
+        app.state.address_book.remove(index);
+        app.state.forms.address_book = Default::default();
+        app.address_book_changed();
+    }
+    if save {
+        save_address_book_entry(app);
+    }
+    if close {
+        app.state.show_address_book_dialog = false;
+    }
+}
+
+fn save_address_book_entry(app: &mut LdkServerApp) {
+    let form = &app.state.forms.address_book;
+    let label = form.label.trim().to_string();
+    let address = form.address.trim().to_string();
+    if label.is_empty() || address.is_empty() {
+        app.state.status_message = Some(StatusMessage::error("Label and address are required"));
+        return;
+    }
+
+    let network = app.state.session.network.clone();
+    let duplicate = app
+        .state
+        .address_book
+        .iter()
+        .enumerate()
+        .any(|(i, e)| Some(i) != form.editing && e.address == address && e.network == network);
+    if duplicate {
+        app.state.status_message = Some(StatusMessage::error("Address is already in the address book"));
+        return;
+    }
+
+    let entry = AddressBookEntry { label, address, network };
+    match form.editing {
+        Some(index) => app.state.address_book[index] = entry,
+        None => app.state.address_book.push(entry),
+    }
+    app.state.forms.address_book = Default::default();
+    app.address_book_changed();
+}
+
+fn open_drain_wallet_dialog(app: &mut LdkServerApp) {
+    // Keeping the reserve is the safe default whenever anchor channels may need it
+    let has_channels =
+        app.state.session.channels.as_ref().is_some_and(|c| !c.data.channels.is_empty());
+    let has_reserve =
+        app.state.session.balances.as_ref().is_some_and(|b| {
+            b.data.total_anchor_channels_reserve_sats > 0
+        });
+    app.state.drain_keep_reserve = has_channels || has_reserve;
+    app.state.show_drain_wallet_dialog = true;
+    app.fetch_balances();
+}
+
+fn render_drain_wallet_dialog(ctx: &Context, app: &mut LdkServerApp) {
+    if !app.state.show_drain_wallet_dialog {
+        return;
+    }
+
+    let fee_rate = app
+        .state
+        .forms
+        .onchain_send
+        .fee_rate_sat_per_vb
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .or_else(|| app.state.fee_estimates.as_ref().map(|e| e.fees.half_hour_fee));
+
+    let mut apply = None;
+    let mut close = false;
+
+    egui::Window::new("Drain Wallet")
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let Some(balances) = app.state.session.balances.as_ref().map(|b| &b.data) else {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Fetching balances...");
+                });
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+                return;
+            };
+            let unit = app.state.onchain_unit;
+            let spendable = balances.spendable_onchain_balance_sats;
+            let reserve = balances.total_anchor_channels_reserve_sats;
+
+            egui::Grid::new("drain_wallet_grid").num_columns(2).spacing([10.0, 5.0]).show(ui, |ui| {
+                ui.label("Total On-chain:");
+                ui.monospace(amount::format_amount(balances.total_onchain_balance_sats, unit));
+                ui.end_row();
+
+                ui.label("Spendable:");
+                ui.monospace(amount::format_amount(spendable, unit));
+                ui.end_row();
+
+                ui.label("Anchor Reserve:");
+                ui.monospace(amount::format_amount(reserve, unit));
+                ui.end_row();
+            });
+
+            ui.add_space(10.0);
+
+            ui.radio_value(
+                &mut app.state.drain_keep_reserve,
+                true,
+                "Keep the anchor reserve (recommended when channels are open)",
+            );
+            ui.radio_value(&mut app.state.drain_keep_reserve, false, "Full drain, including the reserve");
+
+            ui.add_space(5.0);
+
+            let keep_amount = fee_rate
+                .map(|rate| spendable.saturating_sub(fees::estimate_fee_sats(rate)));
+            if app.state.drain_keep_reserve {
+                match keep_amount {
+                    Some(drain_sats) => {
+                        ui.label(format!(
+                            "Will send ≈ {} (spendable balance minus the estimated fee). \
+                             The {} reserve stays in the wallet to cover anchor channel closures.",
+                            amount::format_amount(drain_sats, unit),
+                            amount::format_amount(reserve, unit)
+                        ));
+                    }
+                    None => {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            "Enter a fee rate on the send form to compute the amount.",
+                        );
+                    }
+                }
+            } else {
+                ui.label(
+                    "Will send the entire on-chain balance minus the transaction fee. \
+                     No reserve is retained.",
+                );
+                if reserve > 0 {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        "Warning: without the reserve, the node may be unable to fee-bump \
+                         anchor channel closures, and funds in those channels could be at risk.",
+                    );
+                }
+            }
+
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                let can_apply = !app.state.drain_keep_reserve || keep_amount.is_some_and(|a| a > 0);
+                if ui.add_enabled(can_apply, egui::Button::new("Use these settings")).clicked() {
+                    apply = Some(if app.state.drain_keep_reserve { keep_amount } else { None });
+                }
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+            });
+        });
+
+    if let Some(drain_amount) = apply {
+        let unit = app.state.onchain_unit;
+        let form = &mut app.state.forms.onchain_send;
+        match drain_amount {
+            Some(sats) => {
+                form.send_all = false;
+                form.amount = amount::format_amount_input(sats, unit);
+                if let Some(rate) = fee_rate {
+                    form.fee_rate_sat_per_vb = rate.to_string();
+                }
+            }
+            None => {
+                form.send_all = true;
+                form.amount.clear();
+            }
+        }
+        close = true;
+    }
+    if close {
+        app.state.show_drain_wallet_dialog = false;
+    }
+}
+
+fn render_send_confirm_dialog(ctx: &Context, app: &mut LdkServerApp) {
+    if !app.state.show_onchain_send_confirm_dialog {
+        return;
+    }
+
+    egui::Window::new("Confirm On-chain Send")
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let form = &app.state.forms.onchain_send;
+            let fee_rate = form.fee_rate_sat_per_vb.trim().parse::<u64>().ok();
+            let dust_warning = dust_warning(app);
+
+            ui.label("On-chain transactions cannot be reversed once broadcast.");
+            ui.add_space(5.0);
+
+            egui::Grid::new("onchain_send_confirm_grid")
+                .num_columns(2)
+                .spacing([10.0, 5.0])
+                .show(ui, |ui| {
+                    ui.label("Destination:");
+                    ui.monospace(form.address.trim());
+                    ui.end_row();
+
+                    ui.label("Amount:");
+                    if form.send_all {
+                        ui.strong("Entire spendable balance");
+                    } else {
+                        match amount::parse_amount(&form.amount, app.state.onchain_unit) {
+                            Ok(sats) => ui.strong(amount::format_amount(sats, app.state.onchain_unit)),
+                            Err(e) => ui.colored_label(egui::Color32::RED, e),
+                        };
+                    }
+                    ui.end_row();
+
+                    ui.label("Fee Rate:");
+                    match fee_rate {
+                        Some(rate) => ui.label(format!("{} sat/vB", rate)),
+                        None => ui.label("Node default"),
+                    };
+                    ui.end_row();
+
+                    if let Some(rate) = fee_rate {
+                        ui.label("Estimated Fee:");
+                        ui.label(format!(
+                            "~{} (assuming ~{} vB)",
+                            format_sats(fees::estimate_fee_sats(rate)),
+                            ESTIMATED_TX_VBYTES
+                        ));
+                        ui.end_row();
+                    }
+                });
+
+            ui.add_space(10.0);
+
+            if let Some(warning) = &dust_warning {
+                ui.colored_label(egui::Color32::YELLOW, warning);
+                ui.checkbox(&mut app.state.dust_acknowledged, "Send this dust amount anyway");
+                ui.add_space(10.0);
+            }
+
+            ui.horizontal(|ui| {
+                let can_confirm = dust_warning.is_none() || app.state.dust_acknowledged;
+                if ui.add_enabled(can_confirm, egui::Button::new("Confirm Send")).clicked() {
+                    app.state.show_onchain_send_confirm_dialog = false;
+                    app.send_onchain();
+                }
+                if ui.button("Cancel").clicked() {
+                    app.state.show_onchain_send_confirm_dialog = false;
+                }
+            });
+        });
+}
+
+fn render_fee_presets(ui: &mut Ui, app: &mut LdkServerApp, now: u64) {
+    let mut selected_rate = None;
+    let mut refresh = false;
+
+    ui.horizontal(|ui| {
+        ui.label("Presets:");
+        if let Some(estimates) = &app.state.fee_estimates {
+            for (label, rate) in estimates.fees.presets() {
+                if ui.small_button(format!("{} ({} sat/vB)", label, rate)).clicked() {
+                    selected_rate = Some(rate);
+                }
+            }
+            ui.label(
+                egui::RichText::new(format!(
+                    "updated {}",
+                    format_age(now.saturating_sub(estimates.fetched_at))
+                ))
+                .small()
+                .color(egui::Color32::GRAY),
+            );
+        }
+
+        if app.state.session.tasks.is_pending(TaskKind::FeeEstimates) {
+            ui.spinner();
+        } else {
+            if let Some(err) = &app.state.fee_estimates_error {
+                ui.label(egui::RichText::new(err).small().color(egui::Color32::GRAY));
+            }
+            if ui.small_button("Refresh").clicked() {
+                refresh = true;
+            }
         }
     });
+
+    egui::CollapsingHeader::new("Fee estimate source").default_open(false).show(ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("API URL:");
+            ui.text_edit_singleline(&mut app.state.mempool_api_url);
+        });
+        ui.label(
+            egui::RichText::new(
+                "mempool.space compatible API. Leave empty to use mempool.space for the configured network.",
+            )
+            .small()
+            .color(egui::Color32::GRAY),
+        );
+    });
+
+    if let Some(rate) = selected_rate {
+        app.state.forms.onchain_send.fee_rate_sat_per_vb = rate.to_string();
+    }
+    if refresh {
+        app.fetch_fee_estimates();
+    }
 }
 
 fn render_receive(ui: &mut Ui, app: &mut LdkServerApp) {
@@ -101,53 +896,212 @@ fn render_receive(ui: &mut Ui, app: &mut LdkServerApp) {
         ui.add_space(5.0);
 
         ui.horizontal(|ui| {
-            let is_pending = app.state.tasks.onchain_receive.is_some();
+            let is_pending = app.state.session.tasks.is_pending(TaskKind::OnchainReceive);
             if is_pending {
                 ui.spinner();
                 ui.label("Generating...");
-            } else if ui.button("Generate Address").clicked() {
-                app.generate_onchain_address();
+            } else {
+                if ui.button("Generate Address").clicked() {
+                    app.generate_onchain_address();
+                }
+                ui.label("Label (optional):");
+                ui.text_edit_singleline(&mut app.state.forms.onchain_receive.address_label);
             }
         });
 
+        let mut copied = false;
         if let Some(address) = &app.state.onchain_address {
             ui.add_space(10.0);
             ui.separator();
-            ui.label("Address:");
+            ui.horizontal(|ui| {
+                ui.label("Address:");
+                if let Some(address_type) = AddressType::detect(address) {
+                    ui.label(egui::RichText::new(address_type.label()).small().color(egui::Color32::GRAY));
+                }
+                let label = app
+                    .state
+                    .address_history
+                    .iter()
+                    .find(|a| &a.address == address)
+                    .map(|a| a.label.as_str())
+                    .filter(|l| !l.is_empty());
+                if let Some(label) = label {
+                    ui.label(egui::RichText::new(format!("\"{}\"", label)).small());
+                }
+            });
             ui.add(egui::TextEdit::singleline(&mut address.as_str())
                 .desired_width(f32::INFINITY)
                 .interactive(false));
-            if ui.button("Copy Address").clicked() {
-                ui.output_mut(|o| o.copied_text = address.clone());
+            if app.state.onchain_address_reused {
+                ui.label(
+                    egui::RichText::new(
+                        "This address has been shown or copied before. Reusing addresses reduces privacy.",
+                    )
+                    .small()
+                    .color(egui::Color32::YELLOW),
+                );
+            }
+            if copy_button_large(ui, "Copy Address", || address.clone()).clicked() {
+                copied = true;
+            }
+
+            ui.add_space(10.0);
+            let form = &mut app.state.forms.onchain_receive;
+            egui::Grid::new("onchain_receive_uri_grid")
+                .num_columns(2)
+                .spacing([10.0, 5.0])
+                .show(ui, |ui| {
+                    ui.label(format!("Amount ({}, optional):", app.state.onchain_unit.label()));
+                    ui.text_edit_singleline(&mut form.amount);
+                    ui.end_row();
+
+                    ui.label("Label (optional):");
+                    ui.text_edit_singleline(&mut form.label);
+                    ui.end_row();
+                });
+            let amount_sats = amount::parse_amount(&form.amount, app.state.onchain_unit).ok();
+            let uri = bip21::build_uri(address, amount_sats, Some(&form.label));
+
+            if copy_button_large(ui, "Copy as URI", || uri.clone()).clicked() {
+                copied = true;
             }
+
+            ui.add_space(5.0);
+            qr::show_qr(ui, &mut app.state.qr_textures, "onchain_address", &uri, 200.0);
+        }
+        if copied {
+            mark_address_copied(app);
         }
     });
+
+    ui.add_space(10.0);
+    render_address_history(ui, app);
+}
+
+fn mark_address_copied(app: &mut LdkServerApp) {
+    let Some(address) = &app.state.onchain_address else {
+        return;
+    };
+    if let Some(entry) = app.state.address_history.iter_mut().find(|a| &a.address == address) {
+        entry.copied = true;
+        app.address_history_changed();
+    }
+}
+
+fn render_address_history(ui: &mut Ui, app: &mut LdkServerApp) {
+    if app.state.address_history.is_empty() {
+        return;
+    }
+
+    let now = unix_timestamp_now();
+    let mut show_address = None;
+    let mut changed = false;
+    let mut save_csv = false;
+
+    egui::CollapsingHeader::new(format!(
+        "Previously Generated Addresses ({})",
+        app.state.address_history.len()
+    ))
+    .default_open(false)
+    .show(ui, |ui| {
+        ui.horizontal(|ui| {
+            let history = &app.state.address_history;
+            copy_button(ui, "Copy CSV", || address_history_csv(history))
+                .on_hover_text("Copy the address list as CSV");
+            save_csv = ui
+                .small_button("Export CSV…")
+                .on_hover_text("Save the address list as a CSV file")
+                .clicked();
+        });
+
+        egui::Grid::new("address_history_grid").striped(true).spacing([10.0, 4.0]).show(ui, |ui| {
+            ui.strong("Address");
+            ui.strong("Type");
+            ui.strong("Generated");
+            ui.strong("Label");
+            ui.strong("");
+            ui.end_row();
+
+            for entry in app.state.address_history.iter_mut().rev() {
+                ui.monospace(truncate_id(&entry.address, 10, 8));
+                ui.label(AddressType::detect(&entry.address).map_or("Unknown", |t| t.short_label()));
+                ui.label(format_age(now.saturating_sub(entry.created_at)));
+                if ui.text_edit_singleline(&mut entry.label).changed() {
+                    changed = true;
+                }
+                ui.horizontal(|ui| {
+                    if ui.small_button("Show").clicked() {
+                        show_address = Some(entry.address.clone());
+                    }
+                    if copy_button(ui, "Copy", || entry.address.clone()).clicked() {
+                        entry.copied = true;
+                        changed = true;
+                    }
+                });
+                ui.end_row();
+            }
+        });
+    });
+
+    if let Some(address) = show_address {
+        app.show_onchain_address(address);
+    } else if changed {
+        app.address_history_changed();
+    }
+    if save_csv {
+        let history = app.state.address_history.clone();
+        let name = export::file_name("addresses", "csv", now);
+        app.save_text_file(name, move || Ok(address_history_csv(&history)));
+    }
+}
+
+/// The address history as CSV with an `address,type,label,created_at` header.
+fn address_history_csv(history: &[GeneratedAddress]) -> String {
+    let mut csv = String::from("address,type,label,created_at\n");
+    for entry in history {
+        let address_type = AddressType::detect(&entry.address).map_or("", |t| t.short_label());
+        csv.push_str(&format!(
+            "{},{},\"{}\",{}\n",
+            entry.address,
+            address_type,
+            entry.label.replace('"', "\"\""),
+            entry.created_at
+        ));
+    }
+    csv
 }
 
 fn render_history(ui: &mut Ui, app: &mut LdkServerApp) {
     ui.heading("On-chain History");
     ui.add_space(10.0);
 
+    let unit = app.state.onchain_unit;
+
     // Show balances summary
-    if let Some(balances) = &app.state.balances {
+    if let Some(balances) = app.state.session.balances.as_ref().map(|b| &b.data) {
         ui.group(|ui| {
-            ui.label("Wallet Summary");
+            ui.horizontal(|ui| {
+                ui.label("Wallet Summary");
+                if app.state.session.balance_refresh.is_some() {
+                    ui.label(egui::RichText::new("updating…").small().color(egui::Color32::GRAY));
+                }
+            });
             ui.add_space(5.0);
             egui::Grid::new("onchain_summary_grid")
                 .num_columns(2)
                 .spacing([10.0, 4.0])
                 .show(ui, |ui| {
                     ui.label("Total Balance:");
-                    ui.label(format!("{} sats", format_sats(balances.total_onchain_balance_sats)));
+                    ui.label(amount::format_amount(balances.total_onchain_balance_sats, unit));
                     ui.end_row();
 
                     ui.label("Spendable:");
-                    ui.label(format!("{} sats", format_sats(balances.spendable_onchain_balance_sats)));
+                    ui.label(amount::format_amount(balances.spendable_onchain_balance_sats, unit));
                     ui.end_row();
 
                     if balances.total_anchor_channels_reserve_sats > 0 {
                         ui.label("Anchor Reserve:");
-                        ui.label(format!("{} sats", format_sats(balances.total_anchor_channels_reserve_sats)));
+                        ui.label(amount::format_amount(balances.total_anchor_channels_reserve_sats, unit));
                         ui.end_row();
                     }
                 });
@@ -162,7 +1116,8 @@ fn render_history(ui: &mut Ui, app: &mut LdkServerApp) {
                 ui.add_space(5.0);
                 for sweep in &balances.pending_balances_from_channel_closures {
                     if let Some(balance_type) = &sweep.balance_type {
-                        render_pending_sweep(ui, balance_type);
+                        let network = app.state.session.chain_network().unwrap_or_default();
+                        render_pending_sweep(ui, balance_type, unit, network);
                         ui.add_space(3.0);
                     }
                 }
@@ -171,34 +1126,24 @@ fn render_history(ui: &mut Ui, app: &mut LdkServerApp) {
         }
     }
 
-    // Note about transaction history
-    ui.group(|ui| {
-        ui.label(egui::RichText::new("Transaction History").strong());
-        ui.add_space(5.0);
-        ui.label("Full on-chain transaction history is not yet available.");
-        ui.label(
-            egui::RichText::new("ldk-node does not currently expose BDK wallet transaction history.")
-                .small()
-                .color(egui::Color32::GRAY),
-        );
+    render_history_table(ui, app);
 
-        if let Some(txid) = &app.state.last_txid {
-            ui.add_space(10.0);
-            ui.separator();
-            ui.horizontal(|ui| {
-                ui.label("Last Sent TXID:");
-                ui.monospace(truncate_id(txid, 8, 8));
-                if ui.small_button("Copy").clicked() {
-                    ui.output_mut(|o| o.copied_text = txid.clone());
-                }
-            });
-        }
-    });
+    if let Some(txid) = &app.state.last_txid {
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.label("Last Sent TXID:");
+            txid_link(ui, app.state.session.chain_network().unwrap_or_default(), txid, 8, 8);
+            copy_button(ui, "Copy", || txid.clone());
+        });
+        render_tx_watch(ui, app);
+    }
 }
 
 fn render_pending_sweep(
     ui: &mut Ui,
     balance_type: &ldk_server_client::ldk_server_protos::types::pending_sweep_balance::BalanceType,
+    unit: AmountUnit,
+    network: Network,
 ) {
     use ldk_server_client::ldk_server_protos::types::pending_sweep_balance::BalanceType;
 
@@ -206,47 +1151,59 @@ fn render_pending_sweep(
         BalanceType::PendingBroadcast(b) => {
             ui.horizontal(|ui| {
                 ui.colored_label(egui::Color32::YELLOW, "Pending Broadcast");
-                ui.label(format!("{} sats", format_sats(b.amount_satoshis)));
+                ui.label(amount::format_amount(b.amount_satoshis, unit));
             });
         }
         BalanceType::BroadcastAwaitingConfirmation(b) => {
             ui.horizontal(|ui| {
                 ui.colored_label(egui::Color32::YELLOW, "Awaiting Confirmation");
-                ui.label(format!("{} sats", format_sats(b.amount_satoshis)));
+                ui.label(amount::format_amount(b.amount_satoshis, unit));
             });
             ui.horizontal(|ui| {
                 ui.label("TXID:");
-                ui.monospace(truncate_id(&b.latest_spending_txid, 8, 8));
-                if ui.small_button("Copy").clicked() {
-                    ui.output_mut(|o| o.copied_text = b.latest_spending_txid.clone());
-                }
+                txid_link(ui, network, &b.latest_spending_txid, 8, 8);
+                copy_button(ui, "Copy", || b.latest_spending_txid.clone());
             });
         }
         BalanceType::AwaitingThresholdConfirmations(b) => {
             ui.horizontal(|ui| {
                 ui.colored_label(egui::Color32::GREEN, "Awaiting Threshold");
-                ui.label(format!("{} sats (height {})", format_sats(b.amount_satoshis), b.confirmation_height));
+                ui.label(format!("{} (height {})", amount::format_amount(b.amount_satoshis, unit), b.confirmation_height));
             });
         }
     }
 }
 
-#[allow(dead_code)]
 fn render_history_table(ui: &mut Ui, app: &mut LdkServerApp) {
-    // This function is kept for future use when ldk-node exposes transaction history
+    let unit = app.state.onchain_unit;
+    let network = app.state.session.chain_network().unwrap_or_default();
     ui.horizontal(|ui| {
         ui.heading("Transaction History");
-        if app.state.tasks.payments.is_some() {
+        if app.state.session.tasks.is_pending(TaskKind::Payments) {
             ui.spinner();
-        } else if ui.button("Refresh").clicked() {
-            app.state.payments_page_token = None;
-            app.fetch_payments();
+        } else {
+            if refresh_button(ui, &app.state.session.refresh, TaskKind::Payments) {
+                app.state.session.payments_page_token = None;
+                app.fetch_payments();
+            }
+            if app.state.session.payments_page_token.is_some() && ui.button("Load More").clicked() {
+                app.fetch_payments();
+            }
         }
+        data_age(ui, app.state.session.payments.as_ref(), app.state.data_age_thresholds);
     });
 
+    let best_block_height = app
+        .state
+        .session
+        .node_info
+        .as_ref()
+        .and_then(|info| info.data.current_best_block.as_ref())
+        .map(|b| b.height);
+
     ui.add_space(10.0);
 
-    if let Some(payments_response) = &app.state.payments {
+    if let Some(payments_response) = app.state.session.payments.as_ref().map(|p| &p.data) {
         use ldk_server_client::ldk_server_protos::types::payment_kind::Kind;
 
         // Filter to only onchain payments
@@ -279,6 +1236,7 @@ fn render_history_table(ui: &mut Ui, app: &mut LdkServerApp) {
                         ui.strong("Amount");
                         ui.strong("Direction");
                         ui.strong("Status");
+                        ui.strong("Confirmations");
                         ui.strong("Time");
                         ui.end_row();
 
@@ -286,19 +1244,15 @@ fn render_history_table(ui: &mut Ui, app: &mut LdkServerApp) {
                             // Payment ID
                             ui.horizontal(|ui| {
                                 ui.monospace(truncate_id(&payment.id, 5, 4));
-                                if ui.small_button("Copy").clicked() {
-                                    ui.output_mut(|o| o.copied_text = payment.id.clone());
-                                }
+                                copy_button(ui, "Copy", || payment.id.clone());
                             });
 
                             // TXID (from onchain kind)
                             if let Some(kind) = &payment.kind {
                                 if let Some(Kind::Onchain(onchain)) = &kind.kind {
                                     ui.horizontal(|ui| {
-                                        ui.monospace(truncate_id(&onchain.txid, 5, 4));
-                                        if ui.small_button("Copy").clicked() {
-                                            ui.output_mut(|o| o.copied_text = onchain.txid.clone());
-                                        }
+                                        txid_link(ui, network, &onchain.txid, 5, 4);
+                                        copy_button(ui, "Copy", || onchain.txid.clone());
                                     });
                                 } else {
                                     ui.label("-");
@@ -308,8 +1262,8 @@ fn render_history_table(ui: &mut Ui, app: &mut LdkServerApp) {
                             }
 
                             // Amount
-                            if let Some(amount) = payment.amount_msat {
-                                ui.label(format!("{} sats", format_sats(amount / 1000)));
+                            if let Some(amount_msat) = payment.amount_msat {
+                                ui.label(amount::format_amount(amount_msat / 1000, unit));
                             } else {
                                 ui.label("-");
                             }
@@ -330,6 +1284,9 @@ fn render_history_table(ui: &mut Ui, app: &mut LdkServerApp) {
                                 _ => ui.label("Unknown"),
                             };
 
+                            // Confirmations
+                            ui.label(format_confirmations(payment, best_block_height));
+
                             // Time
                             ui.label(format_timestamp(payment.latest_update_timestamp));
 
@@ -340,12 +1297,34 @@ fn render_history_table(ui: &mut Ui, app: &mut LdkServerApp) {
         }
     } else {
         ui.label("Loading transaction history...");
-        if app.state.tasks.payments.is_none() {
+        if !app.state.session.tasks.is_pending(TaskKind::Payments) {
             app.fetch_payments();
         }
     }
 }
 
+fn format_confirmations(
+    payment: &ldk_server_client::ldk_server_protos::types::Payment,
+    best_block_height: Option<u32>,
+) -> String {
+    use ldk_server_client::ldk_server_protos::types::confirmation_status::Status;
+    use ldk_server_client::ldk_server_protos::types::payment_kind::Kind;
+
+    let status = match payment.kind.as_ref().and_then(|k| k.kind.as_ref()) {
+        Some(Kind::Onchain(onchain)) => onchain.status.as_ref().and_then(|s| s.status.as_ref()),
+        _ => None,
+    };
+
+    match status {
+        Some(Status::Confirmed(confirmed)) => match best_block_height {
+            Some(height) => (height.saturating_sub(confirmed.height) + 1).to_string(),
+            None => format!("at height {}", confirmed.height),
+        },
+        Some(Status::Unconfirmed(_)) => "0".to_string(),
+        None => "-".to_string(),
+    }
+}
+
 fn format_timestamp(ts: u64) -> String {
     #[cfg(target_arch = "wasm32")]
     let now_secs = (js_sys::Date::now() / 1000.0) as u64;
@@ -0,0 +1,230 @@
+use egui::{ScrollArea, Ui};
+
+use crate::app::LdkServerApp;
+use crate::state::{ActiveTab, Cached, ConnectionStatus, DataAgeThresholds, TaskKind};
+use crate::ui::payments::format_payment_kind;
+use crate::ui::{
+    balance_change, connection, copy_button, data_age, fiat_value, format_age, format_msat,
+    format_sats, truncate_id, unix_timestamp_now,
+};
+
+/// Number of payments listed on the overview.
+const RECENT_PAYMENTS_SHOWN: usize = 3;
+
+const ONCHAIN_COLOR: egui::Color32 = egui::Color32::from_rgb(247, 147, 26);
+const LIGHTNING_COLOR: egui::Color32 = egui::Color32::from_rgb(138, 92, 246);
+
+pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
+    ui.heading("Overview");
+    ui.add_space(10.0);
+
+    // Being the landing page, it offers the connection settings until connected
+    if !matches!(app.state.session.connection_status, ConnectionStatus::Connected) {
+        connection::render_settings(ui, app);
+        return;
+    }
+
+    // Fill whatever is missing once per connection; the cards only read the cached responses
+    let session = &mut app.state.session;
+    let missing = session.node_info.is_none()
+        || session.balances.is_none()
+        || session.channels.is_none()
+        || session.payments.is_none();
+    if missing && session.overview_synced_generation != Some(session.connection_generation) {
+        session.overview_synced_generation = Some(session.connection_generation);
+        app.sync_all();
+    }
+    app.refresh_fiat_rate();
+
+    if app.state.session.tasks.is_pending(TaskKind::SyncAll) {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.label("Syncing…");
+        });
+    }
+
+    ScrollArea::vertical().show(ui, |ui| {
+        render_node_card(ui, app);
+        ui.add_space(10.0);
+        render_balance_card(ui, app);
+        ui.add_space(10.0);
+        render_channels_card(ui, app);
+        ui.add_space(10.0);
+        render_payments_card(ui, app);
+    });
+}
+
+/// Title of a card with the age of its data and a link to the full tab. Returns whether the link
+/// was clicked.
+fn card_header<T>(
+    ui: &mut Ui, title: &str, cached: Option<&Cached<T>>, thresholds: DataAgeThresholds,
+) -> bool {
+    ui.horizontal(|ui| {
+        ui.strong(title);
+        data_age(ui, cached, thresholds);
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.link("Details →").clicked()
+        })
+        .inner
+    })
+    .inner
+}
+
+fn render_node_card(ui: &mut Ui, app: &mut LdkServerApp) {
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        let cached = app.state.session.node_info.as_ref();
+        if card_header(ui, "Node", cached, app.state.data_age_thresholds) {
+            app.state.active_tab = ActiveTab::NodeInfo;
+        }
+        let Some(info) = app.state.session.node_info.as_ref().map(|i| &i.data) else {
+            ui.label("Not loaded yet.");
+            return;
+        };
+        let now = unix_timestamp_now();
+        egui::Grid::new("overview_node_grid").num_columns(2).spacing([10.0, 5.0]).show(ui, |ui| {
+            ui.label("Node ID:");
+            ui.horizontal(|ui| {
+                ui.monospace(truncate_id(&info.node_id, 12, 12));
+                copy_button(ui, "Copy", || info.node_id.clone());
+            });
+            ui.end_row();
+
+            if let Some(block) = &info.current_best_block {
+                ui.label("Best block:");
+                ui.monospace(block.height.to_string());
+                ui.end_row();
+            }
+
+            let syncs = [
+                ("Lightning sync:", info.latest_lightning_wallet_sync_timestamp),
+                ("On-chain sync:", info.latest_onchain_wallet_sync_timestamp),
+            ];
+            for (label, timestamp) in syncs {
+                ui.label(label);
+                match timestamp {
+                    Some(ts) => ui.label(format_age(now.saturating_sub(ts))),
+                    None => ui.label("Never"),
+                };
+                ui.end_row();
+            }
+        });
+    });
+}
+
+fn render_balance_card(ui: &mut Ui, app: &mut LdkServerApp) {
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        let cached = app.state.session.balances.as_ref();
+        if card_header(ui, "Balance", cached, app.state.data_age_thresholds) {
+            app.state.active_tab = ActiveTab::Balances;
+        }
+        let Some(balances) = app.state.session.balances.as_ref().map(|b| &b.data) else {
+            ui.label("Not loaded yet.");
+            return;
+        };
+        let onchain = balances.total_onchain_balance_sats;
+        let lightning = balances.total_lightning_balance_sats;
+        let rate = app.state.usable_fiat_rate();
+
+        ui.horizontal(|ui| {
+            ui.heading(format_sats(onchain.saturating_add(lightning)));
+            fiat_value(ui, rate, onchain.saturating_add(lightning));
+            balance_change(ui, app.state.session.balance_changes.map(|c| c.total()));
+        });
+        balance_bar(ui, onchain, lightning);
+        ui.horizontal(|ui| {
+            ui.colored_label(ONCHAIN_COLOR, "■");
+            ui.label(format!("On-chain {}", format_sats(onchain)));
+            ui.add_space(10.0);
+            ui.colored_label(LIGHTNING_COLOR, "■");
+            ui.label(format!("Lightning {}", format_sats(lightning)));
+        });
+    });
+}
+
+/// Horizontal bar split between the on-chain and lightning shares of the balance.
+fn balance_bar(ui: &mut Ui, onchain: u64, lightning: u64) {
+    let size = egui::vec2(ui.available_width(), 12.0);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter();
+    let total = onchain.saturating_add(lightning);
+    if total == 0 {
+        painter.rect_filled(rect, 3.0, ui.visuals().faint_bg_color);
+        return;
+    }
+    let split = rect.left() + rect.width() * (onchain as f64 / total as f64) as f32;
+    let (left, right) = rect.split_left_right_at_x(split);
+    painter.rect_filled(left, 0.0, ONCHAIN_COLOR);
+    painter.rect_filled(right, 0.0, LIGHTNING_COLOR);
+}
+
+fn render_channels_card(ui: &mut Ui, app: &mut LdkServerApp) {
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        let cached = app.state.session.channels.as_ref();
+        if card_header(ui, "Channels", cached, app.state.data_age_thresholds) {
+            app.state.active_tab = ActiveTab::Channels;
+        }
+        let Some(channels) = app.state.session.channels.as_ref().map(|c| &c.data.channels) else {
+            ui.label("Not loaded yet.");
+            return;
+        };
+        let usable = channels.iter().filter(|ch| ch.is_usable).count();
+        let outbound: u64 = channels.iter().map(|ch| ch.outbound_capacity_msat).sum();
+        let inbound: u64 = channels.iter().map(|ch| ch.inbound_capacity_msat).sum();
+
+        egui::Grid::new("overview_channels_grid").num_columns(2).spacing([10.0, 5.0]).show(
+            ui,
+            |ui| {
+                ui.label("Channels:");
+                ui.label(format!("{} ({} usable)", channels.len(), usable));
+                ui.end_row();
+
+                ui.label("Can send:");
+                ui.monospace(format_msat(outbound));
+                ui.end_row();
+
+                ui.label("Can receive:");
+                ui.monospace(format_msat(inbound));
+                ui.end_row();
+            },
+        );
+    });
+}
+
+fn render_payments_card(ui: &mut Ui, app: &mut LdkServerApp) {
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        let cached = app.state.session.payments.as_ref();
+        if card_header(ui, "Recent payments", cached, app.state.data_age_thresholds) {
+            app.state.active_tab = ActiveTab::Payments;
+        }
+        let Some(payments) = app.state.session.payments.as_ref().map(|p| &p.data.payments) else {
+            ui.label("Not loaded yet.");
+            return;
+        };
+        if payments.is_empty() {
+            ui.label("No payments yet.");
+            return;
+        }
+
+        let mut recent: Vec<_> = payments.iter().collect();
+        recent.sort_by_key(|p| std::cmp::Reverse(p.latest_update_timestamp));
+        let now = unix_timestamp_now();
+        egui::Grid::new("overview_payments_grid").num_columns(4).spacing([10.0, 5.0]).show(
+            ui,
+            |ui| {
+                for payment in recent.into_iter().take(RECENT_PAYMENTS_SHOWN) {
+                    // Direction: 0 = Inbound, 1 = Outbound
+                    ui.label(if payment.direction == 1 { "↑ Sent" } else { "↓ Received" });
+                    ui.monospace(payment.amount_msat.map_or("-".to_string(), format_msat));
+                    let kind = payment.kind.as_ref().map(format_payment_kind);
+                    ui.label(kind.unwrap_or_else(|| "Unknown".to_string()));
+                    ui.label(format_age(now.saturating_sub(payment.latest_update_timestamp)));
+                    ui.end_row();
+                }
+            },
+        );
+    });
+}
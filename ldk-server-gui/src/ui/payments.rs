@@ -3,120 +3,91 @@ use egui::{ScrollArea, Ui};
 use web_sys::js_sys;
 
 use crate::app::LdkServerApp;
-use crate::state::ConnectionStatus;
-use crate::ui::{format_msat, truncate_id};
+use crate::fiat::FiatRate;
+use crate::state::{ConnectionStatus, StatusMessage, TaskKind};
+use crate::ui::{
+    copy_button, data_age, fiat_value, format_msat, layout, refresh_button, truncate_id,
+};
+use ldk_server_client::ldk_server_protos::types::Payment;
 
 pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
     ui.heading("Payments");
     ui.add_space(10.0);
 
-    if !matches!(app.state.connection_status, ConnectionStatus::Connected) {
+    if !matches!(app.state.session.connection_status, ConnectionStatus::Connected) {
         ui.label("Connect to a server to view payments.");
         return;
     }
+    app.refresh_fiat_rate();
 
     ui.horizontal(|ui| {
-        if app.state.tasks.payments.is_some() {
+        // Refreshing while a further page loads cancels that page
+        if refresh_button(ui, &app.state.session.refresh, TaskKind::Payments) {
+            app.state.session.payments_page_token = None;
+            app.fetch_payments();
+        }
+        if app.state.session.tasks.is_pending(TaskKind::Payments) {
             ui.spinner();
             ui.label("Loading...");
-        } else {
-            if ui.button("Refresh").clicked() {
-                app.state.payments_page_token = None;
-                app.fetch_payments();
-            }
-            if app.state.payments_page_token.is_some() && ui.button("Load More").clicked() {
-                app.fetch_payments();
+            if ui.small_button("Cancel").clicked() {
+                app.state.session.tasks.abort(TaskKind::Payments);
+                app.state.status_message = Some(StatusMessage::success("Stopped loading payments"));
             }
+        } else if app.state.session.payments_page_token.is_some()
+            && ui.button("Load More").clicked()
+        {
+            app.fetch_payments();
         }
+        data_age(ui, app.state.session.payments.as_ref(), app.state.data_age_thresholds);
     });
 
     ui.add_space(10.0);
 
-    if let Some(payments_response) = &app.state.payments {
+    if let Some(payments_response) = app.state.session.payments.as_ref().map(|p| &p.data) {
         let payments = &payments_response.payments;
+        let rate = app.state.usable_fiat_rate();
         if payments.is_empty() {
             ui.label("No payments found.");
         } else {
             ui.label(format!("{} payment(s)", payments.len()));
             ui.add_space(5.0);
 
-            ScrollArea::both().max_height(500.0).show(ui, |ui| {
-                egui::Grid::new("payments_grid")
-                    .striped(true)
-                    .min_col_width(80.0)
-                    .show(ui, |ui| {
-                        // Header
-                        ui.strong("Payment ID");
-                        ui.strong("Type");
-                        ui.strong("Amount");
-                        ui.strong("Fee");
-                        ui.strong("Direction");
-                        ui.strong("Status");
-                        ui.strong("Timestamp");
-                        ui.end_row();
-
-                        for payment in payments {
-                            // Payment ID
-                            ui.horizontal(|ui| {
-                                ui.monospace(truncate_id(&payment.id, 5, 4));
-                                if ui.small_button("Copy").clicked() {
-                                    ui.output_mut(|o| o.copied_text = payment.id.clone());
+            if layout::is_compact(ui.ctx()) {
+                // One card per payment, each column on its own row
+                ScrollArea::vertical().max_height(500.0).show(ui, |ui| {
+                    for (index, payment) in payments.iter().enumerate() {
+                        ui.group(|ui| {
+                            let grid = egui::Grid::new(("payment_card", index)).num_columns(2);
+                            grid.show(ui, |ui| {
+                                for column in Column::ALL {
+                                    ui.strong(column.header());
+                                    render_cell(ui, column, payment, rate);
+                                    ui.end_row();
                                 }
                             });
-
-                            // Type
-                            let payment_type = payment
-                                .kind
-                                .as_ref()
-                                .map(|k| format_payment_kind(k))
-                                .unwrap_or_else(|| "Unknown".to_string());
-                            ui.label(payment_type);
-
-                            // Amount
-                            if let Some(amount) = payment.amount_msat {
-                                ui.label(format_msat(amount));
-                            } else {
-                                ui.label("-");
-                            }
-
-                            // Fee
-                            if let Some(fee) = payment.fee_paid_msat {
-                                ui.label(format_msat(fee));
-                            } else {
-                                ui.label("-");
+                        });
+                    }
+                });
+            } else {
+                ScrollArea::both().max_height(500.0).show(ui, |ui| {
+                    egui::Grid::new("payments_grid")
+                        .striped(true)
+                        .min_col_width(80.0)
+                        .show(ui, |ui| {
+                            for column in Column::ALL {
+                                ui.strong(column.header());
                             }
+                            ui.end_row();
 
-                            // Direction (0 = Inbound, 1 = Outbound)
-                            let direction = match payment.direction {
-                                0 => "Inbound",
-                                1 => "Outbound",
-                                _ => "Unknown",
-                            };
-                            ui.label(direction);
-
-                            // Status (0 = Pending, 1 = Succeeded, 2 = Failed)
-                            match payment.status {
-                                0 => {
-                                    ui.colored_label(egui::Color32::YELLOW, "Pending");
-                                }
-                                1 => {
-                                    ui.colored_label(egui::Color32::GREEN, "Succeeded");
-                                }
-                                2 => {
-                                    ui.colored_label(egui::Color32::RED, "Failed");
+                            for payment in payments {
+                                for column in Column::ALL {
+                                    render_cell(ui, column, payment, rate);
                                 }
-                                _ => {
-                                    ui.label("Unknown");
-                                }
-                            };
-
-                            // Timestamp
-                            ui.label(format_timestamp(payment.latest_update_timestamp));
-
-                            ui.end_row();
-                        }
-                    });
-            });
+                                ui.end_row();
+                            }
+                        });
+                });
+            }
 
             if payments_response.next_page_token.is_some() {
                 ui.add_space(5.0);
@@ -128,7 +99,105 @@ pub fn render(ui: &mut Ui, app: &mut LdkServerApp) {
     }
 }
 
-fn format_payment_kind(kind: &ldk_server_client::ldk_server_protos::types::PaymentKind) -> String {
+/// Column of the payments table.
+#[derive(Clone, Copy)]
+enum Column {
+    Id,
+    Type,
+    Amount,
+    Fee,
+    Direction,
+    Status,
+    Timestamp,
+}
+
+impl Column {
+    const ALL: [Column; 7] = [
+        Column::Id,
+        Column::Type,
+        Column::Amount,
+        Column::Fee,
+        Column::Direction,
+        Column::Status,
+        Column::Timestamp,
+    ];
+
+    fn header(self) -> &'static str {
+        match self {
+            Column::Id => "Payment ID",
+            Column::Type => "Type",
+            Column::Amount => "Amount",
+            Column::Fee => "Fee",
+            Column::Direction => "Direction",
+            Column::Status => "Status",
+            Column::Timestamp => "Timestamp",
+        }
+    }
+}
+
+fn render_cell(ui: &mut Ui, column: Column, payment: &Payment, rate: Option<&FiatRate>) {
+    match column {
+        Column::Id => {
+            ui.horizontal(|ui| {
+                ui.monospace(truncate_id(&payment.id, 5, 4));
+                copy_button(ui, "Copy", || payment.id.clone());
+            });
+        }
+        Column::Type => {
+            let payment_type = payment
+                .kind
+                .as_ref()
+                .map(|k| format_payment_kind(k))
+                .unwrap_or_else(|| "Unknown".to_string());
+            ui.label(payment_type);
+        }
+        Column::Amount | Column::Fee => {
+            let msat = match column {
+                Column::Amount => payment.amount_msat,
+                _ => payment.fee_paid_msat,
+            };
+            if let Some(msat) = msat {
+                ui.vertical(|ui| {
+                    ui.label(format_msat(msat));
+                    fiat_value(ui, rate, msat / 1000);
+                });
+            } else {
+                ui.label("-");
+            }
+        }
+        // 0 = Inbound, 1 = Outbound
+        Column::Direction => {
+            let direction = match payment.direction {
+                0 => "Inbound",
+                1 => "Outbound",
+                _ => "Unknown",
+            };
+            ui.label(direction);
+        }
+        // 0 = Pending, 1 = Succeeded, 2 = Failed
+        Column::Status => match payment.status {
+            0 => {
+                ui.colored_label(egui::Color32::YELLOW, "Pending");
+            }
+            1 => {
+                ui.colored_label(egui::Color32::GREEN, "Succeeded");
+            }
+            2 => {
+                ui.colored_label(egui::Color32::RED, "Failed");
+            }
+            _ => {
+                ui.label("Unknown");
+            }
+        },
+        Column::Timestamp => {
+            ui.label(format_timestamp(payment.latest_update_timestamp));
+        }
+    }
+}
+
+pub fn format_payment_kind(
+    kind: &ldk_server_client::ldk_server_protos::types::PaymentKind,
+) -> String {
     use ldk_server_client::ldk_server_protos::types::payment_kind::Kind;
 
     match &kind.kind {
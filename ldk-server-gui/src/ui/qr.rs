@@ -0,0 +1,64 @@
+//! QR code rendering shared by the receive views.
+
+use std::collections::HashMap;
+
+use egui::{Color32, ColorImage, TextureHandle, TextureOptions, Ui};
+use qrcode::{Color, QrCode};
+
+/// Number of light modules around the code, as required by the QR spec.
+const QUIET_ZONE: usize = 4;
+
+/// Cached QR textures keyed by widget id, regenerated only when the encoded data changes.
+#[derive(Default)]
+pub struct QrTextures {
+    textures: HashMap<&'static str, (String, TextureHandle)>,
+}
+
+impl QrTextures {
+    fn get_or_create(
+        &mut self,
+        ctx: &egui::Context,
+        id: &'static str,
+        data: &str,
+    ) -> Option<&TextureHandle> {
+        let is_current = self.textures.get(id).is_some_and(|(cached, _)| cached == data);
+        if !is_current {
+            let Some(image) = qr_image(data) else {
+                self.textures.remove(id);
+                return None;
+            };
+            let texture = ctx.load_texture(format!("qr_{}", id), image, TextureOptions::NEAREST);
+            self.textures.insert(id, (data.to_string(), texture));
+        }
+        self.textures.get(id).map(|(_, texture)| texture)
+    }
+}
+
+/// Render `data` as a QR code of the given size. The texture is cached under `id`.
+pub fn show_qr(ui: &mut Ui, textures: &mut QrTextures, id: &'static str, data: &str, size: f32) {
+    match textures.get_or_create(ui.ctx(), id, data) {
+        Some(texture) => {
+            ui.add(egui::Image::new(texture).fit_to_exact_size(egui::vec2(size, size)));
+        }
+        None => {
+            ui.colored_label(Color32::GRAY, "Data too long to encode as QR code");
+        }
+    }
+}
+
+/// Build a one-pixel-per-module image of the QR code, including the quiet zone.
+fn qr_image(data: &str) -> Option<ColorImage> {
+    let code = QrCode::new(data.as_bytes()).ok()?;
+    let width = code.width();
+    let colors = code.to_colors();
+    let size = width + 2 * QUIET_ZONE;
+
+    let mut image = ColorImage::new([size, size], Color32::WHITE);
+    for (i, color) in colors.iter().enumerate() {
+        if *color == Color::Dark {
+            let (x, y) = (i % width + QUIET_ZONE, i / width + QUIET_ZONE);
+            image.pixels[y * size + x] = Color32::BLACK;
+        }
+    }
+    Some(image)
+}